@@ -9,8 +9,12 @@
 use crate::data::table::VegaFusionTable;
 use crate::error::Result;
 use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 
+/// A named in-memory dataset that specs may reference with a
+/// `vegafusion+dataset://{name}` url, avoiding the need to write temp files
+/// or inline the data as JSON values
 #[derive(Clone, Debug)]
 pub enum VegaFusionDataset {
     Table { table: VegaFusionTable, hash: u64 },
@@ -31,4 +35,22 @@ impl VegaFusionDataset {
         let table = VegaFusionTable::from_ipc_bytes(ipc_bytes)?;
         Ok(Self::Table { table, hash })
     }
+
+    pub fn from_table(table: VegaFusionTable) -> Result<Self> {
+        // Hash the IPC representation of the table so that identical tables produce
+        // identical fingerprints regardless of how they were constructed
+        let mut hasher = deterministic_hash::DeterministicHasher::new(DefaultHasher::new());
+        table.to_ipc_bytes()?.hash(&mut hasher);
+        let hash = hasher.finish();
+        Ok(Self::Table { table, hash })
+    }
+
+    /// Convert a collection of named in-memory tables into inline datasets suitable
+    /// for passing to the pre-transform methods
+    pub fn from_tables(tables: HashMap<String, VegaFusionTable>) -> Result<HashMap<String, Self>> {
+        tables
+            .into_iter()
+            .map(|(name, table)| Ok((name, Self::from_table(table)?)))
+            .collect()
+    }
 }
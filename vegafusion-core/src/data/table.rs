@@ -26,9 +26,52 @@ use super::scalar::ScalarValue;
 use crate::arrow::array::ArrayRef;
 use crate::data::json_writer::record_batches_to_json_rows;
 
-use arrow::array::StructArray;
+use arrow::array::{Int64Array, StructArray, UInt64Array};
+use arrow::compute::cast;
+use arrow::datatypes::{Field, Schema};
 use arrow::json::reader::DecoderOptions;
 use serde_json::{json, Value};
+use std::sync::RwLock;
+
+/// Largest integer that JavaScript numbers can represent exactly (2^53 - 1)
+pub const MAX_SAFE_INTEGER: i64 = 9007199254740991;
+
+/// How numeric columns that can't be represented exactly by JavaScript numbers
+/// (int64 values beyond 2^53 and decimal columns) are surfaced in JSON output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnsafeNumberHandling {
+    /// Serialize values as exact JSON numbers. JavaScript clients will silently
+    /// round values beyond 2^53 when parsing (the default)
+    Passthrough,
+
+    /// Convert affected columns to strings so no precision is lost
+    Stringify,
+
+    /// Cast affected columns to Float64 before serialization, losing precision
+    /// deterministically on the server
+    Cast,
+
+    /// Return an error when a column contains an unrepresentable value
+    Error,
+}
+
+lazy_static! {
+    static ref UNSAFE_NUMBER_HANDLING: RwLock<UnsafeNumberHandling> =
+        RwLock::new(UnsafeNumberHandling::Passthrough);
+}
+
+/// Replace the process-wide handling of numeric values that JavaScript numbers
+/// can't represent exactly
+pub fn set_unsafe_number_handling(handling: UnsafeNumberHandling) {
+    let mut guard = UNSAFE_NUMBER_HANDLING.write().unwrap();
+    *guard = handling;
+}
+
+/// Get the current process-wide handling of numeric values that JavaScript
+/// numbers can't represent exactly
+pub fn get_unsafe_number_handling() -> UnsafeNumberHandling {
+    *UNSAFE_NUMBER_HANDLING.read().unwrap()
+}
 
 #[derive(Clone, Debug)]
 pub struct VegaFusionTable {
@@ -127,7 +170,120 @@ impl VegaFusionTable {
         serde_json::Value::Array(rows)
     }
 
+    /// Like to_json, but applies the configured UnsafeNumberHandling to columns
+    /// whose values JavaScript numbers can't represent exactly
+    pub fn try_to_json(&self) -> Result<serde_json::Value> {
+        let handling = get_unsafe_number_handling();
+        if handling == UnsafeNumberHandling::Passthrough {
+            return Ok(self.to_json());
+        }
+
+        // Find columns containing values beyond JavaScript's safe integer range,
+        // along with decimal columns (which have no exact JSON representation)
+        let mut affected: Vec<usize> = Vec::new();
+        for (index, field) in self.schema.fields().iter().enumerate() {
+            let unsafe_column = match field.data_type() {
+                DataType::Int64 => self.batches.iter().any(|batch| {
+                    let array = batch
+                        .column(index)
+                        .as_any()
+                        .downcast_ref::<Int64Array>()
+                        .unwrap();
+                    // unsigned_abs avoids overflow on i64::MIN
+                    array
+                        .iter()
+                        .flatten()
+                        .any(|v| v.unsigned_abs() > MAX_SAFE_INTEGER as u64)
+                }),
+                DataType::UInt64 => self.batches.iter().any(|batch| {
+                    let array = batch
+                        .column(index)
+                        .as_any()
+                        .downcast_ref::<UInt64Array>()
+                        .unwrap();
+                    array.iter().flatten().any(|v| v > MAX_SAFE_INTEGER as u64)
+                }),
+                DataType::Decimal(_, _) => true,
+                _ => false,
+            };
+            if unsafe_column {
+                affected.push(index);
+            }
+        }
+
+        if affected.is_empty() {
+            return Ok(self.to_json());
+        }
+
+        if handling == UnsafeNumberHandling::Error {
+            let names: Vec<_> = affected
+                .iter()
+                .map(|index| self.schema.field(*index).name().clone())
+                .collect();
+            return Err(VegaFusionError::internal(&format!(
+                "Columns contain numeric values that JavaScript numbers cannot represent \
+                 exactly: {}",
+                names.join(", ")
+            )));
+        }
+
+        let new_dtype = if handling == UnsafeNumberHandling::Stringify {
+            DataType::Utf8
+        } else {
+            DataType::Float64
+        };
+
+        let new_fields: Vec<Field> = self
+            .schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                if affected.contains(&index) {
+                    Field::new(field.name(), new_dtype.clone(), true)
+                } else {
+                    field.clone()
+                }
+            })
+            .collect();
+        let new_schema = SchemaRef::new(Schema::new(new_fields));
+
+        let new_batches = self
+            .batches
+            .iter()
+            .map(|batch| {
+                let columns = batch
+                    .columns()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, column)| {
+                        if affected.contains(&index) {
+                            Ok(cast(column, &new_dtype)?)
+                        } else {
+                            Ok(column.clone())
+                        }
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(RecordBatch::try_new(new_schema.clone(), columns)?)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self::try_new(new_schema, new_batches)?.to_json())
+    }
+
     pub fn from_json(value: &serde_json::Value, batch_size: usize) -> Result<Self> {
+        Self::from_json_opts(value, batch_size, 1024, None)
+    }
+
+    /// Like from_json, but with control over how many rows are examined during
+    /// schema inference, and support for an explicit schema that skips inference
+    /// entirely
+    pub fn from_json_opts(
+        value: &serde_json::Value,
+        batch_size: usize,
+        schema_infer_max_records: usize,
+        schema: Option<SchemaRef>,
+    ) -> Result<Self> {
         if let serde_json::Value::Array(values) = value {
             // Handle special case where array elements are non-object scalars
             let mut values = Cow::Borrowed(values);
@@ -149,9 +305,15 @@ impl VegaFusionTable {
                 }
             }
 
-            let schema_result = json::reader::infer_json_schema_from_iterator(
-                values.iter().take(1024).map(|v| Ok(v.clone())),
-            );
+            let schema_result = match schema {
+                Some(schema) => Ok(schema.as_ref().clone()),
+                None => json::reader::infer_json_schema_from_iterator(
+                    values
+                        .iter()
+                        .take(schema_infer_max_records)
+                        .map(|v| Ok(v.clone())),
+                ),
+            };
 
             match schema_result {
                 Err(_) => {
@@ -235,3 +397,67 @@ impl Hash for VegaFusionTable {
         self.to_ipc_bytes().unwrap().hash(state)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{ArrayRef, Int64Array};
+    use std::sync::Arc;
+
+    fn unsafe_int_table() -> VegaFusionTable {
+        let schema = SchemaRef::new(Schema::new(vec![
+            Field::new("big", DataType::Int64, true),
+            Field::new("small", DataType::Int64, true),
+        ]));
+        let big = Int64Array::from(vec![Some(1), Some(i64::MIN), None]);
+        let small = Int64Array::from(vec![Some(1), Some(2), Some(3)]);
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(big) as ArrayRef, Arc::new(small) as ArrayRef],
+        )
+        .unwrap();
+        VegaFusionTable::try_new(schema, vec![batch]).unwrap()
+    }
+
+    /// The handling setting is process-wide, so all modes are exercised from a
+    /// single test to avoid races between parallel tests
+    #[test]
+    fn test_try_to_json_unsafe_number_handling() {
+        let table = unsafe_int_table();
+
+        // Passthrough serializes the exact value. The i64::MIN value must not
+        // overflow the safe-integer check in any mode
+        set_unsafe_number_handling(UnsafeNumberHandling::Passthrough);
+        let rows = table.try_to_json().unwrap();
+        assert_eq!(rows[1]["big"], json!(i64::MIN));
+
+        // Stringify converts only the affected column to strings
+        set_unsafe_number_handling(UnsafeNumberHandling::Stringify);
+        let rows = table.try_to_json().unwrap();
+        assert_eq!(rows[1]["big"], json!(i64::MIN.to_string()));
+        assert_eq!(rows[1]["small"], json!(2));
+
+        // Cast converts the affected column to Float64
+        set_unsafe_number_handling(UnsafeNumberHandling::Cast);
+        let rows = table.try_to_json().unwrap();
+        assert_eq!(rows[1]["big"], json!(i64::MIN as f64));
+        assert_eq!(rows[1]["small"], json!(2));
+
+        // Error reports the affected column by name
+        set_unsafe_number_handling(UnsafeNumberHandling::Error);
+        let err = table.try_to_json().unwrap_err();
+        assert!(err.to_string().contains("big"));
+
+        // Columns within the safe range are not affected by Error mode
+        set_unsafe_number_handling(UnsafeNumberHandling::Error);
+        let safe_table = VegaFusionTable::from_json(
+            &json!([{"small": 1}, {"small": MAX_SAFE_INTEGER}]),
+            1024,
+        )
+        .unwrap();
+        assert!(safe_table.try_to_json().is_ok());
+
+        // Restore the default for any other tests in this process
+        set_unsafe_number_handling(UnsafeNumberHandling::Passthrough);
+    }
+}
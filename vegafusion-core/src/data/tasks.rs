@@ -95,4 +95,10 @@ impl TaskDependencies for SignalTask {
         let expr = self.expr.as_ref().unwrap();
         expr.input_vars()
     }
+
+    fn output_vars(&self) -> Vec<Variable> {
+        // Datasets mutated through the modify() expression function
+        let expr = self.expr.as_ref().unwrap();
+        expr.update_vars()
+    }
 }
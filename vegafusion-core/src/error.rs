@@ -122,6 +122,41 @@ impl VegaFusionError {
         Self::ParseError(message.into(), Default::default())
     }
 
+    /// Create a parse error annotated with the byte-offset span of the offending tokens
+    /// and a caret-annotated snippet of the full expression. e.g.
+    ///
+    /// ```text
+    /// Unexpected token '+' at position 11
+    ///   datum.one >+ 2
+    ///              ^
+    /// ```
+    pub fn parse_with_span<S: Into<String>>(
+        message: S,
+        full_expr: &str,
+        start: usize,
+        end: usize,
+    ) -> Self {
+        // Compute display widths in characters so the caret line stays aligned for
+        // expressions containing multi-byte characters
+        let prefix_width = full_expr
+            .get(..start)
+            .map(|prefix| prefix.chars().count())
+            .unwrap_or(start);
+        let span_width = full_expr
+            .get(start..end)
+            .map(|span| span.chars().count().max(1))
+            .unwrap_or(1);
+        let annotated = format!(
+            "{} at position {}\n  {}\n  {}{}",
+            message.into(),
+            start,
+            full_expr,
+            " ".repeat(prefix_width),
+            "^".repeat(span_width),
+        );
+        Self::ParseError(annotated, Default::default())
+    }
+
     pub fn compilation<S: Into<String>>(message: S) -> Self {
         Self::CompilationError(message.into(), Default::default())
     }
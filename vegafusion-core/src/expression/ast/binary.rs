@@ -27,6 +27,12 @@ impl Display for BinaryOperator {
             BinaryOperator::LessThan => write!(f, "<"),
             BinaryOperator::GreaterThanEqual => write!(f, ">="),
             BinaryOperator::LessThanEqual => write!(f, "<="),
+            BinaryOperator::BitwiseAnd => write!(f, "&"),
+            BinaryOperator::BitwiseOr => write!(f, "|"),
+            BinaryOperator::BitwiseXor => write!(f, "^"),
+            BinaryOperator::LeftShift => write!(f, "<<"),
+            BinaryOperator::RightShift => write!(f, ">>"),
+            BinaryOperator::UnsignedRightShift => write!(f, ">>>"),
         }
     }
 }
@@ -48,6 +54,12 @@ impl BinaryOperator {
             | BinaryOperator::StrictEquals
             | BinaryOperator::NotEquals
             | BinaryOperator::NotStrictEquals => (11.0, 11.5),
+            BinaryOperator::LeftShift
+            | BinaryOperator::RightShift
+            | BinaryOperator::UnsignedRightShift => (13.0, 13.5),
+            BinaryOperator::BitwiseAnd => (10.0, 10.5),
+            BinaryOperator::BitwiseXor => (9.0, 9.5),
+            BinaryOperator::BitwiseOr => (8.0, 8.5),
         }
     }
 }
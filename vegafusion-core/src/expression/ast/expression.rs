@@ -159,9 +159,11 @@ impl Expression {
             Expr::Member(node) => {
                 node.object.as_ref().unwrap().walk(visitor);
                 let prop_expr = node.property.as_ref().unwrap().expr.as_ref().unwrap();
-                if let Expr::Identifier(identifier) = prop_expr {
+                if let (false, Expr::Identifier(identifier)) = (node.computed, prop_expr) {
                     visitor.visit_static_member_identifier(identifier);
                 } else {
+                    // For computed member access (e.g. datum[field_signal]) the property is a
+                    // general expression and identifiers within it are true input variables
                     node.property.as_ref().unwrap().walk(visitor);
                 }
                 visitor.visit_member(node);
@@ -224,7 +226,7 @@ impl Expression {
             Expr::Member(node) => {
                 node.object.as_mut().unwrap().walk_mut(visitor);
                 let prop_expr = node.property.as_mut().unwrap().expr.as_mut().unwrap();
-                if let Expr::Identifier(identifier) = prop_expr {
+                if let (false, Expr::Identifier(identifier)) = (node.computed, prop_expr) {
                     visitor.visit_static_member_identifier(identifier);
                 } else {
                     node.property.as_mut().unwrap().walk_mut(visitor);
@@ -348,6 +350,6 @@ impl GetDatasetsColumnUsage for Expression {
             vl_selection_fields,
         );
         self.walk(&mut visitor);
-        visitor.dataset_column_usage
+        visitor.column_usage()
     }
 }
@@ -17,6 +17,7 @@ impl Display for UnaryOperator {
             UnaryOperator::Pos => write!(f, "+"),
             UnaryOperator::Neg => write!(f, "-"),
             UnaryOperator::Not => write!(f, "!"),
+            UnaryOperator::BitwiseNot => write!(f, "~"),
         }
     }
 }
@@ -25,7 +26,10 @@ impl UnaryOperator {
     pub fn unary_binding_power(&self) -> f64 {
         // See https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Operators/Operator_Precedence
         match self {
-            UnaryOperator::Neg | UnaryOperator::Pos | UnaryOperator::Not => 17.0,
+            UnaryOperator::Neg
+            | UnaryOperator::Pos
+            | UnaryOperator::Not
+            | UnaryOperator::BitwiseNot => 17.0,
         }
     }
 }
@@ -43,6 +43,13 @@ pub enum Token {
     LessThanEquals,
     LogicalAnd,
     LogicalOr,
+    Ampersand,
+    Pipe,
+    Caret,
+    Tilde,
+    DoubleLessThan,
+    DoubleGreaterThan,
+    TripleGreaterThan,
 }
 
 impl fmt::Display for Token {
@@ -79,6 +86,13 @@ impl fmt::Display for Token {
             Token::Exclamation => write!(f, "!"),
             Token::ExclamationEquals => write!(f, "!="),
             Token::ExclamationDoubleEquals => write!(f, "!=="),
+            Token::Ampersand => write!(f, "&"),
+            Token::Pipe => write!(f, "|"),
+            Token::Caret => write!(f, "^"),
+            Token::Tilde => write!(f, "~"),
+            Token::DoubleLessThan => write!(f, "<<"),
+            Token::DoubleGreaterThan => write!(f, ">>"),
+            Token::TripleGreaterThan => write!(f, ">>>"),
         }
     }
 }
@@ -198,6 +212,8 @@ pub fn tokenize_single_token(data: &str) -> Result<(Token, usize)> {
         ',' => (Token::Comma, 1),
         '?' => (Token::Question, 1),
         ':' => (Token::Colon, 1),
+        '^' => tokenize_single_char_operator(data, '^', Token::Caret)?,
+        '~' => (Token::Tilde, 1),
         '|' => tokenize_logical_or(data)?,
         '&' => tokenize_logical_and(data)?,
         c @ '"' | c @ '\'' => tokenize_string(data, c)?,
@@ -275,7 +291,7 @@ fn tokenize_single_char_operator(data: &str, ch: char, token: Token) -> Result<(
 fn tokenize_logical_or(data: &str) -> Result<(Token, usize)> {
     let taken = take_while(data, |c| Ok(c == '|'))?;
     let token = match taken {
-        "|" => return Err(VegaFusionError::parse("Bitwise OR operator not supported")),
+        "|" => Token::Pipe,
         "||" => Token::LogicalOr,
         _ => {
             return Err(VegaFusionError::parse(&format!(
@@ -291,7 +307,7 @@ fn tokenize_logical_or(data: &str) -> Result<(Token, usize)> {
 fn tokenize_logical_and(data: &str) -> Result<(Token, usize)> {
     let taken = take_while(data, |c| Ok(c == '&'))?;
     let token = match taken {
-        "&" => return Err(VegaFusionError::parse("Bitwise AND operator not supported")),
+        "&" => Token::Ampersand,
         "&&" => Token::LogicalAnd,
         _ => {
             return Err(VegaFusionError::parse(&format!(
@@ -328,21 +344,9 @@ fn tokenize_comparison_operators(data: &str) -> Result<(Token, usize)> {
         }
         "==" => Token::DoubleEquals,
         "===" => Token::TripleEquals,
-        "<<" => {
-            return Err(VegaFusionError::parse(
-                "Bitwise left shift operator not supported",
-            ))
-        }
-        ">>" => {
-            return Err(VegaFusionError::parse(
-                "Bitwise signed right shift operator not supported",
-            ))
-        }
-        ">>>" => {
-            return Err(VegaFusionError::parse(
-                "Bitwise right shift operator not supported",
-            ))
-        }
+        "<<" => Token::DoubleLessThan,
+        ">>" => Token::DoubleGreaterThan,
+        ">>>" => Token::TripleGreaterThan,
         "!" => Token::Exclamation,
         "!=" => Token::ExclamationEquals,
         "!==" => Token::ExclamationDoubleEquals,
@@ -12,5 +12,6 @@ pub mod escape;
 pub mod lexer;
 pub mod ops;
 pub mod parser;
+pub mod sql;
 pub mod supported;
 pub mod visitors;
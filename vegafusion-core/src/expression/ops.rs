@@ -16,6 +16,7 @@ pub fn unary_op_from_token(tok: &Token) -> Result<UnaryOperator> {
         Token::Plus => UnaryOperator::Pos,
         Token::Minus => UnaryOperator::Neg,
         Token::Exclamation => UnaryOperator::Not,
+        Token::Tilde => UnaryOperator::BitwiseNot,
         t => {
             return Err(VegaFusionError::parse(&format!(
                 "Token '{}' is not a valid prefix operator",
@@ -40,6 +41,12 @@ pub fn binary_op_from_token(value: &Token) -> Result<BinaryOperator> {
         Token::GreaterThanEquals => BinaryOperator::GreaterThanEqual,
         Token::LessThan => BinaryOperator::LessThan,
         Token::LessThanEquals => BinaryOperator::LessThanEqual,
+        Token::Ampersand => BinaryOperator::BitwiseAnd,
+        Token::Pipe => BinaryOperator::BitwiseOr,
+        Token::Caret => BinaryOperator::BitwiseXor,
+        Token::DoubleLessThan => BinaryOperator::LeftShift,
+        Token::DoubleGreaterThan => BinaryOperator::RightShift,
+        Token::TripleGreaterThan => BinaryOperator::UnsignedRightShift,
         t => {
             return Err(VegaFusionError::parse(&format!(
                 "Token '{}' is not a valid binary operator",
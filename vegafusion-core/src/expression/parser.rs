@@ -21,11 +21,13 @@ pub fn parse(expr: &str) -> Result<Expression> {
     let mut tokens = tokenize(expr)?;
     let result = perform_parse(&mut tokens, 0.0, expr)?;
     if !tokens.is_empty() {
-        let (token, start, _) = &tokens[0];
-        return Err(VegaFusionError::parse(&format!(
-            "Unexpected token {} at position {} in expression: {}",
-            token, start, expr
-        )));
+        let (token, start, end) = &tokens[0];
+        return Err(VegaFusionError::parse_with_span(
+            format!("Unexpected token '{}'", token),
+            expr,
+            *start,
+            *end,
+        ));
     }
 
     Ok(result)
@@ -37,7 +39,12 @@ fn perform_parse(
     full_expr: &str,
 ) -> Result<Expression> {
     if tokens.is_empty() {
-        return Err(VegaFusionError::parse("Unexpected end of expression"));
+        return Err(VegaFusionError::parse_with_span(
+            "Unexpected end of expression",
+            full_expr,
+            full_expr.len(),
+            full_expr.len(),
+        ));
     }
 
     // Pop leading token
@@ -60,10 +67,12 @@ fn perform_parse(
         // Object literal expression
         parse_object(tokens, start, full_expr)
     } else {
-        Err(VegaFusionError::parse(&format!(
-            "Unexpected token: {}",
-            lhs_token
-        )))
+        Err(VegaFusionError::parse_with_span(
+            format!("Unexpected token '{}'", lhs_token),
+            full_expr,
+            start,
+            end,
+        ))
     };
 
     let mut lhs = lhs_result.with_context(|| {
@@ -134,10 +143,13 @@ fn perform_parse(
                 break;
             }
         } else {
-            Err(VegaFusionError::parse(&format!(
-                "Unexpected token '{}'",
-                token
-            )))
+            let (token, start, end) = &tokens[0];
+            Err(VegaFusionError::parse_with_span(
+                format!("Unexpected token '{}'", token),
+                full_expr,
+                *start,
+                *end,
+            ))
         };
 
         lhs = expr_result.with_context(|| {
@@ -550,12 +562,31 @@ mod test_parse {
         assert_eq!(format!("{}", node), "\"hello\"");
     }
 
+    #[test]
+    fn test_parse_error_span() {
+        let err = parse("datum.one >* 2").unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("Unexpected token '*' at position 11"));
+        assert!(msg.contains("  datum.one >* 2"));
+        assert!(msg.contains("             ^"));
+    }
+
     #[test]
     fn test_parse_binary() {
         let node = parse("23.50 + foo * 87").unwrap();
         assert_eq!(node.to_string(), "23.5 + foo * 87");
     }
 
+    #[test]
+    fn test_parse_bitwise() {
+        // Shifts bind tighter than &, which binds tighter than ^, which binds tighter than |
+        let node = parse("a | b ^ c & d << 2").unwrap();
+        assert_eq!(node.to_string(), "a | b ^ c & d << 2");
+
+        let node = parse("(a | b) ^ ~c >>> 1").unwrap();
+        assert_eq!(node.to_string(), "(a | b) ^ ~c >>> 1");
+    }
+
     #[test]
     fn test_parse_logical() {
         let node = parse("false || (foo && bar)").unwrap();
@@ -0,0 +1,338 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+/*!
+Render a parsed Vega expression to a SQL scalar expression string.
+
+This is an alternate expression backend to the DataFusion compiler in
+vegafusion-rt-datafusion, intended as a building block for pushing transforms into
+external databases. Only the subset of the expression language with a direct SQL
+equivalent is supported; expressions outside that subset produce a compilation error
+so callers can fall back to another evaluation strategy.
+ */
+use crate::error::{Result, VegaFusionError};
+use crate::proto::gen::expression::expression::Expr;
+use crate::proto::gen::expression::literal::Value;
+use crate::proto::gen::expression::{
+    BinaryExpression, BinaryOperator, CallExpression, ConditionalExpression, Expression,
+    Identifier, Literal, LogicalExpression, LogicalOperator, MemberExpression, UnaryExpression,
+    UnaryOperator,
+};
+
+/// SQL dialect to target when rendering an expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlDialect {
+    /// ANSI SQL with double-quoted identifiers
+    Generic,
+    Postgres,
+    Sqlite,
+    /// MySQL-style backtick-quoted identifiers
+    MySql,
+}
+
+impl Default for SqlDialect {
+    fn default() -> Self {
+        Self::Generic
+    }
+}
+
+impl SqlDialect {
+    /// Quote a column identifier using the dialect's quoting style
+    pub fn quote_identifier(&self, name: &str) -> String {
+        match self {
+            SqlDialect::MySql => format!("`{}`", name.replace('`', "``")),
+            _ => format!("\"{}\"", name.replace('"', "\"\"")),
+        }
+    }
+
+    /// Quote a string literal, escaping embedded quotes
+    pub fn quote_string(&self, value: &str) -> String {
+        format!("'{}'", value.replace('\'', "''"))
+    }
+}
+
+/// Render a parsed Vega expression to a SQL scalar expression string targeting
+/// the provided dialect. Column references take the form `datum.col` or
+/// `datum['col']` in the input expression.
+pub fn expression_to_sql(expression: &Expression, dialect: &SqlDialect) -> Result<String> {
+    match expression.expr() {
+        Expr::Literal(node) => literal_to_sql(node, dialect),
+        Expr::Identifier(node) => identifier_to_sql(node),
+        Expr::Binary(node) => binary_to_sql(node, dialect),
+        Expr::Logical(node) => logical_to_sql(node, dialect),
+        Expr::Unary(node) => unary_to_sql(node, dialect),
+        Expr::Conditional(node) => conditional_to_sql(node, dialect),
+        Expr::Member(node) => member_to_sql(node, dialect),
+        Expr::Call(node) => call_to_sql(node, dialect),
+        _ => Err(VegaFusionError::compilation(&format!(
+            "Expression not supported in SQL conversion: {}",
+            expression
+        ))),
+    }
+}
+
+fn literal_to_sql(node: &Literal, dialect: &SqlDialect) -> Result<String> {
+    Ok(match node.value() {
+        Value::Number(value) => {
+            if value.fract() == 0.0 && value.abs() < (i64::MAX as f64) {
+                format!("{}", *value as i64)
+            } else {
+                format!("{}", value)
+            }
+        }
+        Value::String(value) => dialect.quote_string(value),
+        Value::Boolean(value) => {
+            if *value {
+                "TRUE".to_string()
+            } else {
+                "FALSE".to_string()
+            }
+        }
+        Value::Null(_) => "NULL".to_string(),
+    })
+}
+
+fn identifier_to_sql(node: &Identifier) -> Result<String> {
+    // Expression constants with SQL equivalents. Signals and other variables are not
+    // available in the SQL context
+    match node.name.as_str() {
+        "PI" => Ok(format!("{}", std::f64::consts::PI)),
+        "E" => Ok(format!("{}", std::f64::consts::E)),
+        name => Err(VegaFusionError::compilation(&format!(
+            "Variable not supported in SQL conversion: {}",
+            name
+        ))),
+    }
+}
+
+fn is_null_literal(expression: &Expression) -> bool {
+    matches!(
+        expression.as_literal(),
+        Ok(Literal {
+            value: Some(Value::Null(_)),
+            ..
+        })
+    )
+}
+
+fn binary_to_sql(node: &BinaryExpression, dialect: &SqlDialect) -> Result<String> {
+    // Equality comparison with null maps to an IS [NOT] NULL check
+    let operator = node.to_operator();
+    if matches!(
+        operator,
+        BinaryOperator::Equals
+            | BinaryOperator::StrictEquals
+            | BinaryOperator::NotEquals
+            | BinaryOperator::NotStrictEquals
+    ) {
+        let non_null_side = if is_null_literal(node.right()) {
+            Some(node.left())
+        } else if is_null_literal(node.left()) {
+            Some(node.right())
+        } else {
+            None
+        };
+        if let Some(side) = non_null_side {
+            let side = expression_to_sql(side, dialect)?;
+            let check = if matches!(
+                operator,
+                BinaryOperator::Equals | BinaryOperator::StrictEquals
+            ) {
+                "IS NULL"
+            } else {
+                "IS NOT NULL"
+            };
+            return Ok(format!("({} {})", side, check));
+        }
+    }
+
+    let lhs = expression_to_sql(node.left(), dialect)?;
+    let rhs = expression_to_sql(node.right(), dialect)?;
+    let op = match node.to_operator() {
+        BinaryOperator::Plus => "+",
+        BinaryOperator::Minus => "-",
+        BinaryOperator::Mult => "*",
+        BinaryOperator::Div => "/",
+        BinaryOperator::Mod => "%",
+        BinaryOperator::Equals | BinaryOperator::StrictEquals => "=",
+        BinaryOperator::NotEquals | BinaryOperator::NotStrictEquals => "<>",
+        BinaryOperator::LessThan => "<",
+        BinaryOperator::LessThanEqual => "<=",
+        BinaryOperator::GreaterThan => ">",
+        BinaryOperator::GreaterThanEqual => ">=",
+        BinaryOperator::BitwiseAnd => "&",
+        BinaryOperator::BitwiseOr => "|",
+        BinaryOperator::LeftShift => "<<",
+        BinaryOperator::RightShift => ">>",
+        BinaryOperator::BitwiseXor => match dialect {
+            SqlDialect::Postgres => "#",
+            _ => "^",
+        },
+        BinaryOperator::UnsignedRightShift => {
+            return Err(VegaFusionError::compilation(
+                "The unsigned right shift operator (>>>) has no SQL equivalent",
+            ))
+        }
+    };
+    Ok(format!("({} {} {})", lhs, op, rhs))
+}
+
+fn logical_to_sql(node: &LogicalExpression, dialect: &SqlDialect) -> Result<String> {
+    let lhs = expression_to_sql(node.left(), dialect)?;
+    let rhs = expression_to_sql(node.right(), dialect)?;
+    let op = match node.to_operator() {
+        LogicalOperator::And => "AND",
+        LogicalOperator::Or => "OR",
+    };
+    Ok(format!("({} {} {})", lhs, op, rhs))
+}
+
+fn unary_to_sql(node: &UnaryExpression, dialect: &SqlDialect) -> Result<String> {
+    let argument = expression_to_sql(node.argument(), dialect)?;
+    Ok(match node.to_operator() {
+        UnaryOperator::Pos => format!("(+{})", argument),
+        UnaryOperator::Neg => format!("(-{})", argument),
+        UnaryOperator::Not => format!("(NOT {})", argument),
+        UnaryOperator::BitwiseNot => format!("(~{})", argument),
+    })
+}
+
+fn conditional_to_sql(node: &ConditionalExpression, dialect: &SqlDialect) -> Result<String> {
+    let test = expression_to_sql(node.test(), dialect)?;
+    let consequent = expression_to_sql(node.consequent(), dialect)?;
+    let alternate = expression_to_sql(node.alternate(), dialect)?;
+    Ok(format!(
+        "(CASE WHEN {} THEN {} ELSE {} END)",
+        test, consequent, alternate
+    ))
+}
+
+fn member_to_sql(node: &MemberExpression, dialect: &SqlDialect) -> Result<String> {
+    // Only member access on the datum free variable maps to SQL (as a column reference)
+    if let Ok(Identifier { name, .. }) = node.object().as_identifier() {
+        if name == "datum" {
+            let column = if node.computed {
+                if let Ok(Literal {
+                    value: Some(Value::String(column)),
+                    ..
+                }) = node.property().as_literal()
+                {
+                    column.clone()
+                } else {
+                    return Err(VegaFusionError::compilation(&format!(
+                        "Computed member property not supported in SQL conversion: {}",
+                        node.property()
+                    )));
+                }
+            } else {
+                node.property().as_identifier()?.name.clone()
+            };
+            return Ok(dialect.quote_identifier(&column));
+        }
+    }
+    Err(VegaFusionError::compilation(&format!(
+        "Member expression not supported in SQL conversion: {}",
+        node.object()
+    )))
+}
+
+fn call_to_sql(node: &CallExpression, dialect: &SqlDialect) -> Result<String> {
+    // The if() function maps to a CASE expression
+    if node.callee == "if" && node.arguments.len() == 3 {
+        let test = expression_to_sql(&node.arguments[0], dialect)?;
+        let consequent = expression_to_sql(&node.arguments[1], dialect)?;
+        let alternate = expression_to_sql(&node.arguments[2], dialect)?;
+        return Ok(format!(
+            "(CASE WHEN {} THEN {} ELSE {} END)",
+            test, consequent, alternate
+        ));
+    }
+
+    // isValid(v) maps to a null check
+    if node.callee == "isValid" && node.arguments.len() == 1 {
+        let argument = expression_to_sql(&node.arguments[0], dialect)?;
+        return Ok(format!("({} IS NOT NULL)", argument));
+    }
+
+    let sql_name = match node.callee.as_str() {
+        "abs" => "ABS",
+        "ceil" => "CEIL",
+        "floor" => "FLOOR",
+        "round" => "ROUND",
+        "sqrt" => "SQRT",
+        "exp" => "EXP",
+        "log" => "LN",
+        "pow" => "POWER",
+        "acos" => "ACOS",
+        "asin" => "ASIN",
+        "atan" => "ATAN",
+        "cos" => "COS",
+        "sin" => "SIN",
+        "tan" => "TAN",
+        "upper" => "UPPER",
+        "lower" => "LOWER",
+        "length" => "LENGTH",
+        "trim" => "TRIM",
+        callee => {
+            return Err(VegaFusionError::compilation(&format!(
+                "Function not supported in SQL conversion: {}",
+                callee
+            )))
+        }
+    };
+
+    let arguments: Vec<String> = node
+        .arguments
+        .iter()
+        .map(|arg| expression_to_sql(arg, dialect))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(format!("{}({})", sql_name, arguments.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::expression::parser::parse;
+    use crate::expression::sql::{expression_to_sql, SqlDialect};
+
+    fn to_sql(expr: &str, dialect: &SqlDialect) -> String {
+        expression_to_sql(&parse(expr).unwrap(), dialect).unwrap()
+    }
+
+    #[test]
+    fn test_binary_and_member() {
+        let sql = to_sql("datum.a + datum['b c'] * 2", &SqlDialect::Generic);
+        assert_eq!(sql, r#"("a" + ("b c" * 2))"#);
+    }
+
+    #[test]
+    fn test_conditional_and_string() {
+        let sql = to_sql("datum.a > 10 ? 'big' : 'small'", &SqlDialect::Generic);
+        assert_eq!(
+            sql,
+            r#"(CASE WHEN ("a" > 10) THEN 'big' ELSE 'small' END)"#
+        );
+    }
+
+    #[test]
+    fn test_mysql_identifier_quoting() {
+        let sql = to_sql("datum.a == null", &SqlDialect::MySql);
+        assert_eq!(sql, "(`a` IS NULL)");
+    }
+
+    #[test]
+    fn test_function_mapping() {
+        let sql = to_sql("pow(abs(datum.a), 2)", &SqlDialect::Generic);
+        assert_eq!(sql, r#"POWER(ABS("a"), 2)"#);
+    }
+
+    #[test]
+    fn test_unsupported_function() {
+        let result = expression_to_sql(&parse("utcFormat(datum.a, '%Y')").unwrap(), &SqlDialect::Generic);
+        assert!(result.is_err());
+    }
+}
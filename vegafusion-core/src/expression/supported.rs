@@ -7,6 +7,7 @@
  * this program the details of the active license.
  */
 use std::collections::HashSet;
+use std::sync::RwLock;
 
 lazy_static! {
     pub static ref BUILT_IN_SIGNALS: HashSet<&'static str> =
@@ -20,7 +21,7 @@ lazy_static! {
             .collect();
 
     pub static ref ALL_DATA_FNS: HashSet<&'static str> = vec![
-        "data", "indata", "vlSelectionTest", "vlSelectionResolve", "modify"
+        "data", "indata", "vlSelectionTest", "vlSelectionIdTest", "vlSelectionResolve", "modify"
     ]
     .into_iter()
     .collect();
@@ -38,34 +39,59 @@ lazy_static! {
 
 
     pub static ref SUPPORTED_DATA_FNS: HashSet<&'static str> = vec![
-        "data", "vlSelectionTest", "vlSelectionResolve"
+        "data", "indata", "vlSelectionTest", "vlSelectionIdTest", "vlSelectionResolve"
     ]
     .into_iter()
     .collect();
 
-    pub static ref SUPPORTED_SCALE_FNS: HashSet<&'static str> = Default::default();
+    pub static ref SUPPORTED_SCALE_FNS: HashSet<&'static str> = vec![
+        "scale", "invert", "domain", "range", "bandwidth"
+    ]
+    .into_iter()
+    .collect();
 
     pub static ref SUPPORTED_EXPRESSION_FNS: HashSet<&'static str> = vec![
         // Math
         "abs", "acos", "asin", "atan", "ceil", "cos", "exp", "floor", "round", "sqrt", "tan",
-        "log", "pow",
+        "log", "pow", "random", "lerp",
+
+        // Statistics
+        "quantile",
+        "cumulativeNormal", "densityNormal", "quantileNormal", "sampleNormal",
+        "cumulativeLogNormal", "densityLogNormal", "quantileLogNormal", "sampleLogNormal",
+        "cumulativeUniform", "densityUniform", "quantileUniform", "sampleUniform",
 
         // Type checking
-        "isNaN", "isFinite", "isValid", "isDate",
+        "isNaN", "isFinite", "isValid", "isDate", "isArray", "isObject", "isString", "isNumber",
+        "isBoolean",
 
         // Array
-        "length", "span",
+        "length", "span", "sequence", "extent", "join", "reverse", "sort", "slice",
+        "indexof", "lastindexof", "pluck", "inrange", "clampRange",
+
+        // Object
+        "merge",
+
+        // Color
+        "rgb", "hsl", "lab", "hcl", "luminance", "contrast",
+
+        // String
+        "pad", "truncate",
 
         // Datetime
         "year", "quarter", "month", "day", "date", "dayofyear", "hours", "minutes", "seconds",
         "utcyear", "utcquarter", "utcmonth", "utcday", "utcdate", "utcdayofyear",
         "utchours", "utcminutes", "utcseconds", "datetime", "utc", "time", "timeFormat", "utcFormat",
+        "dayFormat", "dayAbbrevFormat", "monthFormat", "monthAbbrevFormat",
 
         // Conversion
         "toBoolean", "toDate", "toNumber", "toString",
 
         // Control flow
         "if",
+
+        // Logging
+        "warn", "info", "debug",
     ]
     .into_iter()
     .collect();
@@ -75,4 +101,24 @@ lazy_static! {
     ]
     .into_iter()
     .collect();
+
+    /// Custom expression functions registered at runtime by downstream applications.
+    /// Names registered here are considered supported by the planner in addition to
+    /// the built-in SUPPORTED_EXPRESSION_FNS
+    pub static ref CUSTOM_EXPRESSION_FNS: RwLock<HashSet<String>> = Default::default();
+}
+
+/// Register the name of a custom expression function so that expressions calling it are
+/// considered plannable. The runtime must separately provide an implementation of the
+/// function for compilation to succeed.
+pub fn register_custom_expression_fn(name: &str) {
+    let mut custom_fns = CUSTOM_EXPRESSION_FNS.write().unwrap();
+    custom_fns.insert(name.to_string());
+}
+
+/// Check whether the named expression function is supported, either as a built-in
+/// function or as a registered custom function
+pub fn is_supported_expression_fn(name: &str) -> bool {
+    SUPPORTED_EXPRESSION_FNS.contains(name)
+        || CUSTOM_EXPRESSION_FNS.read().unwrap().contains(name)
 }
@@ -14,8 +14,8 @@ use crate::proto::gen::expression::{
 
 use crate::expression::column_usage::{ColumnUsage, DatasetsColumnUsage, VlSelectionFields};
 use crate::expression::supported::{
-    ALL_DATA_FNS, ALL_EXPRESSION_CONSTANTS, ALL_SCALE_FNS, IMPLICIT_VARS, SUPPORTED_DATA_FNS,
-    SUPPORTED_EXPRESSION_FNS, SUPPORTED_SCALE_FNS,
+    is_supported_expression_fn, ALL_DATA_FNS, ALL_EXPRESSION_CONSTANTS, ALL_SCALE_FNS,
+    IMPLICIT_VARS, SUPPORTED_DATA_FNS, SUPPORTED_SCALE_FNS,
 };
 use crate::proto::gen::expression::expression::Expr;
 use crate::proto::gen::expression::literal::Value;
@@ -204,7 +204,13 @@ impl ExpressionVisitor for CheckSupportedExprVisitor {
             if !SUPPORTED_SCALE_FNS.contains(node.name.as_str()) {
                 self.supported = false;
             }
-        } else if !SUPPORTED_EXPRESSION_FNS.contains(node.name.as_str()) {
+            // The runtime resolves the scale at compile time, so the scale name must
+            // be a literal string
+            match args.get(0).map(|arg0| arg0.as_literal()) {
+                Some(Ok(arg0)) if matches!(arg0.value(), Value::String(_)) => {}
+                _ => self.supported = false,
+            }
+        } else if !is_supported_expression_fn(node.name.as_str()) {
             self.supported = false;
         }
     }
@@ -252,6 +258,8 @@ pub struct DatasetsColumnUsageVisitor<'a> {
     pub usage_scope: &'a [u32],
     pub task_scope: &'a TaskScope,
     pub dataset_column_usage: DatasetsColumnUsage,
+    datum_identifier_count: usize,
+    handled_datum_count: usize,
 }
 
 impl<'a> DatasetsColumnUsageVisitor<'a> {
@@ -267,11 +275,32 @@ impl<'a> DatasetsColumnUsageVisitor<'a> {
             usage_scope,
             task_scope,
             dataset_column_usage: DatasetsColumnUsage::empty(),
+            datum_identifier_count: 0,
+            handled_datum_count: 0,
+        }
+    }
+
+    /// The collected column usage, accounting for bare references to the `datum`
+    /// free variable. A bare `datum` (e.g. the `{"signal": "datum"}` encoding that
+    /// Vega-Lite generates for tooltips showing all fields) uses every column, so
+    /// any occurrence not handled precisely results in unknown usage
+    pub fn column_usage(&self) -> DatasetsColumnUsage {
+        if self.datum_identifier_count > self.handled_datum_count {
+            if let Some(datum_var) = self.datum_var {
+                return self.dataset_column_usage.with_unknown_usage(datum_var);
+            }
         }
+        self.dataset_column_usage.clone()
     }
 }
 
 impl<'a> ExpressionVisitor for DatasetsColumnUsageVisitor<'a> {
+    fn visit_identifier(&mut self, node: &Identifier) {
+        if node.name == "datum" {
+            self.datum_identifier_count += 1;
+        }
+    }
+
     fn visit_member(&mut self, node: &MemberExpression) {
         if let (Some(datum_var), Some(object), Some(property)) =
             (&self.datum_var, &node.object, &node.property)
@@ -281,6 +310,7 @@ impl<'a> ExpressionVisitor for DatasetsColumnUsageVisitor<'a> {
             {
                 if object_id.name == "datum" {
                     // This expression is a member expression on the datum free variable
+                    self.handled_datum_count += 1;
                     if node.computed {
                         match property_expr {
                             Expr::Literal(Literal {
@@ -348,6 +378,14 @@ impl<'a> ExpressionVisitor for DatasetsColumnUsageVisitor<'a> {
 
                     // Handle vlSelectionTest, which also uses datum columns
                     if node.callee == "vlSelectionTest" {
+                        // The bare `datum` argument is accounted for by the
+                        // selection's field list (or the unknown usage recorded below)
+                        for arg in &node.arguments {
+                            if matches!(&arg.expr, Some(Expr::Identifier(id)) if id.name == "datum")
+                            {
+                                self.handled_datum_count += 1;
+                            }
+                        }
                         if let Some(datum_var) = self.datum_var {
                             if let Some(fields) =
                                 self.vl_selection_fields.get(&scoped_reference_data_var)
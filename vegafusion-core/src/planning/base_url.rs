@@ -0,0 +1,76 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use crate::error::Result;
+use crate::spec::chart::{ChartSpec, MutChartVisitor};
+use crate::spec::data::DataSpec;
+use crate::spec::values::StringOrSignalSpec;
+
+/// Check whether a data url is relative (has no scheme and is not an absolute path).
+/// Matches the protocol check performed by vega-loader's sanitize logic
+pub fn is_relative_url(url: &str) -> bool {
+    !url.contains("://") && !url.starts_with("data:") && !url.starts_with('/')
+}
+
+/// Resolve a relative url against a base url, matching vega-loader's `baseURL` option
+pub fn resolve_url(url: &str, base_url: &str) -> String {
+    if base_url.ends_with('/') {
+        format!("{}{}", base_url, url)
+    } else {
+        format!("{}/{}", base_url, url)
+    }
+}
+
+/// Resolve all relative string data urls in a chart spec against a base url.
+/// Urls defined by signal expressions are left unchanged since their values
+/// aren't known until evaluation
+pub fn apply_base_url(spec: &mut ChartSpec, base_url: &str) -> Result<()> {
+    let mut visitor = ApplyBaseUrlVisitor { base_url };
+    spec.walk_mut(&mut visitor)
+}
+
+struct ApplyBaseUrlVisitor<'a> {
+    base_url: &'a str,
+}
+
+impl<'a> MutChartVisitor for ApplyBaseUrlVisitor<'a> {
+    fn visit_data(&mut self, data: &mut DataSpec, _scope: &[u32]) -> Result<()> {
+        if let Some(StringOrSignalSpec::String(url)) = &mut data.url {
+            if is_relative_url(url) {
+                *url = resolve_url(url, self.base_url);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_relative_url, resolve_url};
+
+    #[test]
+    fn test_is_relative_url() {
+        assert!(is_relative_url("data/cars.json"));
+        assert!(!is_relative_url("https://vega.github.io/data/cars.json"));
+        assert!(!is_relative_url("s3://bucket/cars.json"));
+        assert!(!is_relative_url("/data/cars.json"));
+        assert!(!is_relative_url("data:text/csv;base64,YSxiCjEsMgo="));
+    }
+
+    #[test]
+    fn test_resolve_url() {
+        assert_eq!(
+            resolve_url("data/cars.json", "https://vega.github.io"),
+            "https://vega.github.io/data/cars.json"
+        );
+        assert_eq!(
+            resolve_url("data/cars.json", "https://vega.github.io/"),
+            "https://vega.github.io/data/cars.json"
+        );
+    }
+}
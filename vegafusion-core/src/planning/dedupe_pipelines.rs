@@ -0,0 +1,124 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use crate::error::Result;
+use crate::spec::chart::ChartSpec;
+use crate::spec::data::DataSpec;
+use crate::spec::mark::MarkSpec;
+
+use std::collections::HashMap;
+
+/// This optimization pass detects datasets in the same scope that read from the same
+/// source (url, inline values, or source dataset) and share a common prefix of
+/// transforms. Vega-Lite output for layered and faceted charts often contains several
+/// such datasets. The shared (source, transform-prefix) pair is rewritten into a
+/// single upstream dataset that the original datasets source from, so the runtime
+/// computes the shared work once
+pub fn dedupe_pipelines(spec: &mut ChartSpec) -> Result<()> {
+    dedupe_dataset_list(&mut spec.data);
+    for mark in &mut spec.marks {
+        dedupe_group(mark);
+    }
+    Ok(())
+}
+
+fn dedupe_group(mark: &mut MarkSpec) {
+    dedupe_dataset_list(&mut mark.data);
+    for mark in &mut mark.marks {
+        dedupe_group(mark);
+    }
+}
+
+fn dedupe_dataset_list(datasets: &mut Vec<DataSpec>) {
+    // Group dataset indices by their source configuration: the serialized dataset
+    // without its name and transforms
+    let mut groups: HashMap<String, Vec<usize>> = HashMap::new();
+    for (index, data) in datasets.iter().enumerate() {
+        // Skip datasets with `on` trigger blocks and datasets without a source
+        if data.on.is_some()
+            || (data.source.is_none() && data.url.is_none() && data.values.is_none())
+        {
+            continue;
+        }
+        let mut signature = data.clone();
+        signature.name = String::new();
+        signature.transform = Vec::new();
+        if let Ok(key) = serde_json::to_string(&signature) {
+            groups.entry(key).or_default().push(index);
+        }
+    }
+
+    // Datasets to insert as (index, dataset) pairs, collected so indices stay valid
+    // while iterating
+    let mut shared_datasets: Vec<(usize, DataSpec)> = Vec::new();
+
+    for indices in groups.values() {
+        if indices.len() < 2 {
+            continue;
+        }
+
+        // Longest common prefix of transforms across the group. Transforms that
+        // output signals end the prefix so signal production isn't relocated
+        let first = &datasets[indices[0]];
+        let mut prefix_len = first.transform.len();
+        for index in &indices[1..] {
+            let other = &datasets[*index].transform;
+            let common = first
+                .transform
+                .iter()
+                .zip(other)
+                .take_while(|(a, b)| a == b && a.output_signals().is_empty())
+                .count();
+            prefix_len = prefix_len.min(common);
+        }
+
+        // An empty prefix is only worth sharing when it avoids repeated url scans
+        if prefix_len == 0 && first.url.is_none() {
+            continue;
+        }
+
+        // If some member's pipeline is exactly the shared prefix, use it as the
+        // upstream dataset. Otherwise introduce a new dataset holding the prefix
+        let upstream_index = indices
+            .iter()
+            .find(|index| datasets[**index].transform.len() == prefix_len)
+            .cloned();
+        let upstream_name = match upstream_index {
+            Some(index) => datasets[index].name.clone(),
+            None => {
+                let mut shared_data = datasets[indices[0]].clone();
+                shared_data.name.insert_str(0, "_shared_");
+                shared_data.transform = Vec::from(&shared_data.transform[..prefix_len]);
+                let name = shared_data.name.clone();
+                shared_datasets.push((indices[0], shared_data));
+                name
+            }
+        };
+
+        // Rewrite the other members to source from the upstream dataset, keeping
+        // only the transforms that follow the shared prefix
+        for index in indices {
+            if Some(*index) == upstream_index {
+                continue;
+            }
+            let data = &mut datasets[*index];
+            data.source = Some(upstream_name.clone());
+            data.url = None;
+            data.format = None;
+            data.values = None;
+            data.transform = Vec::from(&data.transform[prefix_len..]);
+        }
+    }
+
+    // Insert new shared datasets ahead of their first member, from the back so
+    // earlier indices remain valid
+    shared_datasets.sort_by_key(|(index, _)| *index);
+    for (index, shared_data) in shared_datasets.into_iter().rev() {
+        datasets.insert(index, shared_data);
+    }
+}
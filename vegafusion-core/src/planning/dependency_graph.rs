@@ -9,7 +9,7 @@
 use crate::error::{Result, ResultWithContext, VegaFusionError};
 use crate::expression::parser::parse;
 use crate::expression::supported::BUILT_IN_SIGNALS;
-use crate::planning::plan::PlannerConfig;
+use crate::planning::plan::{DatasetOverride, DatasetPlacement, PlannerConfig};
 use crate::proto::gen::tasks::{Variable, VariableNamespace};
 use crate::spec::chart::{ChartSpec, ChartVisitor};
 use crate::spec::data::{DataSpec, DependencyNodeSupported};
@@ -31,6 +31,13 @@ pub fn get_supported_data_variables(
     config: &PlannerConfig,
 ) -> Result<HashMap<ScopedVariable, DependencyNodeSupported>> {
     let data_graph = build_dependency_graph(chart_spec, config)?;
+
+    // Collect datasets with `on` trigger blocks. These stay on the client, but like
+    // client signals their values are sent to the server as task-graph updates, so
+    // they are acceptable inputs for server-side datasets
+    let mut trigger_visitor = CollectTriggeredDataVisitor::default();
+    chart_spec.walk(&mut trigger_visitor)?;
+    let triggered_data = trigger_visitor.triggered_data;
     // Sort dataset nodes topologically
     let nodes: Vec<NodeIndex> = match toposort(&data_graph, None) {
         Ok(v) => v,
@@ -49,6 +56,19 @@ pub fn get_supported_data_variables(
     for node_index in &nodes {
         let (scoped_var, node_supported) = data_graph.node_weight(*node_index).unwrap();
 
+        // Variables pinned to the client are treated as unsupported, which also
+        // keeps their dependents on the client
+        if config.keep_variables.contains(scoped_var) {
+            continue;
+        }
+
+        // Datasets pinned to the client by an override behave like keep_variables
+        if let Some(dataset_override) = config.dataset_overrides.get(scoped_var) {
+            if matches!(dataset_override.placement, Some(DatasetPlacement::Client)) {
+                continue;
+            }
+        }
+
         // Unsupported nodes not included
         if !matches!(node_supported, DependencyNodeSupported::Unsupported) {
             // Check whether all parents are fully supported
@@ -74,10 +94,12 @@ pub fn get_supported_data_variables(
                     .map(|edge| data_graph.node_weight(edge.source()).unwrap().0.clone())
                     .all(|parent_var| match parent_var.0.namespace() {
                         VariableNamespace::Data => {
+                            // Triggered datasets are client-side, but their values are sent
+                            // to the server like signal updates
                             matches!(
                                 all_supported_vars.get(&parent_var),
                                 Some(DependencyNodeSupported::Supported)
-                            )
+                            ) || triggered_data.contains(&parent_var)
                         }
                         _ => true,
                     });
@@ -92,6 +114,17 @@ pub fn get_supported_data_variables(
         }
     }
 
+    // Datasets pinned to the server must have made it through the dependency checks
+    for forced_var in &config.force_server_datasets {
+        if !all_supported_vars.contains_key(forced_var) {
+            return Err(VegaFusionError::internal(format!(
+                "Dataset \"{}\" is pinned to the server, but its dependencies are not \
+                 available on the server",
+                forced_var.0.name
+            )));
+        }
+    }
+
     // Traverse again, this time keep all data nodes, but only keep signals that are ancestors
     // of supported data nodes. This is to avoid bringing over unnecessary signals
     let mut supported_vars = HashMap::new();
@@ -104,7 +137,7 @@ pub fn get_supported_data_variables(
                     supported_vars.insert(scoped_var.clone(), supported.clone());
                 }
             }
-            VariableNamespace::Signal => {
+            VariableNamespace::Signal | VariableNamespace::Scale => {
                 if all_supported_vars.contains_key(scoped_var) {
                     // Check if any dependent nodes are supported data sets
                     let mut dfs = petgraph::visit::Dfs::new(&data_graph, *node_index);
@@ -113,8 +146,8 @@ pub fn get_supported_data_variables(
                         if matches!(dfs_scoped_var.0.namespace(), VariableNamespace::Data)
                             && all_supported_vars.contains_key(dfs_scoped_var)
                         {
-                            // Found supported child data node. Add signal as supported and bail
-                            // out of DFS
+                            // Found supported child data node. Add signal/scale as supported and
+                            // bail out of DFS
                             supported_vars.insert(
                                 scoped_var.clone(),
                                 all_supported_vars.get(dfs_scoped_var).unwrap().clone(),
@@ -124,7 +157,6 @@ pub fn get_supported_data_variables(
                     }
                 }
             }
-            _ => {}
         }
     }
 
@@ -136,7 +168,11 @@ pub fn build_dependency_graph(
     config: &PlannerConfig,
 ) -> Result<DiGraph<(ScopedVariable, DependencyNodeSupported), ()>> {
     // Initialize graph with nodes
-    let mut nodes_visitor = AddDependencyNodesVisitor::new(config.extract_inline_data);
+    let mut nodes_visitor = AddDependencyNodesVisitor::new(
+        config.extract_inline_data,
+        &config.dataset_overrides,
+        &config.force_server_datasets,
+    );
     chart_spec.walk(&mut nodes_visitor)?;
 
     // Add dependency edges
@@ -151,16 +187,40 @@ pub fn build_dependency_graph(
     Ok(nodes_visitor.dependency_graph)
 }
 
-/// Visitor to initialize directed graph with nodes for each dataset (no edges yet)
+/// Visitor to collect datasets with `on` trigger blocks. These remain client-side so
+/// the Vega runtime can apply the triggers, while their values are communicated to
+/// the server as task-graph updates
 #[derive(Debug, Default)]
-pub struct AddDependencyNodesVisitor {
+pub struct CollectTriggeredDataVisitor {
+    pub triggered_data: HashSet<ScopedVariable>,
+}
+
+impl ChartVisitor for CollectTriggeredDataVisitor {
+    fn visit_data(&mut self, data: &DataSpec, scope: &[u32]) -> Result<()> {
+        if data.on.is_some() {
+            self.triggered_data
+                .insert((Variable::new_data(&data.name), Vec::from(scope)));
+        }
+        Ok(())
+    }
+}
+
+/// Visitor to initialize directed graph with nodes for each dataset (no edges yet)
+#[derive(Debug)]
+pub struct AddDependencyNodesVisitor<'a> {
     pub dependency_graph: DiGraph<(ScopedVariable, DependencyNodeSupported), ()>,
     pub node_indexes: HashMap<ScopedVariable, NodeIndex>,
     pub extract_inline_data: bool,
+    pub dataset_overrides: &'a HashMap<ScopedVariable, DatasetOverride>,
+    pub force_server_datasets: &'a [ScopedVariable],
 }
 
-impl AddDependencyNodesVisitor {
-    pub fn new(extract_inline_data: bool) -> Self {
+impl<'a> AddDependencyNodesVisitor<'a> {
+    pub fn new(
+        extract_inline_data: bool,
+        dataset_overrides: &'a HashMap<ScopedVariable, DatasetOverride>,
+        force_server_datasets: &'a [ScopedVariable],
+    ) -> Self {
         let mut dependency_graph = DiGraph::new();
         let mut node_indexes = HashMap::new();
 
@@ -176,15 +236,51 @@ impl AddDependencyNodesVisitor {
             dependency_graph,
             node_indexes,
             extract_inline_data,
+            dataset_overrides,
+            force_server_datasets,
         }
     }
 }
 
-impl ChartVisitor for AddDependencyNodesVisitor {
+impl<'a> ChartVisitor for AddDependencyNodesVisitor<'a> {
     fn visit_data(&mut self, data: &DataSpec, scope: &[u32]) -> Result<()> {
         // Add scoped variable for dataset as node
         let scoped_var = (Variable::new_data(&data.name), Vec::from(scope));
-        let data_suported = data.supported(self.extract_inline_data);
+        let mut data_suported = data.supported(self.extract_inline_data);
+
+        // Datasets pinned to the server by an override are treated as supported,
+        // trusting the caller over the planner's classification
+        if let Some(dataset_override) = self.dataset_overrides.get(&scoped_var) {
+            if matches!(dataset_override.placement, Some(DatasetPlacement::Server)) {
+                data_suported = DependencyNodeSupported::Supported;
+            }
+        }
+
+        // Datasets pinned to the server by configuration must have fully supported
+        // pipelines; fail with a clear error rather than quietly keeping them
+        // client-side
+        if self.force_server_datasets.contains(&scoped_var) {
+            if let Some((index, _)) = data
+                .transform
+                .iter()
+                .enumerate()
+                .find(|(_, tx)| !tx.supported())
+            {
+                return Err(VegaFusionError::internal(format!(
+                    "Dataset \"{}\" is pinned to the server, but transform {} of its \
+                     pipeline is not supported for server evaluation",
+                    data.name, index
+                )));
+            }
+            if matches!(data_suported, DependencyNodeSupported::Unsupported) {
+                return Err(VegaFusionError::internal(format!(
+                    "Dataset \"{}\" is pinned to the server, but its definition is not \
+                     supported for server evaluation",
+                    data.name
+                )));
+            }
+            data_suported = DependencyNodeSupported::Supported;
+        }
         let node_index = self
             .dependency_graph
             .add_node((scoped_var.clone(), data_suported.clone()));
@@ -218,7 +314,7 @@ impl ChartVisitor for AddDependencyNodesVisitor {
         let scoped_var = (Variable::new_scale(&scale.name), Vec::from(scope));
         let node_index = self
             .dependency_graph
-            .add_node((scoped_var.clone(), DependencyNodeSupported::Unsupported));
+            .add_node((scoped_var.clone(), scale.supported()));
         self.node_indexes.insert(scoped_var, node_index);
         Ok(())
     }
@@ -353,13 +449,7 @@ impl<'a> ChartVisitor for AddDependencyEdgesVisitor<'a> {
 
         if let Some(update) = &signal.update {
             let expression = parse(update)?;
-            // Add edges from nodes to signal (Scale dependencies not supported on server yet)
-            input_vars.extend(
-                expression
-                    .input_vars()
-                    .into_iter()
-                    .filter(|v| v.var.namespace() != VariableNamespace::Scale),
-            );
+            input_vars.extend(expression.input_vars());
         }
 
         if let Some(init) = &signal.init {
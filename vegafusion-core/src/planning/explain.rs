@@ -0,0 +1,154 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use crate::error::Result;
+use crate::planning::watch::{Watch, WatchPlan};
+use crate::spec::chart::{ChartSpec, ChartVisitor};
+use crate::spec::data::DataSpec;
+use crate::spec::signal::SignalSpec;
+use crate::spec::transform::TransformSpec;
+use serde::{Deserialize, Serialize};
+
+/// Structured report of the decisions made while planning a Vega specification.
+/// Lists the datasets and signals placed on the server and on the client, why
+/// client datasets could not be (fully) evaluated on the server, and the
+/// resulting communication plan
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanExplanation {
+    pub server_datasets: Vec<DatasetExplanation>,
+    pub client_datasets: Vec<DatasetExplanation>,
+    pub server_signals: Vec<Watch>,
+    pub comm_plan: WatchPlan,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatasetExplanation {
+    pub name: String,
+    pub scope: Vec<u32>,
+
+    /// Dataset the transforms read from, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+
+    /// The `type` of each of the dataset's transforms
+    pub transforms: Vec<String>,
+
+    /// For client datasets: why the dataset (or the remainder of its transform
+    /// pipeline) was not evaluated on the server
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback_reason: Option<String>,
+}
+
+impl PlanExplanation {
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// The `type` discriminant of a transform spec
+fn transform_type(tx: &TransformSpec) -> String {
+    serde_json::to_value(tx)
+        .ok()
+        .and_then(|value| {
+            value
+                .get("type")
+                .and_then(|type_| type_.as_str())
+                .map(|type_| type_.to_string())
+        })
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Best-effort description of why a client dataset fell back to the Vega runtime
+fn fallback_reason(data: &DataSpec) -> Option<String> {
+    if data.on.is_some() {
+        return Some(
+            "dataset has an `on` trigger block, which must run in the Vega runtime".to_string(),
+        );
+    }
+
+    if let Some(Some(format_type)) = data.format.as_ref().map(|fmt| fmt.type_.clone()) {
+        if !matches!(
+            format_type.as_str(),
+            "csv" | "tsv" | "arrow" | "feather" | "parquet" | "json" | "topojson"
+        ) {
+            return Some(format!("data format \"{}\" is not supported", format_type));
+        }
+    }
+
+    for (i, tx) in data.transform.iter().enumerate() {
+        if !tx.supported() {
+            return Some(format!(
+                "transform {} (\"{}\") is not supported",
+                i,
+                transform_type(tx)
+            ));
+        }
+    }
+
+    if !data.transform.is_empty() || data.url.is_some() || data.values.is_some() {
+        // All transforms are individually supported, so the dataset was kept on the
+        // client because of its dependencies or the planner configuration
+        Some(
+            "dataset depends on client-side variables or was pinned to the client".to_string(),
+        )
+    } else {
+        None
+    }
+}
+
+/// Visitor to collect dataset and signal explanations from a planned spec
+#[derive(Debug, Default)]
+pub struct CollectExplanationsVisitor {
+    pub datasets: Vec<DatasetExplanation>,
+    pub signals: Vec<Watch>,
+    /// Whether to compute fallback reasons (true for the client spec)
+    pub explain_fallback: bool,
+}
+
+impl CollectExplanationsVisitor {
+    pub fn new(explain_fallback: bool) -> Self {
+        Self {
+            datasets: Vec::new(),
+            signals: Vec::new(),
+            explain_fallback,
+        }
+    }
+}
+
+impl ChartVisitor for CollectExplanationsVisitor {
+    fn visit_data(&mut self, data: &DataSpec, scope: &[u32]) -> Result<()> {
+        self.datasets.push(DatasetExplanation {
+            name: data.name.clone(),
+            scope: Vec::from(scope),
+            source: data.source.clone(),
+            transforms: data.transform.iter().map(transform_type).collect(),
+            fallback_reason: if self.explain_fallback {
+                fallback_reason(data)
+            } else {
+                None
+            },
+        });
+        Ok(())
+    }
+
+    fn visit_signal(&mut self, signal: &SignalSpec, scope: &[u32]) -> Result<()> {
+        self.signals.push(Watch {
+            namespace: crate::planning::watch::WatchNamespace::Signal,
+            name: signal.name.clone(),
+            scope: Vec::from(scope),
+        });
+        Ok(())
+    }
+}
+
+pub fn explain_spec(spec: &ChartSpec, explain_fallback: bool) -> Result<CollectExplanationsVisitor> {
+    let mut visitor = CollectExplanationsVisitor::new(explain_fallback);
+    spec.walk(&mut visitor)?;
+    Ok(visitor)
+}
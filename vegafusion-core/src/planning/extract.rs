@@ -11,8 +11,8 @@ use crate::planning::dependency_graph::{get_supported_data_variables, scoped_var
 use crate::proto::gen::tasks::Variable;
 use crate::spec::chart::{ChartSpec, MutChartVisitor};
 use crate::spec::data::{DataSpec, DependencyNodeSupported};
-use crate::spec::mark::MarkSpec;
 
+use crate::spec::scale::ScaleSpec;
 use crate::spec::signal::SignalSpec;
 use crate::task_graph::scope::TaskScope;
 
@@ -103,6 +103,13 @@ impl<'a> MutChartVisitor for ExtractServerDependenciesVisitor<'a> {
                     num_supported = i + 1;
                 }
 
+                if num_supported == 0 && data.url.is_none() && data.values.is_none() {
+                    // No transforms can run on the server and there is no scan to
+                    // extract, so a server copy would be a pure passthrough of its
+                    // source. Leave the dataset on the client
+                    return Ok(());
+                }
+
                 let server_tx: Vec<_> = Vec::from(&data.transform[..num_supported]);
                 let client_tx: Vec<_> = Vec::from(&data.transform[num_supported..]);
 
@@ -120,7 +127,7 @@ impl<'a> MutChartVisitor for ExtractServerDependenciesVisitor<'a> {
                 if scope.is_empty() {
                     self.server_spec.data.push(server_data)
                 } else {
-                    let server_group = self.server_spec.get_nested_group_mut(scope)?;
+                    let server_group = self.server_spec.ensure_nested_group_mut(scope)?;
                     server_group.data.push(server_data);
                 }
 
@@ -152,7 +159,7 @@ impl<'a> MutChartVisitor for ExtractServerDependenciesVisitor<'a> {
                 if scope.is_empty() {
                     self.server_spec.data.push(server_data)
                 } else {
-                    let server_group = self.server_spec.get_nested_group_mut(scope)?;
+                    let server_group = self.server_spec.ensure_nested_group_mut(scope)?;
                     server_group.data.push(server_data);
                 }
 
@@ -181,7 +188,7 @@ impl<'a> MutChartVisitor for ExtractServerDependenciesVisitor<'a> {
             if scope.is_empty() {
                 self.server_spec.signals.push(server_signal)
             } else {
-                let server_group = self.server_spec.get_nested_group_mut(scope)?;
+                let server_group = self.server_spec.ensure_nested_group_mut(scope)?;
                 server_group.signals.push(server_signal);
             }
 
@@ -193,31 +200,20 @@ impl<'a> MutChartVisitor for ExtractServerDependenciesVisitor<'a> {
         Ok(())
     }
 
-    fn visit_group_mark(&mut self, _mark: &mut MarkSpec, scope: &[u32]) -> Result<()> {
-        // Initialize group mark in server spec
-        let parent_scope = &scope[..scope.len() - 1];
-        let new_group = MarkSpec {
-            type_: "group".to_string(),
-            name: None,
-            from: None,
-            sort: None,
-            encode: None,
-            data: vec![],
-            signals: vec![],
-            marks: vec![],
-            scales: vec![],
-            axes: vec![],
-            transform: vec![],
-            title: None,
-            extra: Default::default(),
-        };
-        if parent_scope.is_empty() {
-            self.server_spec.marks.push(new_group);
-        } else {
-            let parent_group = self.server_spec.get_nested_group_mut(parent_scope)?;
-            parent_group.marks.push(new_group);
+    fn visit_scale(&mut self, scale: &mut ScaleSpec, scope: &[u32]) -> Result<()> {
+        // Copy supported scales to the server so that expressions evaluated there can
+        // reference them. The client keeps its own copy for rendering, so nothing is
+        // cleared and no communication is required
+        let scoped_scale_var = (Variable::new_scale(&scale.name), Vec::from(scope));
+        if self.supported_vars.contains_key(&scoped_scale_var) {
+            let server_scale = scale.clone();
+            if scope.is_empty() {
+                self.server_spec.scales.push(server_scale)
+            } else {
+                let server_group = self.server_spec.ensure_nested_group_mut(scope)?;
+                server_group.scales.push(server_scale);
+            }
         }
-
         Ok(())
     }
 }
@@ -0,0 +1,102 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use crate::error::{Result, ResultWithContext, VegaFusionError};
+use crate::proto::gen::tasks::VariableNamespace;
+use crate::spec::chart::ChartSpec;
+use crate::task_graph::graph::ScopedVariable;
+use serde_json::Value;
+
+/// Apply initial variable values to a chart spec before planning, so the
+/// pre-transformed output reflects a specific interactive state (e.g. a saved
+/// brush extent stored in a selection-store dataset).
+///
+/// Signal values replace the signal's `value` property and dataset values (which
+/// must be arrays of rows) replace the dataset's `values` property. An error is
+/// raised if a referenced variable doesn't exist in the spec
+pub fn apply_initial_values(
+    spec: &mut ChartSpec,
+    initial_values: &[(ScopedVariable, Value)],
+) -> Result<()> {
+    for (scoped_var, value) in initial_values {
+        let scope = scoped_var.1.as_slice();
+        let name = scoped_var.0.name.as_str();
+        match scoped_var.0.ns() {
+            VariableNamespace::Signal => {
+                let signal = spec.get_nested_signal_mut(scope, name).with_context(|| {
+                    format!("No signal named {} with scope {:?}", name, scope)
+                })?;
+                signal.value = Some(value.clone());
+            }
+            VariableNamespace::Data => {
+                if !value.is_array() {
+                    return Err(VegaFusionError::pre_transform(format!(
+                        "Initial value for dataset {} must be an array of rows",
+                        name
+                    )));
+                }
+                let data = spec.get_nested_data_mut(scope, name).with_context(|| {
+                    format!("No dataset named {} with scope {:?}", name, scope)
+                })?;
+                data.values = Some(value.clone());
+            }
+            VariableNamespace::Scale => {
+                return Err(VegaFusionError::pre_transform(format!(
+                    "Initial values are not supported for scale variable {:?}",
+                    scoped_var.0
+                )))
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::apply_initial_values;
+    use crate::proto::gen::tasks::Variable;
+    use crate::spec::chart::ChartSpec;
+    use serde_json::json;
+
+    #[test]
+    fn test_apply_initial_values() {
+        let mut spec: ChartSpec = serde_json::from_value(json!({
+            "signals": [{"name": "brush_x", "value": [0, 0]}],
+            "data": [{"name": "brush_store"}]
+        }))
+        .unwrap();
+
+        apply_initial_values(
+            &mut spec,
+            &[
+                ((Variable::new_signal("brush_x"), Vec::new()), json!([10, 50])),
+                (
+                    (Variable::new_data("brush_store"), Vec::new()),
+                    json!([{"unit": "", "fields": [], "values": []}]),
+                ),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(spec.signals[0].value, Some(json!([10, 50])));
+        assert_eq!(
+            spec.data[0].values,
+            Some(json!([{"unit": "", "fields": [], "values": []}]))
+        );
+    }
+
+    #[test]
+    fn test_apply_initial_values_missing_variable() {
+        let mut spec: ChartSpec = serde_json::from_value(json!({"signals": [], "data": []})).unwrap();
+        let result = apply_initial_values(
+            &mut spec,
+            &[((Variable::new_signal("bogus"), Vec::new()), json!(1))],
+        );
+        assert!(result.is_err());
+    }
+}
@@ -6,8 +6,12 @@
  * Please consult the license documentation provided alongside
  * this program the details of the active license.
  */
+pub mod base_url;
+pub mod dedupe_pipelines;
 pub mod dependency_graph;
+pub mod explain;
 pub mod extract;
+pub mod initial_values;
 pub mod optimize_server;
 pub mod plan;
 pub mod projection_pushdown;
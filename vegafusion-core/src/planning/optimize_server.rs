@@ -7,10 +7,16 @@
  * this program the details of the active license.
  */
 use crate::error::Result;
+use crate::planning::dependency_graph::build_dependency_graph;
+use crate::planning::plan::PlannerConfig;
+use crate::planning::stitch::CommPlan;
 use crate::proto::gen::tasks::Variable;
-use crate::spec::chart::{ChartSpec, MutChartVisitor};
+use crate::spec::chart::{ChartSpec, ChartVisitor, MutChartVisitor};
 use crate::spec::data::DataSpec;
+use crate::spec::mark::MarkSpec;
+use crate::task_graph::graph::ScopedVariable;
 
+use petgraph::visit::{Dfs, Reversed};
 use std::collections::HashSet;
 
 /// This optimization pass examines data nodes that have been planned to execute on the server.
@@ -106,3 +112,107 @@ impl MutChartVisitor for SplitUrlDataNodeVisitor {
         Ok(())
     }
 }
+
+/// Prune server datasets and signals whose outputs are never sent to the client and
+/// aren't consumed by any other server node. These are typically planner byproducts
+/// (e.g. datasets whose consumers all ended up on the client), and evaluating them
+/// would waste computation on every interaction
+pub fn prune_unused_server_nodes(
+    server_spec: &mut ChartSpec,
+    comm_plan: &CommPlan,
+    config: &PlannerConfig,
+) -> Result<()> {
+    let graph = build_dependency_graph(server_spec, config)?;
+
+    // Roots that must be kept: values sent to the client, and the entry points that
+    // receive client updates (these must stay addressable in the task graph)
+    let mut roots: HashSet<ScopedVariable> = comm_plan
+        .server_to_client
+        .iter()
+        .chain(comm_plan.client_to_server.iter())
+        .cloned()
+        .collect();
+
+    // A dataset whose transforms produce a needed output signal must be kept as
+    // well, since the dependency graph doesn't link datasets to the signals their
+    // transforms define
+    let mut signals_visitor = CollectTransformSignalsVisitor::default();
+    server_spec.walk(&mut signals_visitor)?;
+    for (signal_var, data_var) in &signals_visitor.producers {
+        if roots.contains(signal_var) {
+            roots.insert(data_var.clone());
+        }
+    }
+
+    // Keep the roots and all of their ancestors
+    let mut needed: HashSet<ScopedVariable> = HashSet::new();
+    let reversed = Reversed(&graph);
+    for node_index in graph.node_indices() {
+        let (scoped_var, _) = graph.node_weight(node_index).unwrap();
+        if roots.contains(scoped_var) {
+            let mut dfs = Dfs::new(reversed, node_index);
+            while let Some(ancestor_index) = dfs.next(reversed) {
+                let (ancestor_var, _) = graph.node_weight(ancestor_index).unwrap();
+                needed.insert(ancestor_var.clone());
+            }
+        }
+    }
+
+    retain_needed(server_spec, &needed);
+    Ok(())
+}
+
+/// Visitor to collect (output signal, defining dataset) pairs for signals produced
+/// by transforms
+#[derive(Debug, Default)]
+struct CollectTransformSignalsVisitor {
+    pub producers: Vec<(ScopedVariable, ScopedVariable)>,
+}
+
+impl ChartVisitor for CollectTransformSignalsVisitor {
+    fn visit_data(&mut self, data: &DataSpec, scope: &[u32]) -> Result<()> {
+        let data_var = (Variable::new_data(&data.name), Vec::from(scope));
+        for sig in data.output_signals() {
+            let signal_var = (Variable::new_signal(&sig), Vec::from(scope));
+            self.producers.push((signal_var, data_var.clone()));
+        }
+        Ok(())
+    }
+}
+
+fn retain_needed(spec: &mut ChartSpec, needed: &HashSet<ScopedVariable>) {
+    let scope: Vec<u32> = Vec::new();
+    spec.data
+        .retain(|data| needed.contains(&(Variable::new_data(&data.name), scope.clone())));
+    spec.signals
+        .retain(|signal| needed.contains(&(Variable::new_signal(&signal.name), scope.clone())));
+
+    let mut group_index = 0;
+    for mark in &mut spec.marks {
+        if mark.type_ == "group" {
+            let mut nested_scope = scope.clone();
+            nested_scope.push(group_index);
+            retain_needed_group(mark, nested_scope, needed);
+            group_index += 1;
+        }
+    }
+}
+
+fn retain_needed_group(group: &mut MarkSpec, scope: Vec<u32>, needed: &HashSet<ScopedVariable>) {
+    group
+        .data
+        .retain(|data| needed.contains(&(Variable::new_data(&data.name), scope.clone())));
+    group
+        .signals
+        .retain(|signal| needed.contains(&(Variable::new_signal(&signal.name), scope.clone())));
+
+    let mut group_index = 0;
+    for mark in &mut group.marks {
+        if mark.type_ == "group" {
+            let mut nested_scope = scope.clone();
+            nested_scope.push(group_index);
+            retain_needed_group(mark, nested_scope, needed);
+            group_index += 1;
+        }
+    }
+}
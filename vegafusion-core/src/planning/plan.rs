@@ -7,25 +7,83 @@
  * this program the details of the active license.
  */
 use crate::error::Result;
+use crate::planning::dedupe_pipelines::dedupe_pipelines;
+use crate::planning::dependency_graph::build_dependency_graph;
+use crate::planning::explain::{explain_spec, PlanExplanation};
 use crate::planning::extract::extract_server_data;
-use crate::planning::optimize_server::split_data_url_nodes;
+use crate::planning::optimize_server::{prune_unused_server_nodes, split_data_url_nodes};
 use crate::planning::projection_pushdown::projection_pushdown;
 use crate::planning::split_domain_data::split_domain_data;
 use crate::planning::stitch::{stitch_specs, CommPlan};
+use crate::planning::watch::WatchPlan;
 use crate::planning::stringify_local_datetimes::stringify_local_datetimes;
-use crate::spec::chart::ChartSpec;
+use crate::proto::gen::pretransform::PlannerWarningType;
+use crate::proto::gen::tasks::{Variable, VariableNamespace};
+use crate::spec::chart::{ChartSpec, ChartVisitor};
+use crate::spec::data::DataSpec;
+use crate::spec::signal::SignalSpec;
+use crate::task_graph::graph::ScopedVariable;
+use crate::task_graph::scope::TaskScope;
+use petgraph::visit::Dfs;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Clone, Debug)]
 pub enum PlannerWarnings {
     StringifyDatetimeMixedUsage(String),
+    LostInteractivity(LostInteractivityWarning),
 }
 
 impl PlannerWarnings {
     pub fn message(&self) -> String {
         match &self {
             PlannerWarnings::StringifyDatetimeMixedUsage(message) => message.clone(),
+            PlannerWarnings::LostInteractivity(warning) => warning.message(),
         }
     }
+
+    pub fn warning_type(&self) -> PlannerWarningType {
+        match &self {
+            PlannerWarnings::StringifyDatetimeMixedUsage(_) => {
+                PlannerWarningType::StringifyDatetimeMixedUsage
+            }
+            PlannerWarnings::LostInteractivity(_) => PlannerWarningType::LostInteractivity,
+        }
+    }
+}
+
+/// Warning that an interaction will not be reflected in server-evaluated results.
+/// Emitted when a server-evaluated dataset depends on an interactive client-side
+/// variable whose updates are not part of the communication plan
+#[derive(Clone, Debug)]
+pub struct LostInteractivityWarning {
+    /// The interactive client-side variable whose updates will not reach the server
+    pub source: ScopedVariable,
+
+    /// The server-evaluated variables sent to the client that depend on it and will
+    /// not update when it changes
+    pub affected: Vec<ScopedVariable>,
+}
+
+impl LostInteractivityWarning {
+    pub fn message(&self) -> String {
+        let source_kind = match self.source.0.namespace() {
+            VariableNamespace::Signal => "signal",
+            VariableNamespace::Data => "dataset",
+            VariableNamespace::Scale => "scale",
+        };
+        let affected: Vec<_> = self
+            .affected
+            .iter()
+            .map(|scoped_var| scoped_var.0.name.clone())
+            .collect();
+        format!(
+            "Interactions that update the {} \"{}\" will not be reflected in the \
+             server-evaluated variable(s): {}",
+            source_kind,
+            self.source.0.name,
+            affected.join(", ")
+        )
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -35,6 +93,23 @@ pub struct PlannerConfig {
     pub stringify_local_datetimes: bool,
     pub projection_pushdown: bool,
     pub extract_inline_data: bool,
+    pub dedupe_pipelines: bool,
+    pub prune_unused: bool,
+
+    /// Per-dataset overrides of planner behavior, keyed by scoped variable. These
+    /// allow working around a planner mis-classification of a single dataset without
+    /// disabling the corresponding optimization globally
+    pub dataset_overrides: HashMap<ScopedVariable, DatasetOverride>,
+
+    /// Scoped variables that must remain in the client spec even if they could be
+    /// planned for server-side evaluation (e.g. so application JS listeners keep
+    /// access to them)
+    pub keep_variables: Vec<ScopedVariable>,
+
+    /// Datasets that must be extracted to the server even when the planner's
+    /// heuristics would keep them client-side. Planning fails with an error if such
+    /// a dataset can't be evaluated on the server
+    pub force_server_datasets: Vec<ScopedVariable>,
 }
 
 impl Default for PlannerConfig {
@@ -45,10 +120,33 @@ impl Default for PlannerConfig {
             stringify_local_datetimes: false,
             projection_pushdown: true,
             extract_inline_data: false,
+            dedupe_pipelines: true,
+            prune_unused: true,
+            dataset_overrides: Default::default(),
+            keep_variables: Vec::new(),
+            force_server_datasets: Vec::new(),
         }
     }
 }
 
+/// Per-dataset planner behavior override
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DatasetOverride {
+    /// Force the dataset onto the server or the client, overriding the planner's
+    /// support classification. Server placement still requires the dataset's
+    /// dependencies to be available on the server
+    pub placement: Option<DatasetPlacement>,
+
+    /// Skip projection pushdown for this dataset, leaving all of its columns intact
+    pub disable_projection_pushdown: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DatasetPlacement {
+    Server,
+    Client,
+}
+
 pub struct SpecPlan {
     pub server_spec: ChartSpec,
     pub client_spec: ChartSpec,
@@ -58,14 +156,14 @@ pub struct SpecPlan {
 
 impl SpecPlan {
     pub fn try_new(full_spec: &ChartSpec, config: &PlannerConfig) -> Result<Self> {
-        let warnings: Vec<PlannerWarnings> = Vec::new();
+        let mut warnings: Vec<PlannerWarnings> = Vec::new();
 
         let mut client_spec = full_spec.clone();
 
         // Attempt to limit the columns produced by each dataset to only include those
         // that are actually used downstream
         if config.projection_pushdown {
-            projection_pushdown(&mut client_spec)?;
+            projection_pushdown(&mut client_spec, config)?;
         }
 
         let domain_dataset_fields = if config.split_domain_data {
@@ -79,6 +177,27 @@ impl SpecPlan {
         let mut server_spec = extract_server_data(&mut client_spec, &mut task_scope, config)?;
         let comm_plan = stitch_specs(&task_scope, &mut server_spec, &mut client_spec)?;
 
+        // Warn about interactions that won't be reflected in server results
+        warnings.extend(detect_lost_interactivity(
+            &client_spec,
+            &server_spec,
+            &comm_plan,
+            &task_scope,
+            config,
+        )?);
+
+        // Collapse identical (source, transform-prefix) pairs in the server spec into
+        // shared upstream datasets so the runtime computes the shared work once
+        if config.dedupe_pipelines {
+            dedupe_pipelines(&mut server_spec)?;
+        }
+
+        // Prune server nodes whose outputs are never consumed by the client or by
+        // other server nodes
+        if config.prune_unused {
+            prune_unused_server_nodes(&mut server_spec, &comm_plan, config)?;
+        }
+
         if config.split_url_data_nodes {
             split_data_url_nodes(&mut server_spec)?;
         }
@@ -99,4 +218,101 @@ impl SpecPlan {
             warnings,
         })
     }
+
+    /// Build a structured report of the planning decisions: which datasets and
+    /// signals were placed on the server vs the client, why client datasets fell
+    /// back to the Vega runtime, and the resulting communication plan
+    pub fn explain(&self) -> Result<PlanExplanation> {
+        let server = explain_spec(&self.server_spec, false)?;
+        let client = explain_spec(&self.client_spec, true)?;
+        Ok(PlanExplanation {
+            server_datasets: server.datasets,
+            client_datasets: client.datasets,
+            server_signals: server.signals,
+            comm_plan: WatchPlan::from(self.comm_plan.clone()),
+            warnings: self.warnings.iter().map(|warning| warning.message()).collect(),
+        })
+    }
+}
+
+/// Detect interactive client-side variables that server-evaluated datasets depend
+/// on but whose updates are not part of the communication plan. Interactions that
+/// update such variables will not be reflected in the server's results
+fn detect_lost_interactivity(
+    client_spec: &ChartSpec,
+    server_spec: &ChartSpec,
+    comm_plan: &CommPlan,
+    task_scope: &TaskScope,
+    config: &PlannerConfig,
+) -> Result<Vec<PlannerWarnings>> {
+    // Collect interactive variables defined in the client spec
+    let mut interactive_visitor = CollectInteractiveVariablesVisitor::default();
+    client_spec.walk(&mut interactive_visitor)?;
+
+    let mut warnings = Vec::new();
+    let mut dependency_graph = None;
+    for input_var in server_spec.input_vars(task_scope)? {
+        if interactive_visitor.interactive_vars.contains(&input_var)
+            && !comm_plan.client_to_server.contains(&input_var)
+        {
+            // Narrow the affected variables to the server-to-client values downstream
+            // of the input variable, when it's represented in the dependency graph
+            let graph = match &dependency_graph {
+                Some(graph) => graph,
+                None => {
+                    dependency_graph = Some(build_dependency_graph(server_spec, config)?);
+                    dependency_graph.as_ref().unwrap()
+                }
+            };
+            let mut affected = Vec::new();
+            for node_index in graph.node_indices() {
+                let (scoped_var, _) = graph.node_weight(node_index).unwrap();
+                if scoped_var == &input_var {
+                    let mut dfs = Dfs::new(graph, node_index);
+                    while let Some(descendant_index) = dfs.next(graph) {
+                        let (descendant_var, _) = graph.node_weight(descendant_index).unwrap();
+                        if comm_plan.server_to_client.contains(descendant_var) {
+                            affected.push(descendant_var.clone());
+                        }
+                    }
+                }
+            }
+            if affected.is_empty() {
+                // Not represented in the server dependency graph, so conservatively
+                // report all values sent to the client
+                affected = comm_plan.server_to_client.clone();
+            }
+
+            warnings.push(PlannerWarnings::LostInteractivity(LostInteractivityWarning {
+                source: input_var,
+                affected,
+            }));
+        }
+    }
+    Ok(warnings)
+}
+
+/// Visitor to collect the variables that interactions can update: signals with
+/// event handlers and datasets with `on` trigger blocks
+#[derive(Debug, Default)]
+struct CollectInteractiveVariablesVisitor {
+    pub interactive_vars: HashSet<ScopedVariable>,
+}
+
+impl ChartVisitor for CollectInteractiveVariablesVisitor {
+    fn visit_data(&mut self, data: &DataSpec, scope: &[u32]) -> Result<()> {
+        if data.on.is_some() {
+            self.interactive_vars
+                .insert((Variable::new_data(&data.name), Vec::from(scope)));
+        }
+        Ok(())
+    }
+
+    fn visit_signal(&mut self, signal: &SignalSpec, scope: &[u32]) -> Result<()> {
+        if !signal.on.is_empty() {
+            self.interactive_vars
+                .insert((Variable::new_signal(&signal.name), Vec::from(scope)));
+        }
+        Ok(())
+    }
 }
@@ -6,6 +6,7 @@ use crate::expression::column_usage::{
 use crate::expression::escape::{escape_field, unescape_field};
 use crate::expression::parser::parse;
 use crate::planning::dependency_graph::build_dependency_graph;
+use crate::planning::plan::PlannerConfig;
 use crate::proto::gen::tasks::{Variable, VariableNamespace};
 use crate::spec::chart::{ChartSpec, ChartVisitor, MutChartVisitor};
 use crate::spec::data::DataSpec;
@@ -21,12 +22,13 @@ use crate::task_graph::scope::TaskScope;
 use arrow::array::StringArray;
 use itertools::sorted;
 use petgraph::algo::toposort;
+use std::collections::HashSet;
 
 /// This planning phase attempts to identify the precise subset of columns that are required
 /// of each dataset. If this can be determined for a particular dataset, then a projection
 /// transform is appended to the dataset's transform array. If it cannot be determined, then
 /// no change is made.
-pub fn projection_pushdown(chart_spec: &mut ChartSpec) -> Result<()> {
+pub fn projection_pushdown(chart_spec: &mut ChartSpec, config: &PlannerConfig) -> Result<()> {
     let datum_var = None;
     let usage_scope = Vec::new();
     let task_scope = chart_spec.to_task_scope()?;
@@ -36,18 +38,151 @@ pub fn projection_pushdown(chart_spec: &mut ChartSpec) -> Result<()> {
     chart_spec.walk(&mut vl_selection_visitor)?;
     let vl_selection_fields = vl_selection_visitor.vl_selection_fields;
 
-    let datasets_column_usage = chart_spec.datasets_column_usage(
+    let mut datasets_column_usage = chart_spec.datasets_column_usage(
         &datum_var,
         usage_scope.as_slice(),
         &task_scope,
         &vl_selection_fields,
     );
 
+    // Unknown usage prevents projection insertion for datasets with pushdown
+    // disabled by an override
+    for (scoped_var, dataset_override) in &config.dataset_overrides {
+        if dataset_override.disable_projection_pushdown {
+            datasets_column_usage = datasets_column_usage.with_unknown_usage(scoped_var);
+        }
+    }
+
+    // Collect datum column usage in signal event handlers, attributed to the
+    // datasets of the chart's marks
+    let mut mark_datasets_visitor = CollectMarkSourceDatasetsVisitor::new(task_scope.clone());
+    chart_spec.walk(&mut mark_datasets_visitor)?;
+
+    let mut event_usage_visitor = CollectSignalEventDatumUsageVisitor::new(
+        mark_datasets_visitor.mark_datasets.into_iter().collect(),
+        task_scope,
+        vl_selection_fields,
+    );
+    chart_spec.walk(&mut event_usage_visitor)?;
+    let datasets_column_usage = datasets_column_usage.union(&event_usage_visitor.usage);
+
     let mut visitor = InsertProjectionVisitor::new(&datasets_column_usage);
     chart_spec.walk_mut(&mut visitor)?;
     Ok(())
 }
 
+/// Visitor to collect the scoped variables of datasets that marks draw from
+#[derive(Clone)]
+pub struct CollectMarkSourceDatasetsVisitor {
+    pub mark_datasets: HashSet<ScopedVariable>,
+    pub task_scope: TaskScope,
+}
+
+impl CollectMarkSourceDatasetsVisitor {
+    pub fn new(task_scope: TaskScope) -> Self {
+        Self {
+            mark_datasets: Default::default(),
+            task_scope,
+        }
+    }
+
+    fn process_mark(&mut self, mark: &MarkSpec, scope: &[u32]) {
+        if let Some(from) = &mark.from {
+            let mut data_names: Vec<&String> = Vec::new();
+            if let Some(data) = &from.data {
+                data_names.push(data);
+            }
+            if let Some(facet) = &from.facet {
+                data_names.push(&facet.data);
+            }
+            for data_name in data_names {
+                let data_var = Variable::new_data(data_name);
+                if let Ok(resolved) = self.task_scope.resolve_scope(&data_var, scope) {
+                    self.mark_datasets.insert((resolved.var, resolved.scope));
+                }
+            }
+        }
+    }
+}
+
+impl ChartVisitor for CollectMarkSourceDatasetsVisitor {
+    fn visit_non_group_mark(&mut self, mark: &MarkSpec, scope: &[u32]) -> Result<()> {
+        self.process_mark(mark, scope);
+        Ok(())
+    }
+
+    fn visit_group_mark(&mut self, mark: &MarkSpec, scope: &[u32]) -> Result<()> {
+        self.process_mark(mark, scope);
+        Ok(())
+    }
+}
+
+/// Visitor to collect column usage in signal event handlers: `on[].update`
+/// expressions and event filters. The datum of an event handler is an item of
+/// whichever mark the event fired on, which isn't statically known, so datum
+/// columns are attributed to the datasets of all marks in the chart. This keeps
+/// projection pushdown sound for interactive specs without giving up on the
+/// touched datasets entirely
+pub struct CollectSignalEventDatumUsageVisitor {
+    pub usage: DatasetsColumnUsage,
+    pub mark_datasets: Vec<ScopedVariable>,
+    pub task_scope: TaskScope,
+    pub vl_selection_fields: VlSelectionFields,
+}
+
+impl CollectSignalEventDatumUsageVisitor {
+    pub fn new(
+        mark_datasets: Vec<ScopedVariable>,
+        task_scope: TaskScope,
+        vl_selection_fields: VlSelectionFields,
+    ) -> Self {
+        Self {
+            usage: DatasetsColumnUsage::empty(),
+            mark_datasets,
+            task_scope,
+            vl_selection_fields,
+        }
+    }
+}
+
+impl ChartVisitor for CollectSignalEventDatumUsageVisitor {
+    fn visit_signal(&mut self, signal: &SignalSpec, scope: &[u32]) -> Result<()> {
+        for sig_on in &signal.on {
+            // Collect the update expression and any event filter expressions
+            let mut expr_strs = vec![sig_on.update.clone()];
+            for sig_event in sig_on.events.to_vec() {
+                if let SignalOnEventSpec::Source(source) = sig_event {
+                    if let Some(filter) = &source.filter {
+                        expr_strs.extend(filter.to_vec());
+                    }
+                }
+            }
+
+            for expr_str in &expr_strs {
+                match parse(expr_str) {
+                    Ok(parsed) => {
+                        for datum_var in &self.mark_datasets {
+                            self.usage = self.usage.union(&parsed.datasets_column_usage(
+                                &Some(datum_var.clone()),
+                                scope,
+                                &self.task_scope,
+                                &self.vl_selection_fields,
+                            ));
+                        }
+                    }
+                    Err(_) => {
+                        // The expression may reference datum columns we can't see
+                        for datum_var in &self.mark_datasets {
+                            self.usage = self.usage.with_unknown_usage(datum_var);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 impl GetDatasetsColumnUsage for MarkEncodingField {
     fn datasets_column_usage(
         &self,
@@ -214,14 +349,29 @@ impl GetDatasetsColumnUsage for MarkSpec {
             // Data is handled at chart-level
 
             // Handle group from->facet->name. In this case, a new dataset is named for the
-            // subsets of the input dataset. For now, this means we don't know what columns
-            // from the input dataset are used. In the future, we could track which columns of
-            // the subset datasets are used.
+            // subsets of the input dataset. For plain groupby facets the subsets have the
+            // same columns as the input dataset, so usage of the facet dataset is aliased
+            // to the input dataset, along with the groupby fields used for partitioning.
+            // For other facet forms we don't know what columns of the input are used
             if let Some(facet) = self.from.as_ref().and_then(|from| from.facet.clone()) {
                 let facet_data_var = Variable::new_data(&facet.data);
                 if let Ok(resolved) = task_scope.resolve_scope(&facet_data_var, usage_scope) {
                     let scoped_facet_data_var = (resolved.var, resolved.scope);
-                    usage = usage.with_unknown_usage(&scoped_facet_data_var);
+                    match facet.groupby_fields() {
+                        Some(fields) => {
+                            let facet_var =
+                                (Variable::new_data(&facet.name), Vec::from(usage_scope));
+                            usage = usage
+                                .with_alias(facet_var, scoped_facet_data_var.clone())
+                                .with_column_usage(
+                                    &scoped_facet_data_var,
+                                    ColumnUsage::from(fields.as_slice()),
+                                );
+                        }
+                        None => {
+                            usage = usage.with_unknown_usage(&scoped_facet_data_var);
+                        }
+                    }
                 }
             }
 
@@ -620,14 +770,16 @@ fn datasets_column_usage_for_data(
         }
     }
 
-    // Check for lookup transform and ensure that all columns are kept from the looked up
-    // dataset
+    // Check for lookup transform and ensure that the key and values columns are kept
+    // from the looked up dataset (or all columns, when values is not specified). This
+    // also covers lookups that transform_columns didn't reach because an earlier
+    // transform in the pipeline had unknown columns
     for tx in &data.transform {
         if let TransformSpec::Lookup(lookup) = tx {
             let lookup_from_var = Variable::new_data(&lookup.from);
             if let Ok(resolved) = task_scope.resolve_scope(&lookup_from_var, usage_scope) {
                 let lookup_data_var = (resolved.var, resolved.scope);
-                usage = usage.with_unknown_usage(&lookup_data_var);
+                usage = usage.with_column_usage(&lookup_data_var, lookup.from_dataset_usage());
             }
         }
     }
@@ -837,6 +989,79 @@ mod tests {
         assert_eq!(usage, expected);
     }
 
+    #[test]
+    fn test_mark_encoding_tooltip_text_usage() {
+        // Object-valued tooltips with multiple fields are analyzable, as are text
+        // channel encodings
+        let encodings: MarkEncodeSpec = serde_json::from_value(json!({
+            "update": {
+                "text": {"field": "label"},
+                "tooltip": {
+                    "signal": "{'One': datum.one, 'Two': format(datum['two'], '.2f')}"
+                },
+            }
+        }))
+        .unwrap();
+
+        let datum_var: ScopedVariable = (Variable::new_data("dataA"), Vec::new());
+        let usage_scope = Vec::new();
+        let task_scope = task_scope();
+
+        let usage = encodings.datasets_column_usage(
+            &Some(datum_var.clone()),
+            &usage_scope,
+            &task_scope,
+            &Default::default(),
+        );
+
+        let expected = DatasetsColumnUsage::empty().with_column_usage(
+            &datum_var,
+            ColumnUsage::from(vec!["label", "one", "two"].as_slice()),
+        );
+        assert_eq!(usage, expected);
+
+        // A tooltip of the whole datum (as generated for tooltips showing all
+        // fields) uses every column
+        let encodings: MarkEncodeSpec = serde_json::from_value(json!({
+            "update": {
+                "tooltip": {"signal": "datum"},
+            }
+        }))
+        .unwrap();
+
+        let usage = encodings.datasets_column_usage(
+            &Some(datum_var.clone()),
+            &usage_scope,
+            &task_scope,
+            &Default::default(),
+        );
+
+        let expected = DatasetsColumnUsage::empty().with_unknown_usage(&datum_var);
+        assert_eq!(usage, expected);
+    }
+
+    #[test]
+    fn test_nested_member_usage() {
+        // Nested member access should report usage of the top-level field only,
+        // since projection pushdown operates on top-level dataset columns
+        let expr = crate::expression::parser::parse("datum.one.sub + datum['two'].sub2").unwrap();
+
+        let datum_var: ScopedVariable = (Variable::new_data("dataA"), Vec::new());
+        let usage_scope = Vec::new();
+        let task_scope = task_scope();
+
+        let usage = expr.datasets_column_usage(
+            &Some(datum_var.clone()),
+            &usage_scope,
+            &task_scope,
+            &Default::default(),
+        );
+
+        let expected = DatasetsColumnUsage::empty()
+            .with_column_usage(&datum_var, ColumnUsage::from(vec!["one", "two"].as_slice()));
+        assert_eq!(usage, expected);
+    }
+
     #[test]
     fn test_mark_with_known_usage() {
         // Define selection dataset fields
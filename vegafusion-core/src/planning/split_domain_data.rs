@@ -14,7 +14,7 @@ use crate::spec::chart::{ChartSpec, MutChartVisitor};
 use crate::spec::data::DataSpec;
 use crate::spec::scale::{
     ScaleDataReferenceSort, ScaleDataReferenceSortParameters, ScaleDataReferenceSpec,
-    ScaleDomainSpec, ScaleSpec, ScaleTypeSpec,
+    ScaleDomainSpec, ScaleSpec,
 };
 use crate::spec::transform::aggregate::AggregateOpSpec;
 use crate::task_graph::graph::ScopedVariable;
@@ -63,7 +63,8 @@ impl<'a> SplitScaleDomainVisitor<'a> {
 
 impl<'a> MutChartVisitor for SplitScaleDomainVisitor<'a> {
     fn visit_scale(&mut self, scale: &mut ScaleSpec, scope: &[u32]) -> Result<()> {
-        let discrete_scale = scale.type_.clone().unwrap_or_default().is_discrete();
+        let scale_type = scale.type_.clone().unwrap_or_default();
+        let discrete_scale = scale_type.is_discrete();
         if let Some(ScaleDomainSpec::FieldReference(field_ref)) = &scale.domain {
             let data_name = field_ref.data.clone();
             let data_var = (Variable::new_data(&data_name), Vec::from(scope));
@@ -162,11 +163,10 @@ impl<'a> MutChartVisitor for SplitScaleDomainVisitor<'a> {
                 });
 
                 (new_data, new_domain)
-            } else if matches!(
-                scale.type_.clone().unwrap_or_default(),
-                ScaleTypeSpec::Linear
-            ) {
-                // Create derived dataset that performs the min/max calculations
+            } else if scale_type.is_numeric_continuous() || scale_type.is_temporal_continuous() {
+                // Create derived dataset that performs the min/max calculations.
+                // The unary plus coerces dates to millisecond timestamps, which
+                // temporal scale domains accept
                 let new_data: DataSpec = serde_json::from_value(serde_json::json!(
                     {
                         "name": new_data_name,
@@ -53,9 +53,12 @@ pub fn stitch_specs(
         .into_iter()
         .collect();
 
-    // Determine communication requirements
+    // Determine communication requirements.
+    // Scales are excluded: scales that reach the server are static copies of scales
+    // the client also defines, so there's never a value to communicate
     let server_to_client: HashSet<_> = client_inputs
         .intersection(&server_updates)
+        .filter(|var| var.0.namespace() != VariableNamespace::Scale)
         .cloned()
         .collect();
 
@@ -99,6 +102,13 @@ fn make_stub(
 ) -> Result<()> {
     let stub_name = stub_var.0.name.clone();
     let stub_path = stub_var.1.clone();
+
+    // Variables defined in nested group scopes need the corresponding group marks
+    // to exist in the receiving spec before a stub can be added at their scope
+    if !stub_path.is_empty() {
+        to_spec.ensure_nested_group_mut(&stub_path)?;
+    }
+
     match stub_var.0.namespace() {
         VariableNamespace::Signal => {
             // Get initial value from client spec, if any
@@ -158,6 +158,7 @@ pub enum UnaryOperator {
     Pos = 0,
     Neg = 1,
     Not = 2,
+    BitwiseNot = 3,
 }
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
@@ -181,4 +182,10 @@ pub enum BinaryOperator {
     Mult = 10,
     Div = 11,
     Mod = 12,
+    BitwiseAnd = 13,
+    BitwiseOr = 14,
+    BitwiseXor = 15,
+    LeftShift = 16,
+    RightShift = 17,
+    UnsignedRightShift = 18,
 }
@@ -42,6 +42,12 @@ pub mod pre_transform_spec_result {
     }
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PreTransformSpecBatchResult {
+    /// One result per request, in request order
+    #[prost(message, repeated, tag="1")]
+    pub results: ::prost::alloc::vec::Vec<PreTransformSpecResult>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PreTransformValuesResult {
     #[prost(oneof="pre_transform_values_result::Result", tags="1, 2")]
     pub result: ::core::option::Option<pre_transform_values_result::Result>,
@@ -56,3 +62,33 @@ pub mod pre_transform_values_result {
         Response(super::super::pretransform::PreTransformValuesResponse),
     }
 }
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PreTransformDatasetsResult {
+    #[prost(oneof="pre_transform_datasets_result::Result", tags="1, 2")]
+    pub result: ::core::option::Option<pre_transform_datasets_result::Result>,
+}
+/// Nested message and enum types in `PreTransformDatasetsResult`.
+pub mod pre_transform_datasets_result {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Result {
+        #[prost(message, tag="1")]
+        Error(super::super::errors::Error),
+        #[prost(message, tag="2")]
+        Response(super::super::pretransform::PreTransformDatasetsResponse),
+    }
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PreTransformExtractResult {
+    #[prost(oneof="pre_transform_extract_result::Result", tags="1, 2")]
+    pub result: ::core::option::Option<pre_transform_extract_result::Result>,
+}
+/// Nested message and enum types in `PreTransformExtractResult`.
+pub mod pre_transform_extract_result {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Result {
+        #[prost(message, tag="1")]
+        Error(super::super::errors::Error),
+        #[prost(message, tag="2")]
+        Response(super::super::pretransform::PreTransformExtractResponse),
+    }
+}
@@ -5,6 +5,30 @@ pub struct PreTransformSpecOpts {
     pub row_limit: ::core::option::Option<u32>,
     #[prost(message, repeated, tag="2")]
     pub inline_datasets: ::prost::alloc::vec::Vec<PreTransformInlineDataset>,
+    /// Base url used to resolve relative data urls in the spec
+    #[prost(string, optional, tag="3")]
+    pub base_url: ::core::option::Option<::prost::alloc::string::String>,
+    /// Per-dataset row limits that take precedence over row_limit
+    #[prost(message, repeated, tag="4")]
+    pub dataset_row_limits: ::prost::alloc::vec::Vec<PreTransformRowLimit>,
+    /// Signals whose definitions must be preserved in the output spec
+    #[prost(message, repeated, tag="5")]
+    pub keep_signals: ::prost::alloc::vec::Vec<PreTransformScopedName>,
+    /// Datasets whose definitions must be preserved in the output spec
+    #[prost(message, repeated, tag="6")]
+    pub keep_datasets: ::prost::alloc::vec::Vec<PreTransformScopedName>,
+    /// Initial variable values applied to the spec before planning
+    #[prost(message, repeated, tag="7")]
+    pub initial_values: ::prost::alloc::vec::Vec<PreTransformInitialValue>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PreTransformRowLimit {
+    #[prost(message, optional, tag="1")]
+    pub variable: ::core::option::Option<super::tasks::Variable>,
+    #[prost(uint32, repeated, tag="2")]
+    pub scope: ::prost::alloc::vec::Vec<u32>,
+    #[prost(uint32, tag="3")]
+    pub row_limit: u32,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PreTransformSpecRequest {
@@ -47,6 +71,22 @@ pub mod pre_transform_spec_warning {
 pub struct PreTransformRowLimitWarning {
     #[prost(message, repeated, tag="1")]
     pub datasets: ::prost::alloc::vec::Vec<super::tasks::Variable>,
+    /// Per-dataset truncation details
+    #[prost(message, repeated, tag="2")]
+    pub limited: ::prost::alloc::vec::Vec<PreTransformRowLimitDataset>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PreTransformRowLimitDataset {
+    #[prost(message, optional, tag="1")]
+    pub variable: ::core::option::Option<super::tasks::Variable>,
+    #[prost(uint32, repeated, tag="2")]
+    pub scope: ::prost::alloc::vec::Vec<u32>,
+    /// Number of rows in the full dataset before truncation
+    #[prost(uint64, tag="3")]
+    pub total_rows: u64,
+    /// Row limit that was applied
+    #[prost(uint32, tag="4")]
+    pub row_limit: u32,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PreTransformBrokenInteractivityWarning {
@@ -56,6 +96,12 @@ pub struct PreTransformBrokenInteractivityWarning {
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PreTransformUnsupportedWarning {
 }
+//// Pre transform spec batch messages
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PreTransformSpecBatchRequest {
+    #[prost(message, repeated, tag="1")]
+    pub requests: ::prost::alloc::vec::Vec<PreTransformSpecRequest>,
+}
 //// Pre transform value messages
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PreTransformVariable {
@@ -70,6 +116,9 @@ pub struct PreTransformValuesOpts {
     pub variables: ::prost::alloc::vec::Vec<PreTransformVariable>,
     #[prost(message, repeated, tag="2")]
     pub inline_datasets: ::prost::alloc::vec::Vec<PreTransformInlineDataset>,
+    /// Base url used to resolve relative data urls in the spec
+    #[prost(string, optional, tag="3")]
+    pub base_url: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PreTransformValuesRequest {
@@ -102,8 +151,132 @@ pub mod pre_transform_values_warning {
         Planner(super::PlannerWarning),
     }
 }
+//// Pre transform dataset messages
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PreTransformDatasetsOpts {
+    #[prost(message, repeated, tag="1")]
+    pub variables: ::prost::alloc::vec::Vec<PreTransformVariable>,
+    #[prost(message, repeated, tag="2")]
+    pub inline_datasets: ::prost::alloc::vec::Vec<PreTransformInlineDataset>,
+    /// Base url used to resolve relative data urls in the spec
+    #[prost(string, optional, tag="3")]
+    pub base_url: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PreTransformDatasetsRequest {
+    #[prost(string, tag="1")]
+    pub spec: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub local_tz: ::prost::alloc::string::String,
+    #[prost(string, optional, tag="3")]
+    pub default_input_tz: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(message, optional, tag="4")]
+    pub opts: ::core::option::Option<PreTransformDatasetsOpts>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PreTransformDatasetsResponse {
+    #[prost(message, repeated, tag="1")]
+    pub datasets: ::prost::alloc::vec::Vec<super::tasks::ResponseTaskValue>,
+    #[prost(message, repeated, tag="2")]
+    pub warnings: ::prost::alloc::vec::Vec<PreTransformDatasetsWarning>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PreTransformDatasetsWarning {
+    #[prost(oneof="pre_transform_datasets_warning::WarningType", tags="1")]
+    pub warning_type: ::core::option::Option<pre_transform_datasets_warning::WarningType>,
+}
+/// Nested message and enum types in `PreTransformDatasetsWarning`.
+pub mod pre_transform_datasets_warning {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum WarningType {
+        #[prost(message, tag="1")]
+        Planner(super::PlannerWarning),
+    }
+}
+//// Pre transform extract messages
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PreTransformExtractOpts {
+    /// Datasets with more rows than this threshold are returned as separate Arrow
+    /// payloads rather than inlined into the spec
+    #[prost(uint32, optional, tag="1")]
+    pub extract_threshold: ::core::option::Option<u32>,
+    #[prost(message, repeated, tag="2")]
+    pub inline_datasets: ::prost::alloc::vec::Vec<PreTransformInlineDataset>,
+    /// Base url used to resolve relative data urls in the spec
+    #[prost(string, optional, tag="3")]
+    pub base_url: ::core::option::Option<::prost::alloc::string::String>,
+    /// Signals whose definitions must be preserved in the output spec
+    #[prost(message, repeated, tag="4")]
+    pub keep_signals: ::prost::alloc::vec::Vec<PreTransformScopedName>,
+    /// Datasets whose definitions must be preserved in the output spec
+    #[prost(message, repeated, tag="5")]
+    pub keep_datasets: ::prost::alloc::vec::Vec<PreTransformScopedName>,
+    /// Initial variable values applied to the spec before planning
+    #[prost(message, repeated, tag="6")]
+    pub initial_values: ::prost::alloc::vec::Vec<PreTransformInitialValue>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PreTransformExtractRequest {
+    #[prost(string, tag="1")]
+    pub spec: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub local_tz: ::prost::alloc::string::String,
+    #[prost(string, optional, tag="3")]
+    pub default_input_tz: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(message, optional, tag="4")]
+    pub opts: ::core::option::Option<PreTransformExtractOpts>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PreTransformExtractDataset {
+    #[prost(string, tag="1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(uint32, repeated, tag="2")]
+    pub scope: ::prost::alloc::vec::Vec<u32>,
+    /// Serialized Arrow record batches in Arrow IPC format
+    #[prost(bytes="vec", tag="3")]
+    pub table: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PreTransformExtractResponse {
+    #[prost(string, tag="1")]
+    pub spec: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag="2")]
+    pub datasets: ::prost::alloc::vec::Vec<PreTransformExtractDataset>,
+    #[prost(message, repeated, tag="3")]
+    pub warnings: ::prost::alloc::vec::Vec<PreTransformExtractWarning>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PreTransformExtractWarning {
+    #[prost(oneof="pre_transform_extract_warning::WarningType", tags="1")]
+    pub warning_type: ::core::option::Option<pre_transform_extract_warning::WarningType>,
+}
+/// Nested message and enum types in `PreTransformExtractWarning`.
+pub mod pre_transform_extract_warning {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum WarningType {
+        #[prost(message, tag="1")]
+        Planner(super::PlannerWarning),
+    }
+}
 //// Common pre-transform messages
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PreTransformScopedName {
+    #[prost(string, tag="1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(uint32, repeated, tag="2")]
+    pub scope: ::prost::alloc::vec::Vec<u32>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PreTransformInitialValue {
+    #[prost(message, optional, tag="1")]
+    pub variable: ::core::option::Option<super::tasks::Variable>,
+    #[prost(uint32, repeated, tag="2")]
+    pub scope: ::prost::alloc::vec::Vec<u32>,
+    /// JSON-serialized value: a signal value, or an array of rows for a dataset
+    #[prost(string, tag="3")]
+    pub value: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PreTransformInlineDataset {
     /// Inline dataset name
     #[prost(string, tag="1")]
@@ -116,4 +289,27 @@ pub struct PreTransformInlineDataset {
 pub struct PlannerWarning {
     #[prost(string, tag="1")]
     pub message: ::prost::alloc::string::String,
+    /// Structured warning category so clients can react without parsing message text
+    #[prost(enumeration="PlannerWarningType", tag="2")]
+    pub warning_type: i32,
+}
+impl PlannerWarning {
+    ///Returns the enum value of `warning_type`, or the default if the field is set to an invalid enum value.
+    pub fn warning_type(&self) -> PlannerWarningType {
+        PlannerWarningType::from_i32(self.warning_type)
+            .unwrap_or(PlannerWarningType::Unknown)
+    }
+    ///Sets `warning_type` to the provided enum value.
+    pub fn set_warning_type(&mut self, value: PlannerWarningType) {
+        self.warning_type = value as i32;
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum PlannerWarningType {
+    Unknown = 0,
+    StringifyDatetimeMixedUsage = 1,
+    LostInteractivity = 2,
+    /// Warning raised by a warn() expression function during server-side evaluation
+    Expression = 3,
 }
@@ -42,6 +42,12 @@ pub mod pre_transform_spec_result {
     }
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PreTransformSpecBatchResult {
+    /// One result per request, in request order
+    #[prost(message, repeated, tag="1")]
+    pub results: ::prost::alloc::vec::Vec<PreTransformSpecResult>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PreTransformValuesResult {
     #[prost(oneof="pre_transform_values_result::Result", tags="1, 2")]
     pub result: ::core::option::Option<pre_transform_values_result::Result>,
@@ -56,6 +62,36 @@ pub mod pre_transform_values_result {
         Response(super::super::pretransform::PreTransformValuesResponse),
     }
 }
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PreTransformDatasetsResult {
+    #[prost(oneof="pre_transform_datasets_result::Result", tags="1, 2")]
+    pub result: ::core::option::Option<pre_transform_datasets_result::Result>,
+}
+/// Nested message and enum types in `PreTransformDatasetsResult`.
+pub mod pre_transform_datasets_result {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Result {
+        #[prost(message, tag="1")]
+        Error(super::super::errors::Error),
+        #[prost(message, tag="2")]
+        Response(super::super::pretransform::PreTransformDatasetsResponse),
+    }
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PreTransformExtractResult {
+    #[prost(oneof="pre_transform_extract_result::Result", tags="1, 2")]
+    pub result: ::core::option::Option<pre_transform_extract_result::Result>,
+}
+/// Nested message and enum types in `PreTransformExtractResult`.
+pub mod pre_transform_extract_result {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Result {
+        #[prost(message, tag="1")]
+        Error(super::super::errors::Error),
+        #[prost(message, tag="2")]
+        Response(super::super::pretransform::PreTransformExtractResponse),
+    }
+}
 /// Generated client implementations.
 pub mod vega_fusion_runtime_client {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
@@ -139,6 +175,28 @@ pub mod vega_fusion_runtime_client {
             );
             self.inner.unary(request.into_request(), path, codec).await
         }
+        pub async fn task_graph_query_stream(
+            &mut self,
+            request: impl tonic::IntoRequest<super::QueryRequest>,
+        ) -> Result<
+            tonic::Response<tonic::codec::Streaming<super::QueryResult>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.VegaFusionRuntime/TaskGraphQueryStream",
+            );
+            self.inner.server_streaming(request.into_request(), path, codec).await
+        }
         pub async fn pre_transform_spec(
             &mut self,
             request: impl tonic::IntoRequest<
@@ -181,6 +239,69 @@ pub mod vega_fusion_runtime_client {
             );
             self.inner.unary(request.into_request(), path, codec).await
         }
+        pub async fn pre_transform_datasets(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::pretransform::PreTransformDatasetsRequest,
+            >,
+        ) -> Result<tonic::Response<super::PreTransformDatasetsResult>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.VegaFusionRuntime/PreTransformDatasets",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn pre_transform_spec_batch(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::pretransform::PreTransformSpecBatchRequest,
+            >,
+        ) -> Result<tonic::Response<super::PreTransformSpecBatchResult>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.VegaFusionRuntime/PreTransformSpecBatch",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn pre_transform_extract(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::super::pretransform::PreTransformExtractRequest,
+            >,
+        ) -> Result<tonic::Response<super::PreTransformExtractResult>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.VegaFusionRuntime/PreTransformExtract",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -194,6 +315,19 @@ pub mod vega_fusion_runtime_server {
             &self,
             request: tonic::Request<super::QueryRequest>,
         ) -> Result<tonic::Response<super::QueryResult>, tonic::Status>;
+        ///Server streaming response type for the TaskGraphQueryStream method.
+        type TaskGraphQueryStreamStream: futures_core::Stream<
+                Item = Result<super::QueryResult, tonic::Status>,
+            >
+            + Send
+            + 'static;
+        async fn task_graph_query_stream(
+            &self,
+            request: tonic::Request<super::QueryRequest>,
+        ) -> Result<
+            tonic::Response<Self::TaskGraphQueryStreamStream>,
+            tonic::Status,
+        >;
         async fn pre_transform_spec(
             &self,
             request: tonic::Request<super::super::pretransform::PreTransformSpecRequest>,
@@ -204,6 +338,24 @@ pub mod vega_fusion_runtime_server {
                 super::super::pretransform::PreTransformValuesRequest,
             >,
         ) -> Result<tonic::Response<super::PreTransformValuesResult>, tonic::Status>;
+        async fn pre_transform_datasets(
+            &self,
+            request: tonic::Request<
+                super::super::pretransform::PreTransformDatasetsRequest,
+            >,
+        ) -> Result<tonic::Response<super::PreTransformDatasetsResult>, tonic::Status>;
+        async fn pre_transform_extract(
+            &self,
+            request: tonic::Request<
+                super::super::pretransform::PreTransformExtractRequest,
+            >,
+        ) -> Result<tonic::Response<super::PreTransformExtractResult>, tonic::Status>;
+        async fn pre_transform_spec_batch(
+            &self,
+            request: tonic::Request<
+                super::super::pretransform::PreTransformSpecBatchRequest,
+            >,
+        ) -> Result<tonic::Response<super::PreTransformSpecBatchResult>, tonic::Status>;
     }
     #[derive(Debug)]
     pub struct VegaFusionRuntimeServer<T: VegaFusionRuntime> {
@@ -292,6 +444,47 @@ pub mod vega_fusion_runtime_server {
                     };
                     Box::pin(fut)
                 }
+                "/services.VegaFusionRuntime/TaskGraphQueryStream" => {
+                    #[allow(non_camel_case_types)]
+                    struct TaskGraphQueryStreamSvc<T: VegaFusionRuntime>(pub Arc<T>);
+                    impl<
+                        T: VegaFusionRuntime,
+                    > tonic::server::ServerStreamingService<super::QueryRequest>
+                    for TaskGraphQueryStreamSvc<T> {
+                        type Response = super::QueryResult;
+                        type ResponseStream = T::TaskGraphQueryStreamStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::QueryRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).task_graph_query_stream(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = TaskGraphQueryStreamSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/services.VegaFusionRuntime/PreTransformSpec" => {
                     #[allow(non_camel_case_types)]
                     struct PreTransformSpecSvc<T: VegaFusionRuntime>(pub Arc<T>);
@@ -378,6 +571,135 @@ pub mod vega_fusion_runtime_server {
                     };
                     Box::pin(fut)
                 }
+                "/services.VegaFusionRuntime/PreTransformDatasets" => {
+                    #[allow(non_camel_case_types)]
+                    struct PreTransformDatasetsSvc<T: VegaFusionRuntime>(pub Arc<T>);
+                    impl<
+                        T: VegaFusionRuntime,
+                    > tonic::server::UnaryService<
+                        super::super::pretransform::PreTransformDatasetsRequest,
+                    > for PreTransformDatasetsSvc<T> {
+                        type Response = super::PreTransformDatasetsResult;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::pretransform::PreTransformDatasetsRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).pre_transform_datasets(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = PreTransformDatasetsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.VegaFusionRuntime/PreTransformSpecBatch" => {
+                    #[allow(non_camel_case_types)]
+                    struct PreTransformSpecBatchSvc<T: VegaFusionRuntime>(pub Arc<T>);
+                    impl<
+                        T: VegaFusionRuntime,
+                    > tonic::server::UnaryService<
+                        super::super::pretransform::PreTransformSpecBatchRequest,
+                    > for PreTransformSpecBatchSvc<T> {
+                        type Response = super::PreTransformSpecBatchResult;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::pretransform::PreTransformSpecBatchRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).pre_transform_spec_batch(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = PreTransformSpecBatchSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.VegaFusionRuntime/PreTransformExtract" => {
+                    #[allow(non_camel_case_types)]
+                    struct PreTransformExtractSvc<T: VegaFusionRuntime>(pub Arc<T>);
+                    impl<
+                        T: VegaFusionRuntime,
+                    > tonic::server::UnaryService<
+                        super::super::pretransform::PreTransformExtractRequest,
+                    > for PreTransformExtractSvc<T> {
+                        type Response = super::PreTransformExtractResult;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::super::pretransform::PreTransformExtractRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).pre_transform_extract(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = PreTransformExtractSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         Ok(
@@ -1,8 +1,32 @@
 /// ## Task Value
+///
+/// Compression codec applied to the Arrow IPC bytes inside a TaskValue
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum DataCompression {
+    CompressionNone = 0,
+    CompressionLz4 = 1,
+    CompressionZstd = 2,
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct TaskValue {
     #[prost(oneof="task_value::Data", tags="1, 2")]
     pub data: ::core::option::Option<task_value::Data>,
+    ///
+    /// Compression applied to the IPC bytes in data
+    #[prost(enumeration="DataCompression", tag="3")]
+    pub compression: i32,
+}
+impl TaskValue {
+    ///Returns the enum value of `compression`, or the default if the field is set to an invalid enum value.
+    pub fn compression(&self) -> DataCompression {
+        DataCompression::from_i32(self.compression)
+            .unwrap_or(DataCompression::CompressionNone)
+    }
+    ///Sets `compression` to the provided enum value.
+    pub fn set_compression(&mut self, value: DataCompression) {
+        self.compression = value as i32;
+    }
 }
 /// Nested message and enum types in `TaskValue`.
 pub mod task_value {
@@ -54,6 +78,8 @@ pub struct ScanUrlFormat {
     pub delimiter: ::core::option::Option<::prost::alloc::string::String>,
     #[prost(string, optional, tag="7")]
     pub feature: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag="8")]
+    pub mesh: ::core::option::Option<::prost::alloc::string::String>,
     ///
     /// JSON encoded string:
     /// If set to auto, perform automatic type inference to determine the desired data types.
@@ -208,10 +234,63 @@ pub struct NodeValueIndex {
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct TaskGraphValueRequest {
-    #[prost(message, optional, tag="1")]
-    pub task_graph: ::core::option::Option<TaskGraph>,
     #[prost(message, repeated, tag="2")]
     pub indices: ::prost::alloc::vec::Vec<NodeValueIndex>,
+    /// Compression codecs the client can decompress, in order of preference. The
+    /// server applies the first codec it supports, or none
+    #[prost(enumeration="DataCompression", repeated, tag="3")]
+    pub accept_compression: ::prost::alloc::vec::Vec<i32>,
+    /// Value updates applied to the graph before the requested indices are
+    /// computed. For registered graphs the updates are applied to the server's
+    /// copy, so each request only needs to carry the updates since the last one
+    #[prost(message, repeated, tag="5")]
+    pub updates: ::prost::alloc::vec::Vec<TaskGraphValueUpdate>,
+    /// Maximum approximate size in bytes of a single response value. Table values
+    /// larger than this are split into multiple chunks, each carried by its own
+    /// ResponseTaskValue with chunk metadata, so transport message-size limits
+    /// aren't exceeded. Zero disables chunking
+    #[prost(uint64, tag="6")]
+    pub chunk_size: u64,
+    #[prost(oneof="task_graph_value_request::Graph", tags="1, 4")]
+    pub graph: ::core::option::Option<task_graph_value_request::Graph>,
+}
+impl TaskGraphValueRequest {
+    ///Returns an iterator which yields the valid enum values contained in `accept_compression`.
+    pub fn accept_compression(
+        &self,
+    ) -> ::core::iter::FilterMap<
+        ::core::iter::Cloned<::core::slice::Iter<i32>>,
+        fn(i32) -> ::core::option::Option<DataCompression>,
+    > {
+        self.accept_compression.iter().cloned().filter_map(DataCompression::from_i32)
+    }
+    ///Appends the provided enum value to `accept_compression`.
+    pub fn push_accept_compression(&mut self, value: DataCompression) {
+        self.accept_compression.push(value as i32);
+    }
+}
+/// Nested message and enum types in `TaskGraphValueRequest`.
+pub mod task_graph_value_request {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Graph {
+        /// Full task graph. The server registers it so that later requests may
+        /// reference it by id instead of resending it
+        #[prost(message, tag="1")]
+        TaskGraph(super::TaskGraph),
+        /// Id of a task graph registered by an earlier request, as computed by
+        /// TaskGraph::registration_id
+        #[prost(uint64, tag="4")]
+        RegisteredGraphId(u64),
+    }
+}
+/// An updated value for a value task node, mirroring a client-side
+/// TaskGraph::update_value call
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TaskGraphValueUpdate {
+    #[prost(uint32, tag="1")]
+    pub node_index: u32,
+    #[prost(message, optional, tag="2")]
+    pub value: ::core::option::Option<TaskValue>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ResponseTaskValue {
@@ -221,11 +300,54 @@ pub struct ResponseTaskValue {
     pub scope: ::prost::alloc::vec::Vec<u32>,
     #[prost(message, optional, tag="3")]
     pub value: ::core::option::Option<TaskValue>,
+    /// Set when this value is one chunk of a larger table value. Chunks for a
+    /// variable are sent in order and are concatenated by the client
+    #[prost(message, optional, tag="4")]
+    pub chunk: ::core::option::Option<ChunkMetadata>,
+}
+/// Sequence metadata for a table value split across multiple response values
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ChunkMetadata {
+    #[prost(uint32, tag="1")]
+    pub chunk_index: u32,
+    #[prost(uint32, tag="2")]
+    pub num_chunks: u32,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct TaskGraphValueResponse {
     #[prost(message, repeated, tag="1")]
     pub response_values: ::prost::alloc::vec::Vec<ResponseTaskValue>,
+    #[prost(message, repeated, tag="2")]
+    pub metrics: ::prost::alloc::vec::Vec<NodeMetrics>,
+    /// Errors for requested nodes that failed to evaluate. Values for the nodes
+    /// that succeeded are still included in response_values, so a single failing
+    /// dataset doesn't blank the entire view
+    #[prost(message, repeated, tag="3")]
+    pub node_errors: ::prost::alloc::vec::Vec<NodeError>,
+}
+/// Error produced while evaluating a single requested node
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NodeError {
+    #[prost(message, optional, tag="1")]
+    pub variable: ::core::option::Option<Variable>,
+    #[prost(uint32, repeated, tag="2")]
+    pub scope: ::prost::alloc::vec::Vec<u32>,
+    #[prost(string, tag="3")]
+    pub msg: ::prost::alloc::string::String,
+}
+/// Per-node execution metadata collected while computing a response
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NodeMetrics {
+    #[prost(message, optional, tag="1")]
+    pub variable: ::core::option::Option<Variable>,
+    #[prost(uint32, repeated, tag="2")]
+    pub scope: ::prost::alloc::vec::Vec<u32>,
+    #[prost(double, tag="3")]
+    pub duration_ms: f64,
+    #[prost(uint64, optional, tag="4")]
+    pub num_rows: ::core::option::Option<u64>,
+    #[prost(bool, tag="5")]
+    pub cache_hit: bool,
 }
 /// ## Variable
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
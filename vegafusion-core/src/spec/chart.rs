@@ -192,6 +192,33 @@ impl ChartSpec {
         Ok(group)
     }
 
+    pub fn ensure_group_mut(&mut self, group_index: u32) -> Result<&mut MarkSpec> {
+        let num_groups = self.marks.iter().filter(|m| m.type_ == "group").count() as u32;
+        for _ in num_groups..=group_index {
+            self.marks.push(MarkSpec {
+                type_: "group".to_string(),
+                ..Default::default()
+            });
+        }
+        self.get_group_mut(group_index)
+    }
+
+    /// Get the nested group mark at the given path, creating empty placeholder
+    /// groups along the way as needed. Placeholder groups are appended so that
+    /// existing group indexes (and therefore nested scopes) are preserved. This
+    /// allows planner passes to place definitions and stubs at nested scopes even
+    /// when the receiving spec does not yet mirror the corresponding group marks
+    pub fn ensure_nested_group_mut(&mut self, path: &[u32]) -> Result<&mut MarkSpec> {
+        if path.is_empty() {
+            return Err(VegaFusionError::internal("Path may not be empty"));
+        }
+        let mut group = self.ensure_group_mut(path[0])?;
+        for group_index in &path[1..] {
+            group = group.ensure_group_mut(*group_index)?;
+        }
+        Ok(group)
+    }
+
     pub fn get_nested_signal(&self, path: &[u32], name: &str) -> Result<&SignalSpec> {
         let signals = if path.is_empty() {
             &self.signals
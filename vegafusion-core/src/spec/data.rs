@@ -52,8 +52,18 @@ impl DataSpec {
     }
 
     pub fn supported(&self, extract_inline_data: bool) -> DependencyNodeSupported {
+        if self.on.is_some() {
+            // Datasets with `on` trigger blocks (insert/remove/toggle/modify) must stay on
+            // the client where the Vega runtime applies the triggers. Their values can still
+            // feed server-side datasets as task-graph updates
+            return DependencyNodeSupported::Unsupported;
+        }
+
         if let Some(Some(format_type)) = self.format.as_ref().map(|fmt| fmt.type_.clone()) {
-            if !matches!(format_type.as_str(), "csv" | "tsv" | "arrow" | "json") {
+            if !matches!(
+                format_type.as_str(),
+                "csv" | "tsv" | "arrow" | "feather" | "parquet" | "json" | "topojson"
+            ) {
                 // We don't know how to read the data, so full node is unsupported
                 return DependencyNodeSupported::Unsupported;
             }
@@ -78,7 +88,9 @@ impl DataSpec {
         let all_supported = self.transform.iter().all(|tx| tx.supported());
         if all_supported {
             DependencyNodeSupported::Supported
-        } else if self.url.is_some() {
+        } else if self.url.is_some() || self.values.is_some() {
+            // The scan (and any supported transform prefix) can still run on the
+            // server, with the client finishing the rest of the pipeline
             DependencyNodeSupported::PartiallySupported
         } else {
             match self.transform.get(0) {
@@ -155,6 +155,17 @@ impl MarkSpec {
         }
         Ok(group)
     }
+
+    pub fn ensure_group_mut(&mut self, group_index: u32) -> Result<&mut MarkSpec> {
+        let num_groups = self.marks.iter().filter(|m| m.type_ == "group").count() as u32;
+        for _ in num_groups..=group_index {
+            self.marks.push(MarkSpec {
+                type_: "group".to_string(),
+                ..Default::default()
+            });
+        }
+        self.get_group_mut(group_index)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -229,6 +240,25 @@ pub struct MarkFacetSpec {
     pub extra: HashMap<String, Value>,
 }
 
+impl MarkFacetSpec {
+    /// The facet's groupby fields, when the facet is a plain groupby partition of
+    /// its source dataset. Returns None for other facet forms (e.g. pre-faceted
+    /// aggregates), where the relationship to the source columns isn't known
+    pub fn groupby_fields(&self) -> Option<Vec<String>> {
+        if self.extra.keys().any(|key| key != "groupby") {
+            return None;
+        }
+        match self.extra.get("groupby") {
+            Some(Value::String(field)) => Some(vec![field.clone()]),
+            Some(Value::Array(fields)) => fields
+                .iter()
+                .map(|field| field.as_str().map(|field| field.to_string()))
+                .collect(),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum MarkEncodingField {
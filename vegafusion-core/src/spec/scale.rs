@@ -6,6 +6,7 @@
  * Please consult the license documentation provided alongside
  * this program the details of the active license.
  */
+use crate::spec::data::DependencyNodeSupported;
 use crate::spec::transform::aggregate::AggregateOpSpec;
 use crate::spec::values::{SignalExpressionSpec, SortOrderSpec};
 use serde::{Deserialize, Serialize};
@@ -32,6 +33,67 @@ pub struct ScaleSpec {
     pub extra: HashMap<String, Value>,
 }
 
+impl ScaleSpec {
+    /// Classify whether this scale can be evaluated on the server. Only scales whose
+    /// full state (type, domain and range) is statically known are supported; scales
+    /// with data- or signal-driven state remain on the client
+    pub fn supported(&self) -> DependencyNodeSupported {
+        if self.static_state().is_some() {
+            DependencyNodeSupported::Supported
+        } else {
+            DependencyNodeSupported::Unsupported
+        }
+    }
+
+    /// Build the JSON representation of this scale's static state, as stored in the
+    /// value task created for the scale. Returns None when the state is not fully
+    /// static (e.g. a data-driven domain), or when the scale has extra options (such
+    /// as `nice` or `padding`) that alter the mapping in ways the runtime doesn't
+    /// model
+    pub fn static_state(&self) -> Option<Value> {
+        if self.bins.is_some() || !self.extra.is_empty() {
+            return None;
+        }
+
+        // Limited to the scale types the runtime's scale functions can apply
+        let type_spec = self.type_.clone().unwrap_or_default();
+        if !matches!(
+            type_spec,
+            ScaleTypeSpec::Linear | ScaleTypeSpec::Log | ScaleTypeSpec::Band
+        ) {
+            return None;
+        }
+
+        let domain = match self.domain.as_ref()? {
+            ScaleDomainSpec::Array(elements) => static_array_values(elements)?,
+            _ => return None,
+        };
+        let range = match self.range.as_ref()? {
+            ScaleRangeSpec::Array(elements) => static_array_values(elements)?,
+            _ => return None,
+        };
+        let type_ = serde_json::to_value(type_spec).ok()?;
+
+        let mut state = serde_json::Map::new();
+        state.insert("type".to_string(), type_);
+        state.insert("domain".to_string(), Value::Array(domain));
+        state.insert("range".to_string(), Value::Array(range));
+        Some(Value::Object(state))
+    }
+}
+
+/// Extract the literal values of a scale domain or range array, returning None if
+/// any element is signal-driven
+fn static_array_values(elements: &[ScaleArrayElementSpec]) -> Option<Vec<Value>> {
+    elements
+        .iter()
+        .map(|element| match element {
+            ScaleArrayElementSpec::Value(value) => Some(value.clone()),
+            ScaleArrayElementSpec::Signal(_) => None,
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Hash, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum ScaleTypeSpec {
@@ -69,6 +131,16 @@ impl ScaleTypeSpec {
         use ScaleTypeSpec::*;
         matches!(self, Ordinal | Band | Point)
     }
+
+    pub fn is_numeric_continuous(&self) -> bool {
+        use ScaleTypeSpec::*;
+        matches!(self, Linear | Log | Pow | Sqrt | Symlog | Sequential)
+    }
+
+    pub fn is_temporal_continuous(&self) -> bool {
+        use ScaleTypeSpec::*;
+        matches!(self, Time | Utc)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
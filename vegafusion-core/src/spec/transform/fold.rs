@@ -0,0 +1,66 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use crate::expression::column_usage::{ColumnUsage, DatasetsColumnUsage, VlSelectionFields};
+use crate::spec::transform::{TransformColumns, TransformSpecTrait};
+use crate::spec::values::Field;
+use crate::task_graph::graph::ScopedVariable;
+use crate::task_graph::scope::TaskScope;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Struct that serializes to Vega spec for the fold transform.
+/// This is currently only needed to report precise column usage
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FoldTransformSpec {
+    pub fields: Vec<Field>,
+
+    #[serde(rename = "as", skip_serializing_if = "Option::is_none")]
+    pub as_: Option<Vec<String>>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl FoldTransformSpec {
+    /// The output key and value column names, defaulting to "key" and "value"
+    pub fn as_fields(&self) -> Vec<String> {
+        let as_ = self.as_.clone().unwrap_or_default();
+        vec![
+            as_.first().cloned().unwrap_or_else(|| "key".to_string()),
+            as_.get(1).cloned().unwrap_or_else(|| "value".to_string()),
+        ]
+    }
+}
+
+impl TransformSpecTrait for FoldTransformSpec {
+    fn supported(&self) -> bool {
+        false
+    }
+
+    fn transform_columns(
+        &self,
+        datum_var: &Option<ScopedVariable>,
+        _usage_scope: &[u32],
+        _task_scope: &TaskScope,
+        _vl_selection_fields: &VlSelectionFields,
+    ) -> TransformColumns {
+        if let Some(datum_var) = datum_var {
+            // Fold reads the folded fields and copies all other input columns through,
+            // adding the key and value columns
+            let fields: Vec<_> = self.fields.iter().map(|field| field.field()).collect();
+            let usage = DatasetsColumnUsage::empty()
+                .with_column_usage(datum_var, ColumnUsage::from(fields.as_slice()));
+            let produced = ColumnUsage::from(self.as_fields().as_slice());
+            TransformColumns::PassThrough { usage, produced }
+        } else {
+            TransformColumns::Unknown
+        }
+    }
+}
@@ -28,10 +28,37 @@ pub struct LookupTransformSpec {
 
     pub fields: Vec<Field>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<Field>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub values: Option<Vec<Field>>,
+
+    #[serde(rename = "as", skip_serializing_if = "Option::is_none")]
+    pub as_: Option<Vec<String>>,
+
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
 }
 
+impl LookupTransformSpec {
+    /// Columns of the looked up (secondary) dataset that the transform reads: the
+    /// key column plus the values columns. When values is not specified the full
+    /// matched record is copied, so every column of the secondary dataset is needed
+    pub fn from_dataset_usage(&self) -> ColumnUsage {
+        match &self.values {
+            Some(values) => {
+                let mut cols: Vec<_> = values.iter().map(|field| field.field()).collect();
+                if let Some(key) = &self.key {
+                    cols.push(key.field())
+                }
+                ColumnUsage::from(cols.as_slice())
+            }
+            None => ColumnUsage::Unknown,
+        }
+    }
+}
+
 impl TransformSpecTrait for LookupTransformSpec {
     fn input_vars(&self) -> Result<Vec<InputVariable>> {
         Ok(vec![InputVariable {
@@ -47,21 +74,42 @@ impl TransformSpecTrait for LookupTransformSpec {
     fn transform_columns(
         &self,
         datum_var: &Option<ScopedVariable>,
-        _usage_scope: &[u32],
-        _task_scope: &TaskScope,
+        usage_scope: &[u32],
+        task_scope: &TaskScope,
         _vl_selection_fields: &VlSelectionFields,
     ) -> TransformColumns {
         if let Some(datum_var) = datum_var {
-            // For lookup, we can tell which field(s) are needed, but we don't currently
-            // attempt to determine the output fields
+            // Key columns of the primary stream
             let fields: Vec<_> = self.fields.iter().map(|field| field.field()).collect();
-            let usage = DatasetsColumnUsage::empty()
+            let mut usage = DatasetsColumnUsage::empty()
                 .with_column_usage(datum_var, ColumnUsage::from(fields.as_slice()));
 
-            TransformColumns::PassThrough {
-                usage,
-                produced: ColumnUsage::Unknown,
+            // Columns of the looked up dataset
+            let from_var = Variable::new_data(&self.from);
+            if let Ok(resolved) = task_scope.resolve_scope(&from_var, usage_scope) {
+                let from_scoped_var = (resolved.var, resolved.scope);
+                usage = usage.with_column_usage(&from_scoped_var, self.from_dataset_usage());
             }
+
+            // Output columns default to the names of the values fields
+            let produced = match &self.values {
+                Some(values) => match &self.as_ {
+                    Some(as_) if as_.len() == values.len() => ColumnUsage::from(as_.as_slice()),
+                    Some(_) => ColumnUsage::Unknown,
+                    None => {
+                        let value_cols: Vec<_> =
+                            values.iter().map(|field| field.field()).collect();
+                        ColumnUsage::from(value_cols.as_slice())
+                    }
+                },
+                // Without values, the full matched record is copied to the field(s) in "as"
+                None => match &self.as_ {
+                    Some(as_) => ColumnUsage::from(as_.as_slice()),
+                    None => ColumnUsage::Unknown,
+                },
+            };
+
+            TransformColumns::PassThrough { usage, produced }
         } else {
             TransformColumns::Unknown
         }
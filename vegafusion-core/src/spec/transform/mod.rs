@@ -11,6 +11,7 @@ pub mod bin;
 pub mod collect;
 pub mod extent;
 pub mod filter;
+pub mod fold;
 pub mod formula;
 pub mod impute;
 pub mod joinaggregate;
@@ -29,6 +30,7 @@ use crate::expression::column_usage::{ColumnUsage, DatasetsColumnUsage, VlSelect
 use crate::spec::transform::aggregate::AggregateTransformSpec;
 use crate::spec::transform::bin::BinTransformSpec;
 use crate::spec::transform::collect::CollectTransformSpec;
+use crate::spec::transform::fold::FoldTransformSpec;
 use crate::spec::transform::formula::FormulaTransformSpec;
 use crate::spec::transform::impute::ImputeTransformSpec;
 use crate::spec::transform::joinaggregate::JoinAggregateTransformSpec;
@@ -120,6 +122,7 @@ impl Deref for TransformSpec {
             TransformSpec::Impute(t) => t,
 
             // Supported for dependency determination, not implementation
+            TransformSpec::Fold(t) => t,
             TransformSpec::Lookup(t) => t,
             TransformSpec::Sequence(t) => t,
 
@@ -131,7 +134,6 @@ impl Deref for TransformSpec {
             TransformSpec::Density(t) => t,
             TransformSpec::DotBin(t) => t,
             TransformSpec::Flatten(t) => t,
-            TransformSpec::Fold(t) => t,
             TransformSpec::Force(t) => t,
             TransformSpec::GeoJson(t) => t,
             TransformSpec::GeoPath(t) => t,
@@ -36,7 +36,6 @@ unsupported_transforms!(
     DensityTransformSpec,
     DotbinTransformSpec,
     FlattenTransformSpec,
-    FoldTransformSpec,
     ForceTransformSpec,
     GeojsonTransformSpec,
     GeopathTransformSpec,
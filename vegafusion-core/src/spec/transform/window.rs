@@ -79,6 +79,16 @@ pub enum WindowOpSpec {
     NextValue,
 }
 
+impl WindowOpSpec {
+    pub fn name(&self) -> String {
+        serde_json::to_value(self)
+            .unwrap()
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum WindowTransformOpSpec {
@@ -86,6 +96,15 @@ pub enum WindowTransformOpSpec {
     Window(WindowOpSpec),
 }
 
+impl WindowTransformOpSpec {
+    pub fn name(&self) -> String {
+        match self {
+            WindowTransformOpSpec::Aggregate(op) => op.name(),
+            WindowTransformOpSpec::Window(op) => op.name(),
+        }
+    }
+}
+
 impl TransformSpecTrait for WindowTransformSpec {
     fn supported(&self) -> bool {
         // Check for supported aggregation op
@@ -136,22 +155,23 @@ impl TransformSpecTrait for WindowTransformSpec {
         _vl_selection_fields: &VlSelectionFields,
     ) -> TransformColumns {
         if let Some(datum_var) = datum_var {
-            // Compute produced columns
-            // Only handle the case where "as" contains a list of strings with length matching ops
-            let ops = self.ops.clone();
-            let as_: Vec<_> = self
-                .as_
-                .clone()
-                .unwrap_or_default()
+            // Compute produced columns. Missing "as" entries fall back to Vega's
+            // default output names: the op name, suffixed with the field name when
+            // a field is provided (e.g. "row_number", "sum_field")
+            let as_ = self.as_.clone().unwrap_or_default();
+            let produced_cols: Vec<_> = self
+                .ops
                 .iter()
-                .cloned()
-                .collect::<Option<Vec<_>>>()
-                .unwrap_or_default();
-            let produced = if ops.len() == as_.len() {
-                ColumnUsage::from(as_.as_slice())
-            } else {
-                ColumnUsage::Unknown
-            };
+                .enumerate()
+                .map(|(i, op)| match as_.get(i).cloned().flatten() {
+                    Some(name) => name,
+                    None => match self.fields.get(i).cloned().flatten() {
+                        Some(field) => format!("{}_{}", op.name(), field.field()),
+                        None => op.name(),
+                    },
+                })
+                .collect();
+            let produced = ColumnUsage::from(produced_cols.as_slice());
 
             // Compute used columns (both groupby, fields, and sort)
             let mut usage_cols: Vec<_> = self
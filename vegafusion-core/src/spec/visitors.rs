@@ -153,12 +153,26 @@ impl<'a> ChartVisitor for MakeTasksVisitor<'a> {
                     }
                 });
 
+                let property = format
+                    .extra
+                    .get("property")
+                    .and_then(|v| v.as_str().map(String::from));
+                let feature = format
+                    .extra
+                    .get("feature")
+                    .and_then(|v| v.as_str().map(String::from));
+                let mesh = format
+                    .extra
+                    .get("mesh")
+                    .and_then(|v| v.as_str().map(String::from));
+
                 Some(ScanUrlFormat {
                     r#type: format.type_.clone(),
-                    property: None,
+                    property,
                     header: vec![],
                     delimiter: None,
-                    feature: None,
+                    feature,
+                    mesh,
                     parse,
                 })
             }
@@ -255,8 +269,19 @@ impl<'a> ChartVisitor for MakeTasksVisitor<'a> {
         Ok(())
     }
 
-    fn visit_scale(&mut self, _scale: &ScaleSpec, _scope: &[u32]) -> Result<()> {
-        unimplemented!("Scale tasks not yet supported")
+    fn visit_scale(&mut self, scale: &ScaleSpec, scope: &[u32]) -> Result<()> {
+        // The planner only places scales with fully static state on the server, so
+        // the scale's state (type, domain and range) can be stored in a value task
+        let state = scale.static_state().ok_or_else(|| {
+            VegaFusionError::internal(format!(
+                "Scale tasks are only supported for scales with static state: {:#?}",
+                scale
+            ))
+        })?;
+        let value = TaskValue::Scalar(ScalarValue::from_json(&state)?);
+        let task = Task::new_value(Variable::new_scale(&scale.name), scope, value);
+        self.tasks.push(task);
+        Ok(())
     }
 }
 
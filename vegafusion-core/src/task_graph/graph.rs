@@ -17,21 +17,38 @@ use petgraph::prelude::EdgeRef;
 use petgraph::Direction;
 use std::collections::HashMap;
 
+use crate::data::table::VegaFusionTable;
 use crate::task_graph::task_value::TaskValue;
 
 use crate::proto::gen::tasks::task::TaskKind;
 use crate::proto::gen::tasks::task_value::Data;
 use crate::proto::gen::tasks::TaskValue as ProtoTaskValue;
+use prost::Message as ProstMessage;
 use std::collections::hash_map::DefaultHasher;
 use std::convert::TryFrom;
 use std::hash::{Hash, Hasher};
 
+/// Version salt mixed into every node fingerprint so that persistent and external
+/// caches keyed by fingerprint are invalidated across VegaFusion upgrades
+const FINGERPRINT_SALT: &str = concat!("vegafusion-", env!("CARGO_PKG_VERSION"));
+
 struct PetgraphEdge {
     output_var: Option<Variable>,
 }
 
 pub type ScopedVariable = (Variable, Vec<u32>);
 
+/// Description of how a value task's table changed relative to its previous value
+#[derive(Debug, Clone)]
+pub enum ValueUpdate {
+    /// The value was replaced outright, or isn't a table
+    Replace,
+
+    /// Whole record batches were appended to the previous table value. Holds the
+    /// appended rows
+    Append(VegaFusionTable),
+}
+
 impl TaskGraph {
     pub fn new(tasks: Vec<Task>, task_scope: &TaskScope) -> Result<Self> {
         let mut graph: petgraph::graph::DiGraph<ScopedVariable, PetgraphEdge> =
@@ -212,6 +229,16 @@ impl TaskGraph {
         mapping
     }
 
+    /// Compute the id under which this graph is registered with a runtime.
+    /// The id is a deterministic hash of the encoded graph, so clients can
+    /// compute it locally without a registration round trip
+    pub fn registration_id(&self) -> u64 {
+        let mut hasher = deterministic_hash::DeterministicHasher::new(DefaultHasher::new());
+        FINGERPRINT_SALT.hash(&mut hasher);
+        self.encode_to_vec().hash(&mut hasher);
+        hasher.finish()
+    }
+
     fn init_identity_fingerprints(&mut self) -> Result<()> {
         // Compute new identity fingerprints
         let mut id_fingerprints: Vec<u64> = Vec::with_capacity(self.nodes.len());
@@ -219,6 +246,10 @@ impl TaskGraph {
             let task = node.task();
             let mut hasher = deterministic_hash::DeterministicHasher::new(DefaultHasher::new());
 
+            // Salt fingerprints with the crate version so values cached externally are
+            // invalidated across upgrades that may change transform semantics
+            FINGERPRINT_SALT.hash(&mut hasher);
+
             if let TaskKind::Value(value) = task.task_kind() {
                 // Only hash the distinction between Scalar and Table, not the value itself.
                 // The state fingerprint takes the value into account.
@@ -260,6 +291,10 @@ impl TaskGraph {
             let task = node.task();
             let mut hasher = deterministic_hash::DeterministicHasher::new(DefaultHasher::new());
 
+            // Salt fingerprints with the crate version so values cached externally are
+            // invalidated across upgrades that may change transform semantics
+            FINGERPRINT_SALT.hash(&mut hasher);
+
             if matches!(task.task_kind(), TaskKind::Value(_)) {
                 // Hash the task with inline TaskValue
                 task.hash(&mut hasher);
@@ -300,6 +335,19 @@ impl TaskGraph {
         node_index: usize,
         value: TaskValue,
     ) -> Result<Vec<NodeValueIndex>> {
+        let (node_value_indexes, _) = self.update_value_with_delta(node_index, value)?;
+        Ok(node_value_indexes)
+    }
+
+    /// Like update_value, but additionally reports how the value changed relative
+    /// to the previous value. When whole record batches were appended to a table
+    /// value (streaming data), the appended rows are returned so callers can
+    /// recompute downstream aggregations incrementally
+    pub fn update_value_with_delta(
+        &mut self,
+        node_index: usize,
+        value: TaskValue,
+    ) -> Result<(Vec<NodeValueIndex>, ValueUpdate)> {
         let mut node = self
             .nodes
             .get_mut(node_index)
@@ -310,6 +358,29 @@ impl TaskGraph {
             ));
         }
 
+        // Detect whether the new value appends whole record batches to the previous
+        // table value
+        let update = match (node.task().task_kind(), &value) {
+            (TaskKind::Value(old_value), TaskValue::Table(new_table)) => {
+                match TaskValue::try_from(old_value) {
+                    Ok(TaskValue::Table(old_table))
+                        if old_table.schema == new_table.schema
+                            && old_table.batches.len() <= new_table.batches.len()
+                            && old_table.batches[..]
+                                == new_table.batches[..old_table.batches.len()] =>
+                    {
+                        let appended = new_table.batches[old_table.batches.len()..].to_vec();
+                        ValueUpdate::Append(VegaFusionTable::try_new(
+                            new_table.schema.clone(),
+                            appended,
+                        )?)
+                    }
+                    _ => ValueUpdate::Replace,
+                }
+            }
+            _ => ValueUpdate::Replace,
+        };
+
         node.task = Some(Task {
             variable: node.task().variable.clone(),
             scope: node.task().scope.clone(),
@@ -335,7 +406,7 @@ impl TaskGraph {
                 ));
             }
         }
-        Ok(node_value_indexes)
+        Ok((node_value_indexes, update))
     }
 
     pub fn parent_nodes(&self, node_index: usize) -> Result<Vec<&TaskNode>> {
@@ -391,6 +462,93 @@ impl TaskGraph {
             .get(node_index)
             .with_context(|| format!("Node index {} out of bounds", node_index))
     }
+
+    /// Render the graph structure in Graphviz DOT format for debugging what the
+    /// planner produced
+    pub fn to_dot(&self) -> String {
+        let mut lines: Vec<String> = vec![
+            "digraph TaskGraph {".to_string(),
+            "  rankdir=LR;".to_string(),
+            "  node [shape=box];".to_string(),
+        ];
+
+        for (node_index, node) in self.nodes.iter().enumerate() {
+            let task = node.task();
+            lines.push(format!(
+                "  n{} [label=\"{}:{}{:?}\\n{}\"];",
+                node_index,
+                format!("{:?}", task.variable().namespace()).to_lowercase(),
+                task.variable().name,
+                task.scope,
+                task_kind_name(task.task_kind()),
+            ));
+        }
+
+        for (node_index, node) in self.nodes.iter().enumerate() {
+            for edge in &node.outgoing {
+                let style = if edge.propagate { "solid" } else { "dashed" };
+                lines.push(format!(
+                    "  n{} -> n{} [style={}];",
+                    node_index, edge.target, style
+                ));
+            }
+        }
+
+        lines.push("}".to_string());
+        lines.join("\n")
+    }
+
+    /// Summarize the graph structure as a JSON value with one entry per node and
+    /// edge
+    pub fn to_json_summary(&self) -> serde_json::Value {
+        let nodes: Vec<_> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(node_index, node)| {
+                let task = node.task();
+                serde_json::json!({
+                    "index": node_index,
+                    "name": task.variable().name.clone(),
+                    "namespace": format!("{:?}", task.variable().namespace()).to_lowercase(),
+                    "scope": task.scope.clone(),
+                    "task_kind": task_kind_name(task.task_kind()),
+                    "id_fingerprint": node.id_fingerprint,
+                    "state_fingerprint": node.state_fingerprint,
+                })
+            })
+            .collect();
+
+        let edges: Vec<_> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .flat_map(|(node_index, node)| {
+                node.outgoing.iter().map(move |edge| {
+                    serde_json::json!({
+                        "source": node_index,
+                        "target": edge.target,
+                        "propagate": edge.propagate,
+                    })
+                })
+            })
+            .collect();
+
+        serde_json::json!({
+            "nodes": nodes,
+            "edges": edges,
+        })
+    }
+}
+
+fn task_kind_name(task_kind: &TaskKind) -> &'static str {
+    match task_kind {
+        TaskKind::Value(_) => "value",
+        TaskKind::DataValues(_) => "data_values",
+        TaskKind::DataUrl(_) => "data_url",
+        TaskKind::DataSource(_) => "data_source",
+        TaskKind::Signal(_) => "signal",
+    }
 }
 
 impl NodeValueIndex {
@@ -130,7 +130,7 @@ impl Task {
             TaskKind::DataUrl(task) => task.output_vars(),
             TaskKind::DataSource(task) => task.output_vars(),
             TaskKind::DataValues(task) => task.output_vars(),
-            TaskKind::Signal(_) => Vec::new(),
+            TaskKind::Signal(task) => task.output_vars(),
         }
     }
 }
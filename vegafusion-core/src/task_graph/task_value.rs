@@ -8,12 +8,16 @@
  */
 use crate::data::scalar::{ScalarValue, ScalarValueHelpers};
 use crate::data::table::VegaFusionTable;
-use crate::error::{Result, ResultWithContext, VegaFusionError};
+use crate::error::{Result, ResultWithContext, ToExternalError, VegaFusionError};
 use crate::proto::gen::tasks::task_value::Data;
-use crate::proto::gen::tasks::{TaskGraphValueResponse, TaskValue as ProtoTaskValue, Variable};
+use crate::proto::gen::tasks::{
+    DataCompression, TaskGraphValueResponse, TaskValue as ProtoTaskValue, Variable,
+};
 use crate::task_graph::memory::{inner_size_of_scalar, inner_size_of_table};
 use arrow::record_batch::RecordBatch;
 use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::convert::TryFrom;
 
 #[derive(Debug, Clone)]
@@ -37,10 +41,17 @@ impl TaskValue {
         }
     }
 
+    pub fn into_table(self) -> Result<VegaFusionTable> {
+        match self {
+            TaskValue::Table(value) => Ok(value),
+            _ => Err(VegaFusionError::internal("Value is not a table")),
+        }
+    }
+
     pub fn to_json(&self) -> Result<Value> {
         match self {
             TaskValue::Scalar(value) => value.to_json(),
-            TaskValue::Table(value) => Ok(value.to_json()),
+            TaskValue::Table(value) => value.try_to_json(),
         }
     }
 
@@ -52,16 +63,79 @@ impl TaskValue {
 
         std::mem::size_of::<Self>() + inner_size
     }
+
+    /// Like `ProtoTaskValue::try_from`, but compresses the IPC bytes with the
+    /// given codec to cut transfer sizes for large tables
+    pub fn to_compressed_proto(&self, compression: DataCompression) -> Result<ProtoTaskValue> {
+        let mut proto = ProtoTaskValue::try_from(self)?;
+        if !matches!(compression, DataCompression::CompressionNone) {
+            proto.data = Some(match proto.data.unwrap() {
+                Data::Scalar(bytes) => Data::Scalar(compress_ipc_bytes(bytes, compression)?),
+                Data::Table(bytes) => Data::Table(compress_ipc_bytes(bytes, compression)?),
+            });
+            proto.set_compression(compression);
+        }
+        Ok(proto)
+    }
+}
+
+fn compress_ipc_bytes(bytes: Vec<u8>, compression: DataCompression) -> Result<Vec<u8>> {
+    match compression {
+        DataCompression::CompressionNone => Ok(bytes),
+        DataCompression::CompressionLz4 => Ok(lz4_flex::compress_prepend_size(&bytes)),
+        DataCompression::CompressionZstd => {
+            #[cfg(feature = "zstd_support")]
+            {
+                zstd::stream::encode_all(bytes.as_slice(), 0)
+                    .external("Failed to compress IPC bytes with zstd")
+            }
+            #[cfg(not(feature = "zstd_support"))]
+            {
+                Err(VegaFusionError::internal(
+                    "zstd compression support is not enabled",
+                ))
+            }
+        }
+    }
+}
+
+fn decompress_ipc_bytes(bytes: &[u8], compression: DataCompression) -> Result<Cow<'_, [u8]>> {
+    match compression {
+        DataCompression::CompressionNone => Ok(Cow::Borrowed(bytes)),
+        DataCompression::CompressionLz4 => Ok(Cow::Owned(
+            lz4_flex::decompress_size_prepended(bytes)
+                .external("Failed to decompress lz4 IPC bytes")?,
+        )),
+        DataCompression::CompressionZstd => {
+            #[cfg(feature = "zstd_support")]
+            {
+                Ok(Cow::Owned(
+                    zstd::stream::decode_all(bytes)
+                        .external("Failed to decompress zstd IPC bytes")?,
+                ))
+            }
+            #[cfg(not(feature = "zstd_support"))]
+            {
+                Err(VegaFusionError::internal(
+                    "zstd compression support is not enabled",
+                ))
+            }
+        }
+    }
 }
 
 impl TryFrom<&ProtoTaskValue> for TaskValue {
     type Error = VegaFusionError;
 
     fn try_from(value: &ProtoTaskValue) -> std::result::Result<Self, Self::Error> {
+        let compression = value.compression();
         match value.data.as_ref().unwrap() {
-            Data::Table(value) => Ok(Self::Table(VegaFusionTable::from_ipc_bytes(value)?)),
+            Data::Table(value) => Ok(Self::Table(VegaFusionTable::from_ipc_bytes(
+                &decompress_ipc_bytes(value, compression)?,
+            )?)),
             Data::Scalar(value) => {
-                let scalar_table = VegaFusionTable::from_ipc_bytes(value)?;
+                let scalar_table =
+                    VegaFusionTable::from_ipc_bytes(&decompress_ipc_bytes(value, compression)?)?;
                 let scalar_rb = scalar_table.to_record_batch()?;
                 let scalar_array = scalar_rb.column(0);
                 let scalar = ScalarValue::try_from_array(scalar_array, 0)?;
@@ -82,35 +156,207 @@ impl TryFrom<&TaskValue> for ProtoTaskValue {
                 let ipc_bytes = VegaFusionTable::from(scalar_rb).to_ipc_bytes()?;
                 Ok(Self {
                     data: Some(Data::Scalar(ipc_bytes)),
+                    compression: DataCompression::CompressionNone as i32,
                 })
             }
             TaskValue::Table(table) => Ok(Self {
                 data: Some(Data::Table(table.to_ipc_bytes()?)),
+                compression: DataCompression::CompressionNone as i32,
             }),
         }
     }
 }
 
 impl TaskGraphValueResponse {
+    /// Deserialize response values into task values. Table values that were split
+    /// into chunks (see ChunkMetadata) are reassembled. All chunks must be
+    /// contained in this response; use ChunkReassembler directly to reassemble
+    /// chunks that are spread across several streamed responses
     pub fn deserialize(self) -> Result<Vec<(Variable, Vec<u32>, TaskValue)>> {
-        self.response_values
-            .into_iter()
-            .map(|response_value| {
-                let variable = response_value
-                    .variable
-                    .with_context(|| "Unwrap failed for variable of response value".to_string())?;
-
-                let scope = response_value.scope;
-                let proto_value = response_value.value.with_context(|| {
-                    "Unwrap failed for value of response value: {:?}".to_string()
-                })?;
-
-                let value = TaskValue::try_from(&proto_value).with_context(|| {
-                    "Deserialization failed for value of response value: {:?}".to_string()
-                })?;
-
-                Ok((variable, scope, value))
-            })
-            .collect::<Result<Vec<_>>>()
+        let mut reassembler = ChunkReassembler::new();
+        let results = reassembler.apply(self)?;
+        reassembler.finish()?;
+        Ok(results)
+    }
+}
+
+/// Reassembles table values that were split into chunks (see ChunkMetadata),
+/// potentially across several TaskGraphValueResponse messages as produced by the
+/// streaming query method. Pending chunks are keyed by variable and scope, so
+/// interleaved chunks of different variables reassemble correctly. Out-of-order
+/// chunks are reported as errors rather than dropped
+#[derive(Debug, Clone, Default)]
+pub struct ChunkReassembler {
+    pending: HashMap<(Variable, Vec<u32>), Vec<VegaFusionTable>>,
+}
+
+impl ChunkReassembler {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Apply the response values of a message, returning the task values that are
+    /// complete. Non-chunked values are returned directly, and chunked values are
+    /// returned when their final chunk is applied
+    pub fn apply(
+        &mut self,
+        response: TaskGraphValueResponse,
+    ) -> Result<Vec<(Variable, Vec<u32>, TaskValue)>> {
+        let mut results: Vec<(Variable, Vec<u32>, TaskValue)> = Vec::new();
+
+        for response_value in response.response_values {
+            let variable = response_value
+                .variable
+                .with_context(|| "Unwrap failed for variable of response value".to_string())?;
+
+            let scope = response_value.scope;
+            let proto_value = response_value
+                .value
+                .with_context(|| "Unwrap failed for value of response value: {:?}".to_string())?;
+
+            let value = TaskValue::try_from(&proto_value).with_context(|| {
+                "Deserialization failed for value of response value: {:?}".to_string()
+            })?;
+
+            match response_value.chunk {
+                None => results.push((variable, scope, value)),
+                Some(chunk) => {
+                    let key = (variable, scope);
+                    let pending = self.pending.entry(key.clone()).or_default();
+                    if chunk.chunk_index as usize != pending.len() {
+                        return Err(VegaFusionError::internal(format!(
+                            "Expected chunk {} of value {} with scope {:?}, \
+                             received chunk {}",
+                            pending.len(),
+                            key.0.name,
+                            key.1,
+                            chunk.chunk_index
+                        )));
+                    }
+
+                    pending.push(value.as_table()?.clone());
+                    if chunk.chunk_index + 1 == chunk.num_chunks {
+                        let chunks = self.pending.remove(&key).unwrap();
+                        let schema = chunks[0].schema.clone();
+                        let batches: Vec<_> =
+                            chunks.into_iter().flat_map(|table| table.batches).collect();
+                        let (variable, scope) = key;
+                        results.push((
+                            variable,
+                            scope,
+                            TaskValue::Table(VegaFusionTable::try_new(schema, batches)?),
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Check that no chunked value is awaiting further chunks. Call after the
+    /// final message of a stream to turn missing chunks into an error rather
+    /// than silently dropping the partial value
+    pub fn finish(self) -> Result<()> {
+        if let Some(((variable, scope), chunks)) = self.pending.into_iter().next() {
+            return Err(VegaFusionError::internal(format!(
+                "Received {} chunks of value {} with scope {:?}, \
+                 but the final chunk is missing",
+                chunks.len(),
+                variable.name,
+                scope
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::gen::tasks::{ChunkMetadata, ResponseTaskValue};
+    use serde_json::json;
+
+    /// Response value holding one chunk of the table value of a data variable
+    fn chunk_value(
+        name: &str,
+        chunk_index: u32,
+        num_chunks: u32,
+        rows: &Value,
+    ) -> ResponseTaskValue {
+        let table = VegaFusionTable::from_json(rows, 1024).unwrap();
+        ResponseTaskValue {
+            variable: Some(Variable::new_data(name)),
+            scope: Vec::new(),
+            value: Some(ProtoTaskValue::try_from(&TaskValue::Table(table)).unwrap()),
+            chunk: Some(ChunkMetadata {
+                chunk_index,
+                num_chunks,
+            }),
+        }
+    }
+
+    fn response(response_values: Vec<ResponseTaskValue>) -> TaskGraphValueResponse {
+        TaskGraphValueResponse {
+            response_values,
+            metrics: Vec::new(),
+            node_errors: Vec::new(),
+        }
+    }
+
+    fn rows_of(value: &TaskValue) -> Value {
+        value.as_table().unwrap().try_to_json().unwrap()
+    }
+
+    #[test]
+    fn test_reassemble_interleaved_chunks() {
+        let mut reassembler = ChunkReassembler::new();
+
+        // First message holds the initial chunk of each variable, so neither
+        // value is complete yet
+        let results = reassembler
+            .apply(response(vec![
+                chunk_value("a", 0, 2, &json!([{"x": 1}])),
+                chunk_value("b", 0, 2, &json!([{"y": 10}])),
+            ]))
+            .unwrap();
+        assert!(results.is_empty());
+
+        // Second message completes both values, in the opposite order
+        let results = reassembler
+            .apply(response(vec![
+                chunk_value("b", 1, 2, &json!([{"y": 20}])),
+                chunk_value("a", 1, 2, &json!([{"x": 2}])),
+            ]))
+            .unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, Variable::new_data("b"));
+        assert_eq!(rows_of(&results[0].2), json!([{"y": 10}, {"y": 20}]));
+        assert_eq!(results[1].0, Variable::new_data("a"));
+        assert_eq!(rows_of(&results[1].2), json!([{"x": 1}, {"x": 2}]));
+
+        reassembler.finish().unwrap();
+    }
+
+    #[test]
+    fn test_out_of_order_chunk_is_an_error() {
+        let mut reassembler = ChunkReassembler::new();
+        let result = reassembler.apply(response(vec![chunk_value(
+            "a",
+            1,
+            2,
+            &json!([{"x": 2}]),
+        )]));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_missing_final_chunk_is_an_error() {
+        let mut reassembler = ChunkReassembler::new();
+        reassembler
+            .apply(response(vec![chunk_value("a", 0, 2, &json!([{"x": 1}]))]))
+            .unwrap();
+        assert!(reassembler.finish().is_err());
     }
 }
@@ -101,6 +101,8 @@ impl PyTaskGraphRuntime {
                 &local_tz,
                 &default_input_tz,
                 row_limit,
+                &Default::default(),
+                &Vec::new(),
                 inline_datasets,
             ))?;
 
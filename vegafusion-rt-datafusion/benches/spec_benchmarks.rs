@@ -3,7 +3,9 @@ use vegafusion_core::planning::plan::SpecPlan;
 use vegafusion_core::planning::watch::ExportUpdateBatch;
 use vegafusion_core::proto::gen::services::query_request::Request;
 use vegafusion_core::proto::gen::services::{QueryRequest, QueryResult};
-use vegafusion_core::proto::gen::tasks::{TaskGraph, TaskGraphValueRequest, TzConfig, Variable};
+use vegafusion_core::proto::gen::tasks::{
+    task_graph_value_request, TaskGraph, TaskGraphValueRequest, TzConfig, Variable,
+};
 use vegafusion_core::spec::chart::ChartSpec;
 
 use vegafusion_rt_datafusion::task_graph::runtime::TaskGraphRuntime;
@@ -64,8 +66,13 @@ async fn eval_spec_get_variable(full_spec: ChartSpec, var: &ScopedVariable) -> Q
     // Make Query request
     let request = QueryRequest {
         request: Some(Request::TaskGraphValues(TaskGraphValueRequest {
-            task_graph: Some(task_graph.clone()),
+            graph: Some(task_graph_value_request::Graph::TaskGraph(
+                task_graph.clone(),
+            )),
             indices: vec![node_index.clone()],
+            accept_compression: Vec::new(),
+            updates: Vec::new(),
+            chunk_size: 0,
         })),
     };
 
@@ -115,8 +122,13 @@ async fn eval_spec_sequence(full_spec: ChartSpec, full_updates: Vec<ExportUpdate
     // Make Query request
     let request = QueryRequest {
         request: Some(Request::TaskGraphValues(TaskGraphValueRequest {
-            task_graph: Some(task_graph.clone()),
+            graph: Some(task_graph_value_request::Graph::TaskGraph(
+                task_graph.clone(),
+            )),
             indices: query_indices,
+            accept_compression: Vec::new(),
+            updates: Vec::new(),
+            chunk_size: 0,
         })),
     };
     let _response = runtime.query_request(request).await.unwrap();
@@ -134,8 +146,13 @@ async fn eval_spec_sequence(full_spec: ChartSpec, full_updates: Vec<ExportUpdate
         // Make Query request
         let request = QueryRequest {
             request: Some(Request::TaskGraphValues(TaskGraphValueRequest {
-                task_graph: Some(task_graph.clone()),
+                graph: Some(task_graph_value_request::Graph::TaskGraph(
+                    task_graph.clone(),
+                )),
                 indices: query_indices,
+                accept_compression: Vec::new(),
+                updates: Vec::new(),
+                chunk_size: 0,
             })),
         };
         let _response = runtime.query_request(request).await.unwrap();
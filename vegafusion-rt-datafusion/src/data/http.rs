@@ -0,0 +1,284 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use bytes::Bytes;
+use lru::LruCache;
+use reqwest::header::{
+    HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_ENCODING, COOKIE, ETAG,
+    IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use reqwest::StatusCode;
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+use vegafusion_core::error::{Result, ResultWithContext, ToExternalError};
+
+/// Runtime-level configuration applied to HTTP requests made when loading
+/// remote url datasets. This makes it possible for charts to read data from
+/// authenticated internal APIs
+#[derive(Debug, Clone, Default)]
+pub struct HttpConfig {
+    /// Base url used to resolve relative data urls, matching vega-loader's
+    /// `baseURL` option
+    pub base_url: Option<String>,
+
+    /// Custom headers included with every request
+    pub headers: HashMap<String, String>,
+
+    /// Bearer token, sent as an `Authorization: Bearer <token>` header
+    pub bearer_token: Option<String>,
+
+    /// Cookies, sent as a `Cookie` header
+    pub cookies: Option<String>,
+
+    /// Proxy url applied to all requests (e.g. `http://proxy.example.com:8080`)
+    pub proxy: Option<String>,
+
+    /// Time-to-live for cached HTTP responses. Expired entries are revalidated
+    /// with conditional requests (If-None-Match / If-Modified-Since) so unchanged
+    /// data isn't re-downloaded. None disables response caching
+    pub cache_ttl: Option<Duration>,
+
+    /// Retry behavior for requests that fail with transient errors
+    pub retry: RetryConfig,
+}
+
+/// Retry with exponential backoff for requests that fail with a network error
+/// or a retryable status code
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    /// Maximum number of attempts per request. A value of 1 disables retries
+    pub max_attempts: u32,
+
+    /// Backoff delay before the first retry. The delay doubles after each
+    /// failed attempt
+    pub initial_backoff: Duration,
+
+    /// Upper bound on the backoff delay
+    pub max_backoff: Duration,
+
+    /// Random jitter fraction applied to each backoff delay. A value of 0.1
+    /// extends each delay by up to 10%
+    pub jitter: f64,
+
+    /// HTTP status codes that trigger a retry
+    pub retryable_status_codes: Vec<u16>,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(10),
+            jitter: 0.1,
+            retryable_status_codes: vec![408, 429, 500, 502, 503, 504],
+        }
+    }
+}
+
+/// Apply a random jitter fraction to a backoff delay. The subsecond nanos of the
+/// system clock are used as a cheap source of randomness rather than pulling in
+/// a dependency for a single jitter factor
+fn with_jitter(backoff: Duration, jitter: f64) -> Duration {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos();
+    let unit = nanos as f64 / 1e9;
+    backoff.mul_f64(1.0 + jitter * unit)
+}
+
+lazy_static! {
+    static ref HTTP_CONFIG: RwLock<HttpConfig> = RwLock::new(HttpConfig::default());
+}
+
+/// Replace the process-wide HTTP configuration used for data url requests
+pub fn set_http_config(config: HttpConfig) {
+    let mut guard = HTTP_CONFIG.write().unwrap();
+    *guard = config;
+}
+
+/// Get a copy of the current process-wide HTTP configuration
+pub fn get_http_config() -> HttpConfig {
+    HTTP_CONFIG.read().unwrap().clone()
+}
+
+/// Build a reqwest Client with the current HTTP configuration applied
+pub fn make_http_client() -> Result<reqwest::Client> {
+    let config = get_http_config();
+
+    let mut headers = HeaderMap::new();
+    for (name, value) in &config.headers {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .external(&format!("Invalid HTTP header name: {}", name))?;
+        let value = HeaderValue::from_str(value)
+            .external(&format!("Invalid HTTP header value for {}", name))?;
+        headers.insert(name, value);
+    }
+
+    if let Some(bearer_token) = &config.bearer_token {
+        let value = HeaderValue::from_str(&format!("Bearer {}", bearer_token))
+            .external("Invalid bearer token")?;
+        headers.insert(AUTHORIZATION, value);
+    }
+
+    if let Some(cookies) = &config.cookies {
+        let value = HeaderValue::from_str(cookies).external("Invalid cookies")?;
+        headers.insert(COOKIE, value);
+    }
+
+    let mut builder = reqwest::Client::builder().default_headers(headers);
+
+    if let Some(proxy) = &config.proxy {
+        let proxy = reqwest::Proxy::all(proxy)
+            .external(&format!("Invalid proxy url: {}", proxy))?;
+        builder = builder.proxy(proxy);
+    }
+
+    builder.build().external("Failed to build HTTP client")
+}
+
+/// Number of url responses to retain in the response cache
+const RESPONSE_CACHE_CAPACITY: usize = 32;
+
+#[derive(Debug, Clone)]
+struct CachedResponse {
+    bytes: Bytes,
+    content_encoding: Option<String>,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    fetched_at: Instant,
+}
+
+lazy_static! {
+    static ref RESPONSE_CACHE: Mutex<LruCache<String, CachedResponse>> =
+        Mutex::new(LruCache::new(RESPONSE_CACHE_CAPACITY));
+}
+
+/// Remove the cached HTTP response for a url (if any), forcing the next request
+/// to re-download it
+pub fn remove_cached_response(url: &str) {
+    RESPONSE_CACHE.lock().unwrap().pop(&url.to_string());
+}
+
+/// Perform a GET request, optionally conditional on the cached ETag / Last-Modified
+/// validators. Returns None when the server responds 304 Not Modified
+async fn request_url(
+    url: &str,
+    etag: Option<&str>,
+    last_modified: Option<&str>,
+) -> Result<Option<CachedResponse>> {
+    let retry = get_http_config().retry;
+    let client = make_http_client()?;
+
+    let mut backoff = retry.initial_backoff;
+    let mut attempt: u32 = 1;
+    let response = loop {
+        let mut request = client.get(url);
+        if let Some(etag) = etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+
+        let result = request.send().await;
+        let retryable = match &result {
+            Ok(response) => retry
+                .retryable_status_codes
+                .contains(&response.status().as_u16()),
+            Err(_) => true,
+        };
+        if !retryable || attempt >= retry.max_attempts {
+            break result.external(&format!("Failed to get URL data from {}", url))?;
+        }
+
+        tokio::time::sleep(with_jitter(backoff, retry.jitter)).await;
+        backoff = (backoff * 2).min(retry.max_backoff);
+        attempt += 1;
+    };
+
+    if response.status() == StatusCode::NOT_MODIFIED {
+        return Ok(None);
+    }
+
+    let header_string = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from)
+    };
+    let content_encoding = header_string(CONTENT_ENCODING);
+    let etag = header_string(ETAG);
+    let last_modified = header_string(LAST_MODIFIED);
+
+    let bytes = response
+        .bytes()
+        .await
+        .external("Failed to read URL data")?;
+
+    Ok(Some(CachedResponse {
+        bytes,
+        content_encoding,
+        etag,
+        last_modified,
+        fetched_at: Instant::now(),
+    }))
+}
+
+/// Fetch the contents of an HTTP url, together with the Content-Encoding response
+/// header (if any). When a cache TTL is configured, responses are cached by url and
+/// expired entries are revalidated with conditional requests
+pub async fn fetch_url_bytes(url: &str) -> Result<(Bytes, Option<String>)> {
+    let config = get_http_config();
+    let ttl = match config.cache_ttl {
+        Some(ttl) => ttl,
+        None => {
+            // Caching disabled
+            let response = request_url(url, None, None)
+                .await?
+                .with_context(|| format!("Unexpected empty response from {}", url))?;
+            return Ok((response.bytes, response.content_encoding));
+        }
+    };
+
+    // Return unexpired cached response (if any)
+    let cached = RESPONSE_CACHE.lock().unwrap().get(&url.to_string()).cloned();
+    if let Some(cached) = &cached {
+        if cached.fetched_at.elapsed() < ttl {
+            return Ok((cached.bytes.clone(), cached.content_encoding.clone()));
+        }
+    }
+
+    // Cache entry is missing or expired. Expired entries are revalidated with a
+    // conditional request so the server can answer 304 without resending the body
+    let etag = cached.as_ref().and_then(|cached| cached.etag.as_deref());
+    let last_modified = cached
+        .as_ref()
+        .and_then(|cached| cached.last_modified.as_deref());
+
+    match request_url(url, etag, last_modified).await? {
+        Some(response) => {
+            let result = (response.bytes.clone(), response.content_encoding.clone());
+            RESPONSE_CACHE.lock().unwrap().put(url.to_string(), response);
+            Ok(result)
+        }
+        None => {
+            // 304 Not Modified: the cached bytes are still valid, so refresh the TTL
+            let mut cached = cached.with_context(|| {
+                format!("Server at {} responded 304 to an unconditional request", url)
+            })?;
+            cached.fetched_at = Instant::now();
+            let result = (cached.bytes.clone(), cached.content_encoding.clone());
+            RESPONSE_CACHE.lock().unwrap().put(url.to_string(), cached);
+            Ok(result)
+        }
+    }
+}
@@ -7,5 +7,11 @@
  * this program the details of the active license.
  */
 pub mod dataset;
+pub mod http;
+pub mod object_store;
+pub mod registry;
+pub mod sandbox;
+pub mod scan;
 pub mod table;
 pub mod tasks;
+pub mod topojson;
@@ -0,0 +1,146 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use object_store::aws::AmazonS3Builder;
+use object_store::azure::MicrosoftAzureBuilder;
+use object_store::gcp::GoogleCloudStorageBuilder;
+use object_store::path::Path;
+use object_store::ObjectStore;
+use std::sync::{Arc, RwLock};
+use vegafusion_core::error::{Result, ResultWithContext, ToExternalError, VegaFusionError};
+
+/// AWS S3 credentials and options used for `s3://` data urls
+#[derive(Debug, Clone, Default)]
+pub struct AwsConfig {
+    pub access_key_id: Option<String>,
+    pub secret_access_key: Option<String>,
+    pub session_token: Option<String>,
+    pub region: Option<String>,
+    pub endpoint: Option<String>,
+}
+
+/// Google Cloud Storage credentials used for `gs://` data urls
+#[derive(Debug, Clone, Default)]
+pub struct GcpConfig {
+    pub service_account_path: Option<String>,
+}
+
+/// Azure Blob Storage credentials used for `az://` data urls
+#[derive(Debug, Clone, Default)]
+pub struct AzureConfig {
+    pub account: Option<String>,
+    pub access_key: Option<String>,
+}
+
+/// Runtime-level configuration for reading data urls with cloud-storage schemes
+#[derive(Debug, Clone, Default)]
+pub struct ObjectStoreConfig {
+    pub aws: AwsConfig,
+    pub gcp: GcpConfig,
+    pub azure: AzureConfig,
+}
+
+lazy_static! {
+    static ref OBJECT_STORE_CONFIG: RwLock<ObjectStoreConfig> =
+        RwLock::new(ObjectStoreConfig::default());
+}
+
+/// Replace the process-wide object store configuration used for data url requests
+pub fn set_object_store_config(config: ObjectStoreConfig) {
+    let mut guard = OBJECT_STORE_CONFIG.write().unwrap();
+    *guard = config;
+}
+
+/// Get a copy of the current process-wide object store configuration
+pub fn get_object_store_config() -> ObjectStoreConfig {
+    OBJECT_STORE_CONFIG.read().unwrap().clone()
+}
+
+/// Check whether a data url refers to a supported cloud-storage scheme
+pub fn is_object_store_url(url: &str) -> bool {
+    url.starts_with("s3://") || url.starts_with("gs://") || url.starts_with("az://")
+}
+
+/// Build an ObjectStore for a bucket (or container) using the current
+/// object store configuration
+fn make_object_store(scheme: &str, bucket: &str) -> Result<Arc<dyn ObjectStore>> {
+    let config = get_object_store_config();
+    match scheme {
+        "s3" => {
+            let mut builder = AmazonS3Builder::new().with_bucket_name(bucket);
+            if let Some(access_key_id) = &config.aws.access_key_id {
+                builder = builder.with_access_key_id(access_key_id);
+            }
+            if let Some(secret_access_key) = &config.aws.secret_access_key {
+                builder = builder.with_secret_access_key(secret_access_key);
+            }
+            if let Some(session_token) = &config.aws.session_token {
+                builder = builder.with_token(session_token);
+            }
+            if let Some(region) = &config.aws.region {
+                builder = builder.with_region(region);
+            }
+            if let Some(endpoint) = &config.aws.endpoint {
+                builder = builder.with_endpoint(endpoint);
+            }
+            Ok(Arc::new(
+                builder.build().external("Failed to build S3 object store")?,
+            ))
+        }
+        "gs" => {
+            let mut builder = GoogleCloudStorageBuilder::new().with_bucket_name(bucket);
+            if let Some(service_account_path) = &config.gcp.service_account_path {
+                builder = builder.with_service_account_path(service_account_path);
+            }
+            Ok(Arc::new(
+                builder
+                    .build()
+                    .external("Failed to build Google Cloud Storage object store")?,
+            ))
+        }
+        "az" => {
+            let mut builder = MicrosoftAzureBuilder::new().with_container_name(bucket);
+            if let Some(account) = &config.azure.account {
+                builder = builder.with_account(account);
+            }
+            if let Some(access_key) = &config.azure.access_key {
+                builder = builder.with_access_key(access_key);
+            }
+            Ok(Arc::new(
+                builder
+                    .build()
+                    .external("Failed to build Azure object store")?,
+            ))
+        }
+        _ => Err(VegaFusionError::internal(format!(
+            "Unsupported object store scheme: {}",
+            scheme
+        ))),
+    }
+}
+
+/// Read the full contents of an object-store url (e.g. `s3://bucket/data.csv`)
+pub async fn read_object_store_bytes(url: &str) -> Result<bytes::Bytes> {
+    let (scheme, remainder) = url
+        .split_once("://")
+        .with_context(|| format!("Invalid object store url: {}", url))?;
+    let (bucket, path) = remainder
+        .split_once('/')
+        .with_context(|| format!("Object store url has no path: {}", url))?;
+
+    let store = make_object_store(scheme, bucket)?;
+    let path = Path::from(path);
+    let result = store
+        .get(&path)
+        .await
+        .external(&format!("Failed to get object store data from {}", url))?;
+    result
+        .bytes()
+        .await
+        .external("Failed to read object store data")
+}
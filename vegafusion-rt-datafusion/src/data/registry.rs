@@ -0,0 +1,44 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use crate::session::make_session_context;
+use datafusion::dataframe::DataFrame;
+use datafusion::datasource::TableProvider;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use vegafusion_core::error::{Result, ResultWithContext};
+
+lazy_static! {
+    static ref TABLE_REGISTRY: RwLock<HashMap<String, Arc<dyn TableProvider>>> =
+        RwLock::new(HashMap::new());
+}
+
+/// Register a named table so specs can reference it as `url: "table://<name>"`.
+/// Replaces any previously registered table with the same name
+pub fn register_table(name: &str, table: Arc<dyn TableProvider>) {
+    let mut registry = TABLE_REGISTRY.write().unwrap();
+    registry.insert(name.to_string(), table);
+}
+
+/// Remove a previously registered table, returning it if present
+pub fn unregister_table(name: &str) -> Option<Arc<dyn TableProvider>> {
+    let mut registry = TABLE_REGISTRY.write().unwrap();
+    registry.remove(name)
+}
+
+/// Build a DataFrame that scans a registered table
+pub fn registered_table_to_dataframe(name: &str) -> Result<Arc<DataFrame>> {
+    let table = {
+        let registry = TABLE_REGISTRY.read().unwrap();
+        registry.get(name).cloned()
+    }
+    .with_context(|| format!("No registered table named {}", name))?;
+
+    let ctx = make_session_context()?;
+    Ok(ctx.read_table(table)?)
+}
@@ -0,0 +1,71 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use std::path::PathBuf;
+use std::sync::RwLock;
+use vegafusion_core::error::{Result, ToExternalError, VegaFusionError};
+
+/// Policy controlling which local file paths url datasets may read. Embedders
+/// that accept untrusted specs should restrict local file access with
+/// FilesystemPolicy::DenyAll or FilesystemPolicy::AllowRoots
+#[derive(Debug, Clone)]
+pub enum FilesystemPolicy {
+    /// Allow reading any local path (the default)
+    AllowAll,
+
+    /// Deny all local file access
+    DenyAll,
+
+    /// Allow reading only paths under one of the listed root directories.
+    /// Paths are canonicalized before the check, so relative segments and
+    /// symlinks can't be used to escape the roots
+    AllowRoots(Vec<PathBuf>),
+}
+
+lazy_static! {
+    static ref FILESYSTEM_POLICY: RwLock<FilesystemPolicy> =
+        RwLock::new(FilesystemPolicy::AllowAll);
+}
+
+/// Replace the process-wide filesystem policy used for local file data urls
+pub fn set_filesystem_policy(policy: FilesystemPolicy) {
+    let mut guard = FILESYSTEM_POLICY.write().unwrap();
+    *guard = policy;
+}
+
+/// Get a copy of the current process-wide filesystem policy
+pub fn get_filesystem_policy() -> FilesystemPolicy {
+    FILESYSTEM_POLICY.read().unwrap().clone()
+}
+
+/// Check whether the filesystem policy allows reading a local path,
+/// returning an error describing the violation if not
+pub fn check_local_path(path: &str) -> Result<()> {
+    match get_filesystem_policy() {
+        FilesystemPolicy::AllowAll => Ok(()),
+        FilesystemPolicy::DenyAll => Err(VegaFusionError::external(format!(
+            "Local file access is disabled by the filesystem policy: {}",
+            path
+        ))),
+        FilesystemPolicy::AllowRoots(roots) => {
+            let canonical = std::fs::canonicalize(path)
+                .external(&format!("Failed to resolve local file path: {}", path))?;
+            for root in &roots {
+                if let Ok(canonical_root) = std::fs::canonicalize(root) {
+                    if canonical.starts_with(&canonical_root) {
+                        return Ok(());
+                    }
+                }
+            }
+            Err(VegaFusionError::external(format!(
+                "Local file path {} is outside the directories allowed by the filesystem policy",
+                path
+            )))
+        }
+    }
+}
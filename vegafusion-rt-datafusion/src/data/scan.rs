@@ -0,0 +1,47 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use datafusion::arrow::datatypes::SchemaRef;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// Runtime-level options controlling schema inference when scanning url datasets
+#[derive(Debug, Clone)]
+pub struct ScanConfig {
+    /// Maximum number of rows examined when inferring csv and json column types
+    pub schema_infer_max_records: usize,
+
+    /// Explicit Arrow schema overrides, keyed by dataset url. When a url has an
+    /// override, the schema is used as-is and type inference (along with any
+    /// format.parse directives) is skipped for that dataset
+    pub schema_overrides: HashMap<String, SchemaRef>,
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            schema_infer_max_records: 1000,
+            schema_overrides: Default::default(),
+        }
+    }
+}
+
+lazy_static! {
+    static ref SCAN_CONFIG: RwLock<ScanConfig> = RwLock::new(ScanConfig::default());
+}
+
+/// Replace the process-wide scan configuration used for url datasets
+pub fn set_scan_config(config: ScanConfig) {
+    let mut guard = SCAN_CONFIG.write().unwrap();
+    *guard = config;
+}
+
+/// Get a copy of the current process-wide scan configuration
+pub fn get_scan_config() -> ScanConfig {
+    SCAN_CONFIG.read().unwrap().clone()
+}
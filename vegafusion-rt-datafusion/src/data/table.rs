@@ -6,11 +6,11 @@
  * Please consult the license documentation provided alongside
  * this program the details of the active license.
  */
+use crate::session::make_session_context;
 use crate::transform::utils::DataFrameUtils;
 use async_trait::async_trait;
 use datafusion::dataframe::DataFrame;
 use datafusion::datasource::MemTable;
-use datafusion::prelude::SessionContext;
 use std::sync::Arc;
 use vegafusion_core::arrow::datatypes::SchemaRef;
 use vegafusion_core::arrow::util::pretty::pretty_format_batches;
@@ -73,7 +73,7 @@ impl VegaFusionTableUtils for VegaFusionTable {
     }
 
     fn to_dataframe(&self) -> Result<Arc<DataFrame>> {
-        let ctx = SessionContext::new();
+        let ctx = make_session_context()?;
         let provider = self.to_memtable();
         ctx.register_table("df", Arc::new(provider)).unwrap();
         ctx.table("df")
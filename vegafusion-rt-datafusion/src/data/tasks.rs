@@ -7,10 +7,19 @@
  * this program the details of the active license.
  */
 use crate::data::table::VegaFusionTableUtils;
+use crate::data::http::{fetch_url_bytes, get_http_config};
+use crate::data::object_store::{is_object_store_url, read_object_store_bytes};
+use crate::data::registry::registered_table_to_dataframe;
+use crate::data::sandbox::check_local_path;
+use crate::data::scan::get_scan_config;
+use crate::data::topojson::{feature_to_geojson, mesh_to_geojson};
+use crate::session::make_session_context;
+use crate::transform::utils::RecordBatchUtils;
 use crate::expression::compiler::builtin_functions::date_time::date_parsing::{
     get_datetime_udf, DateParseMode,
 };
 use crate::expression::compiler::builtin_functions::date_time::datetime::make_datetime_components_udf;
+use crate::expression::compiler::builtin_functions::control_flow::logging::ExpressionWarningCollector;
 use crate::expression::compiler::compile;
 use crate::expression::compiler::config::CompilationConfig;
 use crate::expression::compiler::utils::{
@@ -19,32 +28,46 @@ use crate::expression::compiler::utils::{
 use crate::task_graph::task::TaskCall;
 use crate::transform::TransformTrait;
 use async_trait::async_trait;
+use datafusion::arrow::array::StringArray;
 use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
 use datafusion::arrow::ipc::reader::{FileReader, StreamReader};
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::dataframe::DataFrame;
 use datafusion::datasource::listing::ListingTableUrl;
-use datafusion::execution::options::CsvReadOptions;
+use datafusion::execution::options::{CsvReadOptions, ParquetReadOptions};
 use datafusion::logical_plan::Expr;
 use datafusion::prelude::{col, SessionContext};
+use lru::LruCache;
+use regex::Regex;
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet};
 use std::fs::File;
+use std::hash::{Hash, Hasher};
 use std::io::Write;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tokio::io::AsyncReadExt;
 use vegafusion_core::arrow::datatypes::TimeUnit;
 use vegafusion_core::data::dataset::VegaFusionDataset;
 
 use crate::data::dataset::VegaFusionDatasetUtils;
 use crate::expression::compiler::builtin_functions::date_time::local_to_utc::make_to_utc_millis_fn;
+use crate::expression::compiler::builtin_functions::scale::ScaleState;
 use crate::task_graph::timezone::RuntimeTzConfig;
 use vegafusion_core::data::scalar::{ScalarValue, ScalarValueHelpers};
 use vegafusion_core::data::table::VegaFusionTable;
 use vegafusion_core::error::{Result, ResultWithContext, ToExternalError, VegaFusionError};
+use vegafusion_core::planning::base_url::{is_relative_url, resolve_url};
 use vegafusion_core::proto::gen::tasks::data_url_task::Url;
 use vegafusion_core::proto::gen::tasks::scan_url_format;
 use vegafusion_core::proto::gen::tasks::scan_url_format::Parse;
-use vegafusion_core::proto::gen::tasks::{DataSourceTask, DataUrlTask, DataValuesTask};
+use vegafusion_core::proto::gen::tasks::{
+    DataSourceTask, DataUrlTask, DataValuesTask, ParseFieldSpec, ParseFieldSpecs,
+    VariableNamespace,
+};
+use vegafusion_core::proto::gen::transforms::{
+    transform::TransformKind, Transform, TransformPipeline,
+};
+use vegafusion_core::transform::TransformDependencies;
 use vegafusion_core::task_graph::task::{InputVariable, TaskDependencies};
 use vegafusion_core::task_graph::task_value::TaskValue;
 
@@ -52,12 +75,25 @@ pub fn build_compilation_config(
     input_vars: &[InputVariable],
     values: &[TaskValue],
     tz_config: &Option<RuntimeTzConfig>,
-) -> CompilationConfig {
+    expression_warnings: ExpressionWarningCollector,
+) -> Result<CompilationConfig> {
     // Build compilation config from input_vals
     let mut signal_scope: HashMap<String, ScalarValue> = HashMap::new();
     let mut data_scope: HashMap<String, VegaFusionTable> = HashMap::new();
+    let mut scale_scope: HashMap<String, ScaleState> = HashMap::new();
 
     for (input_var, input_val) in input_vars.iter().zip(values) {
+        // Scale values hold the scale's static state encoded as a scalar, so they are
+        // distinguished by namespace rather than by task value variant
+        if input_var.var.namespace() == VariableNamespace::Scale {
+            if let TaskValue::Scalar(value) = input_val {
+                scale_scope.insert(
+                    input_var.var.name.clone(),
+                    ScaleState::from_scalar_value(value)?,
+                );
+            }
+            continue;
+        }
         match input_val {
             TaskValue::Scalar(value) => {
                 signal_scope.insert(input_var.var.name.clone(), value.clone());
@@ -70,11 +106,68 @@ pub fn build_compilation_config(
 
     // CompilationConfig is not Send, so use local scope here to make sure it's dropped
     // before the call to await below.
-    CompilationConfig {
+    Ok(CompilationConfig {
         signal_scope,
         data_scope,
+        scale_scope,
         tz_config: *tz_config,
+        expression_warnings,
         ..Default::default()
+    })
+}
+
+/// Number of scanned source tables to retain for reuse across evaluations
+const SOURCE_TABLE_CACHE_CAPACITY: usize = 8;
+
+lazy_static! {
+    /// Cache of scanned (and datetime-processed) source tables, keyed by the
+    /// value-independent portion of a url task: the resolved url together with the
+    /// format, parsing, and timezone options that affect how it is read. Compiled
+    /// plans can't be reused directly because expression compilation inlines the
+    /// current signal values, but the scan stage excludes dynamic values entirely,
+    /// so repeated interactions only need to re-run the transform pipeline. The
+    /// url is stored alongside each table to support invalidation by url
+    static ref SOURCE_TABLE_CACHE: Mutex<LruCache<u64, (String, VegaFusionTable)>> =
+        Mutex::new(LruCache::new(SOURCE_TABLE_CACHE_CAPACITY));
+}
+
+/// Split the leading filter transforms that don't reference signals or other
+/// datasets off the front of a pipeline. These filters produce the same rows for
+/// every signal value, so they can be applied at the scan stage (and included in
+/// the scan cache key) to prune rows before the source table is materialized
+fn split_scan_filters(
+    pipeline: &Option<TransformPipeline>,
+) -> (Vec<Transform>, Option<TransformPipeline>) {
+    let pipeline = match pipeline {
+        Some(pipeline) => pipeline,
+        None => return (Vec::new(), None),
+    };
+    let num_filters = pipeline
+        .transforms
+        .iter()
+        .take_while(|tx| {
+            matches!(tx.transform_kind.as_ref(), Some(TransformKind::Filter(_)))
+                && tx.input_vars().is_empty()
+        })
+        .count();
+    let scan_filters = pipeline.transforms[..num_filters].to_vec();
+    let rest = TransformPipeline {
+        transforms: pipeline.transforms[num_filters..].to_vec(),
+    };
+    (scan_filters, Some(rest))
+}
+
+/// Remove cached source tables scanned from the given url (if any), forcing the
+/// next evaluation to re-read and re-parse it
+pub fn invalidate_source_table_cache(url: &str) {
+    let mut cache = SOURCE_TABLE_CACHE.lock().unwrap();
+    let keys: Vec<u64> = cache
+        .iter()
+        .filter(|(_, (cached_url, _))| cached_url == url)
+        .map(|(key, _)| *key)
+        .collect();
+    for key in keys {
+        cache.pop(&key);
     }
 }
 
@@ -85,9 +178,16 @@ impl TaskCall for DataUrlTask {
         values: &[TaskValue],
         tz_config: &Option<RuntimeTzConfig>,
         inline_datasets: HashMap<String, VegaFusionDataset>,
+        expression_warnings: ExpressionWarningCollector,
     ) -> Result<(TaskValue, Vec<TaskValue>)> {
         // Build compilation config for url signal (if any) and transforms (if any)
-        let config = build_compilation_config(&self.input_vars(), values, tz_config);
+        let config =
+            build_compilation_config(&self.input_vars(), values, tz_config, expression_warnings)?;
+
+        // Split leading filter transforms that don't reference signals or other
+        // datasets off the front of the pipeline so they can be applied at the
+        // scan stage, before the source table is materialized and cached
+        let (scan_filters, pipeline) = split_scan_filters(&self.pipeline);
 
         // Build url string
         let url = match self.url.as_ref().unwrap() {
@@ -103,14 +203,74 @@ impl TaskCall for DataUrlTask {
         let url_parts: Vec<&str> = url.splitn(2, '#').collect();
         let url = url_parts.first().cloned().unwrap_or(&url).to_string();
 
+        // Resolve relative urls against the runtime-level base url (if any)
+        let url = if let Some(base_url) = get_http_config().base_url {
+            if is_relative_url(&url) {
+                resolve_url(&url, &base_url)
+            } else {
+                url
+            }
+        } else {
+            url
+        };
+
         // Handle references to vega default datasets (e.g. "data/us-10m.json")
         let url = check_builtin_dataset(url);
 
         // Load data from URL
-        let parse = self.format_type.as_ref().and_then(|fmt| fmt.parse.clone());
+        let mut parse = self.format_type.as_ref().and_then(|fmt| fmt.parse.clone());
+
+        // An explicit format type takes precedence over the url's file extension
+        let format_type = self
+            .format_type
+            .as_ref()
+            .and_then(|fmt| fmt.r#type.clone());
+        let format_type = format_type.as_deref();
+
+        // For data: urls the media type stands in for the file extension
+        let format_type = format_type.or_else(|| data_url_format_type(&url));
+
+        // Transparently handle compressed files (e.g. foo.csv.gz). The compression
+        // suffix is stripped before checking the data file extension below
+        let (base_url, compression) = if url.starts_with("data:") {
+            (url.as_str(), UrlCompression::None)
+        } else {
+            split_url_compression(&url)
+        };
+        let base_url = base_url.to_string();
 
         let date_mode = DateParseMode::JavaScript;
-        let df = if let Some(inline_name) = url.strip_prefix("vegafusion+dataset://") {
+
+        // Key identifying the scan stage of this task. Inline datasets and registered
+        // tables are excluded because their contents can change without the url changing
+        let scan_key = if url.starts_with("vegafusion+dataset://") || url.starts_with("table://") {
+            None
+        } else {
+            let mut hasher = DefaultHasher::new();
+            url.hash(&mut hasher);
+            format!("{:?}", self.format_type).hash(&mut hasher);
+            self.batch_size.hash(&mut hasher);
+            format!("{:?}", tz_config).hash(&mut hasher);
+            format!("{:?}", scan_filters).hash(&mut hasher);
+            Some(hasher.finish())
+        };
+        let cached_source = match &scan_key {
+            Some(scan_key) => SOURCE_TABLE_CACHE
+                .lock()
+                .unwrap()
+                .get(scan_key)
+                .map(|(_, table)| table.clone()),
+            None => None,
+        };
+
+        // Temp directory (if any) backing the scanned dataframe. Held until the end of
+        // evaluation so file-backed scans can stream through the transform pipeline
+        // rather than materializing the full source table first
+        let mut _tempdir: Option<tempfile::TempDir> = None;
+
+        let df = if let Some(table) = &cached_source {
+            table.to_dataframe()?
+        } else if let Some(inline_name) = url.strip_prefix("vegafusion+dataset://") {
             let inline_name = inline_name.trim().to_string();
             if let Some(inline_dataset) = inline_datasets.get(&inline_name) {
                 inline_dataset.to_dataframe()?
@@ -120,29 +280,85 @@ impl TaskCall for DataUrlTask {
                     inline_name
                 )));
             }
-        } else if url.ends_with(".csv") || url.ends_with(".tsv") {
-            read_csv(url, &parse).await?
-        } else if url.ends_with(".json") {
-            read_json(&url, self.batch_size as usize).await?
-        } else if url.ends_with(".arrow") || url.ends_with(".feather") {
-            read_arrow(&url).await?
+        } else if let Some(table_name) = url.strip_prefix("table://") {
+            // Scan a table the host application registered ahead of time
+            registered_table_to_dataframe(table_name.trim())?
+        } else if matches!(format_type, Some("csv" | "tsv"))
+            || (format_type.is_none() && (base_url.ends_with(".csv") || base_url.ends_with(".tsv")))
+        {
+            let (df, tempdir) = read_csv(&url, &base_url, &parse, compression).await?;
+            _tempdir = tempdir;
+            parse = detect_date_columns(&df, &parse).await?;
+            df
+        } else if matches!(format_type, Some("json"))
+            || (format_type.is_none() && base_url.ends_with(".json"))
+        {
+            let property = self
+                .format_type
+                .as_ref()
+                .and_then(|fmt| fmt.property.clone());
+            read_json(&url, self.batch_size as usize, &property, compression).await?
+        } else if matches!(format_type, Some("topojson")) {
+            let fmt = self.format_type.as_ref().unwrap();
+            read_topojson(
+                &url,
+                self.batch_size as usize,
+                &fmt.feature,
+                &fmt.mesh,
+                compression,
+            )
+            .await?
+        } else if matches!(format_type, Some("arrow" | "feather"))
+            || (format_type.is_none()
+                && (base_url.ends_with(".arrow") || base_url.ends_with(".feather")))
+        {
+            read_arrow(&url, compression).await?
+        } else if matches!(format_type, Some("parquet"))
+            || (format_type.is_none() && base_url.ends_with(".parquet"))
+        {
+            let (df, tempdir) = read_parquet(&url).await?;
+            _tempdir = tempdir;
+            df
         } else {
             return Err(VegaFusionError::internal(&format!(
-                "Invalid url file extension {}",
-                url
+                "Unsupported data format {:?} for url {}",
+                format_type, url
             )));
         };
 
-        let df = process_datetimes(&parse, date_mode, df, tz_config)?;
+        let df = if cached_source.is_none() {
+            let mut df = process_datetimes(&parse, date_mode, df, tz_config)?;
 
-        // Apply transforms (if any)
-        let (transformed_df, output_values) = if self
-            .pipeline
+            // Prune rows that the pipeline would immediately drop. The predicates are
+            // part of the scan cache key, so the filtered table is safe to reuse
+            for tx in &scan_filters {
+                df = tx.eval(df, &config).await?.0;
+            }
+
+            // Store the scanned table for reuse, unless it's backed by a temp file
+            // (which streams through the pipeline rather than being materialized)
+            if _tempdir.is_none() {
+                if let Some(scan_key) = scan_key {
+                    let table = VegaFusionTable::from_dataframe(df.clone()).await?;
+                    SOURCE_TABLE_CACHE
+                        .lock()
+                        .unwrap()
+                        .put(scan_key, (url.clone(), table));
+                }
+            }
+            df
+        } else {
+            // Cached tables were datetime-processed before being stored
+            df
+        };
+
+        // Apply remaining transforms (if any)
+        let (transformed_df, output_values) = if pipeline
             .as_ref()
             .map(|p| !p.transforms.is_empty())
             .unwrap_or(false)
         {
-            let pipeline = self.pipeline.as_ref().unwrap();
+            let pipeline = pipeline.as_ref().unwrap();
             pipeline.eval(df, &config).await?
         } else {
             // No transforms
@@ -395,6 +611,7 @@ impl TaskCall for DataValuesTask {
         values: &[TaskValue],
         tz_config: &Option<RuntimeTzConfig>,
         _inline_datasets: HashMap<String, VegaFusionDataset>,
+        expression_warnings: ExpressionWarningCollector,
     ) -> Result<(TaskValue, Vec<TaskValue>)> {
         // Deserialize data into table
         let values_table = VegaFusionTable::from_ipc_bytes(&self.values)?;
@@ -414,7 +631,12 @@ impl TaskCall for DataValuesTask {
             let values_df = values_table.to_dataframe()?;
             let values_df = process_datetimes(&parse, date_mode, values_df, tz_config)?;
 
-            let config = build_compilation_config(&self.input_vars(), values, tz_config);
+            let config = build_compilation_config(
+                &self.input_vars(),
+                values,
+                tz_config,
+                expression_warnings,
+            )?;
             let (df, output_values) = pipeline.eval(values_df, &config).await?;
 
             (VegaFusionTable::from_dataframe(df).await?, output_values)
@@ -441,9 +663,11 @@ impl TaskCall for DataSourceTask {
         values: &[TaskValue],
         tz_config: &Option<RuntimeTzConfig>,
         _inline_datasets: HashMap<String, VegaFusionDataset>,
+        expression_warnings: ExpressionWarningCollector,
     ) -> Result<(TaskValue, Vec<TaskValue>)> {
         let input_vars = self.input_vars();
-        let mut config = build_compilation_config(&input_vars, values, tz_config);
+        let mut config =
+            build_compilation_config(&input_vars, values, tz_config, expression_warnings)?;
 
         // Remove source table from config
         let source_table = config.data_scope.remove(&self.source).unwrap_or_else(|| {
@@ -474,9 +698,219 @@ impl TaskCall for DataSourceTask {
     }
 }
 
-async fn read_csv(url: String, parse: &Option<Parse>) -> Result<Arc<DataFrame>> {
+lazy_static! {
+    static ref ISO_DATE_RE: Regex = Regex::new(
+        r"^\d{4}[-/]\d{2}[-/]\d{2}([T ]\d{2}:\d{2}(:\d{2}(\.\d+)?)?(Z|[+-]\d{2}:?\d{2})?)?$"
+    )
+    .unwrap();
+}
+
+/// Detect string columns whose values all look like ISO-8601 dates and add "date"
+/// parse directives for them so that process_datetimes converts them to timestamps.
+/// This matches vega-loader, which auto-parses date-like strings when reading CSV
+/// files. Columns with explicit parse directives are left as specified
+async fn detect_date_columns(df: &Arc<DataFrame>, parse: &Option<Parse>) -> Result<Option<Parse>> {
+    // Columns that already have parse directives
+    let explicit: HashSet<String> = if let Some(Parse::Object(specs)) = parse {
+        specs.specs.iter().map(|spec| spec.name.clone()).collect()
+    } else {
+        Default::default()
+    };
+
+    let string_columns: Vec<String> = df
+        .schema()
+        .fields()
+        .iter()
+        .filter(|field| {
+            field.data_type() == &DataType::Utf8 && !explicit.contains(field.name())
+        })
+        .map(|field| field.name().clone())
+        .collect();
+    if string_columns.is_empty() {
+        return Ok(parse.clone());
+    }
+
+    // Sample the leading rows for detection
+    let batches = df.limit(1024)?.collect().await?;
+    let mut date_columns: Vec<String> = Vec::new();
+    for name in string_columns {
+        let mut any_value = false;
+        let mut all_dates = true;
+        'batches: for batch in &batches {
+            let column = batch.column_by_name(&name)?;
+            let array = column.as_any().downcast_ref::<StringArray>().unwrap();
+            for i in 0..array.len() {
+                if array.is_null(i) {
+                    continue;
+                }
+                let value = array.value(i);
+                if value.is_empty() {
+                    continue;
+                }
+                any_value = true;
+                if !ISO_DATE_RE.is_match(value) {
+                    all_dates = false;
+                    break 'batches;
+                }
+            }
+        }
+        if any_value && all_dates {
+            date_columns.push(name);
+        }
+    }
+
+    if date_columns.is_empty() {
+        return Ok(parse.clone());
+    }
+
+    // Merge the detected columns into the parse directives as "date" fields
+    let mut specs = if let Some(Parse::Object(specs)) = parse {
+        specs.specs.clone()
+    } else {
+        Vec::new()
+    };
+    specs.extend(date_columns.into_iter().map(|name| ParseFieldSpec {
+        name,
+        datatype: "date".to_string(),
+    }));
+    Ok(Some(Parse::Object(ParseFieldSpecs { specs })))
+}
+
+/// Compression applied to a data url, detected from the file extension or the
+/// Content-Encoding response header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UrlCompression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+/// Strip a compression suffix (e.g. ".gz" in "foo.csv.gz") from a url, returning
+/// the base url to use for file extension detection along with the compression
+fn split_url_compression(url: &str) -> (&str, UrlCompression) {
+    for (suffix, compression) in [
+        (".gz", UrlCompression::Gzip),
+        (".gzip", UrlCompression::Gzip),
+        (".zst", UrlCompression::Zstd),
+        (".zstd", UrlCompression::Zstd),
+    ] {
+        if let Some(base_url) = url.strip_suffix(suffix) {
+            return (base_url, compression);
+        }
+    }
+    (url, UrlCompression::None)
+}
+
+/// Infer a format type from the media type of a `data:` url
+/// (e.g. `data:text/csv;base64,...`)
+fn data_url_format_type(url: &str) -> Option<&'static str> {
+    let media_type = url
+        .strip_prefix("data:")?
+        .split(|c| c == ';' || c == ',')
+        .next()?;
+    match media_type {
+        "text/csv" => Some("csv"),
+        "text/tab-separated-values" => Some("tsv"),
+        "application/json" => Some("json"),
+        "application/vnd.apache.arrow.file" | "application/vnd.apache.arrow.stream" => {
+            Some("arrow")
+        }
+        _ => None,
+    }
+}
+
+/// Decode the payload of a base64-encoded `data:` url
+fn decode_data_url(url: &str) -> Result<Vec<u8>> {
+    let contents = url
+        .strip_prefix("data:")
+        .with_context(|| format!("Invalid data url: {}", url))?;
+    let (metadata, payload) = contents
+        .split_once(',')
+        .with_context(|| "data url has no comma separating metadata from payload".to_string())?;
+    if metadata.ends_with(";base64") {
+        base64::decode(payload).external("Failed to decode base64 data url payload")
+    } else {
+        Err(VegaFusionError::parse(
+            "Only base64-encoded data urls are supported",
+        ))
+    }
+}
+
+fn decompress_bytes(buffer: &[u8], compression: UrlCompression) -> Result<Vec<u8>> {
+    match compression {
+        UrlCompression::None => Ok(buffer.to_vec()),
+        UrlCompression::Gzip => {
+            let mut decoder = flate2::read::GzDecoder::new(buffer);
+            let mut decompressed: Vec<u8> = Vec::new();
+            std::io::Read::read_to_end(&mut decoder, &mut decompressed)
+                .external("Failed to decompress gzip data")?;
+            Ok(decompressed)
+        }
+        UrlCompression::Zstd => {
+            zstd::stream::decode_all(buffer).external("Failed to decompress zstd data")
+        }
+    }
+}
+
+/// Read the raw contents of a local or remote url, transparently decompressing
+/// them. For remote urls the Content-Encoding response header is consulted when
+/// the url itself doesn't carry a compression extension
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(url = %url))
+)]
+async fn read_url_bytes(url: &str, compression: UrlCompression) -> Result<Vec<u8>> {
+    let (buffer, header_compression) = if url.starts_with("data:") {
+        let buffer = bytes::Bytes::from(decode_data_url(url)?);
+        (buffer, UrlCompression::None)
+    } else if url.starts_with("http://") || url.starts_with("https://") {
+        let (buffer, content_encoding) = fetch_url_bytes(url).await?;
+        let header_compression = match content_encoding.as_deref() {
+            Some("gzip") => UrlCompression::Gzip,
+            Some("zstd") => UrlCompression::Zstd,
+            _ => UrlCompression::None,
+        };
+        (buffer, header_compression)
+    } else if is_object_store_url(url) {
+        let buffer = read_object_store_bytes(url).await?;
+        (buffer, UrlCompression::None)
+    } else {
+        // Assume local file
+        check_local_path(url)?;
+        let mut file = tokio::fs::File::open(url)
+            .await
+            .external(&format!("Failed to open as local file: {}", url))?;
+
+        let mut buffer: Vec<u8> = Vec::new();
+        file.read_to_end(&mut buffer)
+            .await
+            .external("Failed to read file contents")?;
+
+        (bytes::Bytes::from(buffer), UrlCompression::None)
+    };
+
+    let compression = if compression == UrlCompression::None {
+        header_compression
+    } else {
+        compression
+    };
+    decompress_bytes(&buffer, compression)
+}
+
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(url = %url))
+)]
+async fn read_csv(
+    url: &str,
+    base_url: &str,
+    parse: &Option<Parse>,
+    compression: UrlCompression,
+) -> Result<(Arc<DataFrame>, Option<tempfile::TempDir>)> {
     // Build base CSV options
-    let csv_opts = if url.ends_with(".tsv") {
+    let csv_opts = if base_url.ends_with(".tsv")
+        || base_url.starts_with("data:text/tab-separated-values")
+    {
         CsvReadOptions::new()
             .delimiter(b'\t')
             .file_extension(".tsv")
@@ -484,16 +918,19 @@ async fn read_csv(url: String, parse: &Option<Parse>) -> Result<Arc<DataFrame>>
         CsvReadOptions::new()
     };
 
-    let ctx = SessionContext::new();
+    let scan_config = get_scan_config();
+    let csv_opts = csv_opts.schema_infer_max_records(scan_config.schema_infer_max_records);
 
-    if url.starts_with("http://") || url.starts_with("https://") {
-        // Perform get request to collect file contents as text
-        let body = reqwest::get(url.clone())
-            .await
-            .external(&format!("Failed to get URL data from {}", url))?
-            .text()
-            .await
-            .external("Failed to convert URL data to text")?;
+    let ctx = make_session_context()?;
+
+    if url.starts_with("http://")
+        || url.starts_with("https://")
+        || url.starts_with("data:")
+        || is_object_store_url(url)
+        || compression != UrlCompression::None
+    {
+        // Collect the (decompressed) file contents
+        let buffer = read_url_bytes(url, compression).await?;
 
         // Write contents to temp csv file
         let tempdir = tempfile::TempDir::new().unwrap();
@@ -502,25 +939,51 @@ async fn read_csv(url: String, parse: &Option<Parse>) -> Result<Arc<DataFrame>>
 
         {
             let mut file = File::create(filepath.clone()).unwrap();
-            writeln!(file, "{}", body).unwrap();
+            file.write_all(&buffer).unwrap();
+            writeln!(file).unwrap();
         }
 
         let path = tempdir.path().to_str().unwrap();
-        let schema = build_csv_schema(&csv_opts, path, parse).await?;
+        let schema = match scan_config.schema_overrides.get(url) {
+            Some(schema) => schema.clone(),
+            None => build_csv_schema(&csv_opts, path, parse).await?,
+        };
         let csv_opts = csv_opts.schema(&schema);
 
-        // Load through VegaFusionTable so that temp file can be deleted
-        let df = ctx.read_csv(path, csv_opts).await.unwrap();
-        let table = VegaFusionTable::from_dataframe(df).await.unwrap();
-        let df = table.to_dataframe().unwrap();
-        Ok(df)
+        // Scan the temp file lazily so record batches stream through the transform
+        // pipeline with bounded memory. The temp directory is returned to the caller,
+        // which keeps it alive until evaluation completes
+        let df = ctx.read_csv(path, csv_opts).await?;
+        Ok((df, Some(tempdir)))
     } else {
-        let schema = build_csv_schema(&csv_opts, &url, parse).await?;
+        check_local_path(url)?;
+        let schema = match scan_config.schema_overrides.get(url) {
+            Some(schema) => schema.clone(),
+            None => build_csv_schema(&csv_opts, url, parse).await?,
+        };
         let csv_opts = csv_opts.schema(&schema);
-        Ok(ctx.read_csv(url, csv_opts).await?)
+        Ok((ctx.read_csv(url, csv_opts).await?, None))
     }
 }
 
+/// Build a unique column name, renaming empty headers to `__unnamed_<index>__`
+/// and appending `_1`, `_2`, ... to duplicated headers
+fn unique_field_name(name: &str, index: usize, used: &mut HashSet<String>) -> String {
+    let base = if name.trim().is_empty() {
+        format!("__unnamed_{}__", index)
+    } else {
+        name.to_string()
+    };
+    let mut name = base.clone();
+    let mut suffix = 1;
+    while used.contains(&name) {
+        name = format!("{}_{}", base, suffix);
+        suffix += 1;
+    }
+    used.insert(name.clone());
+    name
+}
+
 async fn build_csv_schema(
     csv_opts: &CsvReadOptions<'_>,
     uri: impl Into<String>,
@@ -534,6 +997,20 @@ async fn build_csv_schema(
         .infer_schema(&ctx.state(), &table_path)
         .await?;
 
+    // Rename empty and duplicated column names (common in CSVs exported from pandas)
+    // so downstream transforms bind to distinct, deterministic columns
+    let mut used: HashSet<String> = HashSet::new();
+    let renamed_fields: Vec<_> = inferred_schema
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let name = unique_field_name(field.name(), index, &mut used);
+            Field::new(&name, field.data_type().clone(), field.is_nullable())
+        })
+        .collect();
+    let inferred_schema = SchemaRef::new(Schema::new(renamed_fields));
+
     // Get HashMap of provided columns formats
     let format_specs = if let Some(parse) = parse {
         match parse {
@@ -557,16 +1034,20 @@ async fn build_csv_schema(
         .iter()
         .map(|field| {
             let dtype = if let Some(f) = format_specs.get(field.name()) {
+                // An explicit parse directive takes precedence over the inferred type
                 match f.as_str() {
                     "number" => DataType::Float64,
                     "boolean" => DataType::Boolean,
-                    "date" => DataType::Utf8, // Parse as string, convert to date later
                     "string" => DataType::Utf8,
+                    // date/utc directives (optionally with a format string, e.g.
+                    // "date:'%Y-%m-%d'") are scanned as strings and converted to dates
+                    // later by process_datetimes
+                    d if d.starts_with("date") || d.starts_with("utc") => DataType::Utf8,
                     _ => DataType::Utf8,
                 }
             } else {
-                // Unspecified, use String
-                DataType::Utf8
+                // No directive for this field, keep the inferred type
+                field.data_type().clone()
             };
             Field::new(field.name(), dtype, true)
         })
@@ -574,59 +1055,159 @@ async fn build_csv_schema(
     Ok(SchemaRef::new(Schema::new(new_fields)))
 }
 
-async fn read_json(url: &str, batch_size: usize) -> Result<Arc<DataFrame>> {
-    // Read to json Value from local file or url.
-    let value: serde_json::Value = if url.starts_with("http://") || url.starts_with("https://") {
-        // Perform get request to collect file contents as text
-        let body = reqwest::get(url)
-            .await
-            .external(&format!("Failed to get URL data from {}", url))?
-            .text()
-            .await
-            .external("Failed to convert URL data to text")?;
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(url = %url))
+)]
+/// Infer Hive-style partition columns (nested `column=value` subdirectories) from
+/// the layout of a parquet directory by following the first subdirectory at each
+/// level. Returns an empty Vec for unpartitioned directories
+fn infer_hive_partition_cols(path: &std::path::Path) -> Result<Vec<String>> {
+    let mut partition_cols: Vec<String> = Vec::new();
+    let mut current = path.to_path_buf();
+    loop {
+        let mut next: Option<(String, std::path::PathBuf)> = None;
+        for entry in std::fs::read_dir(&current)
+            .external(&format!("Failed to list directory: {:?}", current))?
+        {
+            let entry = entry.external("Failed to read directory entry")?;
+            if !entry.path().is_dir() {
+                continue;
+            }
+            let file_name = entry.file_name();
+            if let Some((column, _)) = file_name.to_string_lossy().split_once('=') {
+                next = Some((column.to_string(), entry.path()));
+                break;
+            }
+        }
+        match next {
+            Some((column, dir)) => {
+                partition_cols.push(column);
+                current = dir;
+            }
+            None => break,
+        }
+    }
+    Ok(partition_cols)
+}
 
-        serde_json::from_str(&body)?
-    } else {
-        // Assume local file
-        let mut file = tokio::fs::File::open(url)
-            .await
-            .external(&format!("Failed to open as local file: {}", url))?;
+/// Scan a parquet file, or a directory of parquet files that may be
+/// Hive-partitioned (nested `column=value` subdirectories). Partition columns are
+/// exposed as string columns. The scan is lazy, so filter predicates on partition
+/// columns (applied at the scan stage by split_scan_filters) are pushed down to
+/// the listing table, which prunes entire partition directories before reading
+/// any files
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(url = %url))
+)]
+async fn read_parquet(url: &str) -> Result<(Arc<DataFrame>, Option<tempfile::TempDir>)> {
+    let ctx = make_session_context()?;
 
-        let mut json_str = String::new();
-        file.read_to_string(&mut json_str)
-            .await
-            .external("Failed to read file contents to string")?;
+    if url.starts_with("http://")
+        || url.starts_with("https://")
+        || url.starts_with("data:")
+        || is_object_store_url(url)
+    {
+        // Collect the file contents and write them to a temp parquet file. Remote
+        // partitioned directories aren't supported, only single files
+        let buffer = read_url_bytes(url, UrlCompression::None).await?;
 
-        serde_json::from_str(&json_str)?
-    };
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let filepath = tempdir
+            .path()
+            .join("file.parquet")
+            .to_str()
+            .unwrap()
+            .to_string();
 
-    VegaFusionTable::from_json(&value, batch_size)?.to_dataframe()
+        {
+            let mut file = File::create(filepath.clone()).unwrap();
+            file.write_all(&buffer).unwrap();
+        }
+
+        let df = ctx
+            .read_parquet(&filepath, ParquetReadOptions::default())
+            .await?;
+        Ok((df, Some(tempdir)))
+    } else {
+        check_local_path(url)?;
+        let path = std::path::Path::new(url);
+        let parquet_opts = if path.is_dir() {
+            ParquetReadOptions::default()
+                .table_partition_cols(infer_hive_partition_cols(path)?)
+        } else {
+            ParquetReadOptions::default()
+        };
+        Ok((ctx.read_parquet(url, parquet_opts).await?, None))
+    }
 }
 
-async fn read_arrow(url: &str) -> Result<Arc<DataFrame>> {
-    // Read to json Value from local file or url.
-    let buffer = if url.starts_with("http://") || url.starts_with("https://") {
-        // Perform get request to collect file contents as text
-        reqwest::get(url)
-            .await
-            .external(&format!("Failed to get URL data from {}", url))?
-            .bytes()
-            .await
-            .external("Failed to convert URL data to text")?
+async fn read_json(
+    url: &str,
+    batch_size: usize,
+    property: &Option<String>,
+    compression: UrlCompression,
+) -> Result<Arc<DataFrame>> {
+    let value = read_json_value(url, compression).await?;
+
+    // When format.property is provided, the data rows live under that key rather than
+    // at the top level of the document
+    let value = if let Some(property) = property {
+        value.get(property).cloned().with_context(|| {
+            format!(
+                "JSON document at {} has no top-level property named {}",
+                url, property
+            )
+        })?
     } else {
-        // Assume local file
-        let mut file = tokio::fs::File::open(url)
-            .await
-            .external(&format!("Failed to open as local file: {}", url))?;
+        value
+    };
 
-        let mut buffer: Vec<u8> = Vec::new();
-        file.read_to_end(&mut buffer)
-            .await
-            .external("Failed to read file contents")?;
+    let scan_config = get_scan_config();
+    let schema = scan_config.schema_overrides.get(url).cloned();
+    VegaFusionTable::from_json_opts(
+        &value,
+        batch_size,
+        scan_config.schema_infer_max_records,
+        schema,
+    )?
+    .to_dataframe()
+}
 
-        bytes::Bytes::from(buffer)
+async fn read_topojson(
+    url: &str,
+    batch_size: usize,
+    feature: &Option<String>,
+    mesh: &Option<String>,
+    compression: UrlCompression,
+) -> Result<Arc<DataFrame>> {
+    let topology = read_json_value(url, compression).await?;
+    let features = match (feature, mesh) {
+        (Some(name), _) => feature_to_geojson(&topology, name)?,
+        (None, Some(name)) => mesh_to_geojson(&topology, name)?,
+        (None, None) => {
+            return Err(VegaFusionError::parse(
+                "topojson format requires a feature or mesh property",
+            ))
+        }
     };
+    VegaFusionTable::from_json(&features, batch_size)?.to_dataframe()
+}
+
+async fn read_json_value(url: &str, compression: UrlCompression) -> Result<serde_json::Value> {
+    // Read to json Value from local file or url.
+    let buffer = read_url_bytes(url, compression).await?;
+    Ok(serde_json::from_slice(&buffer)?)
+}
 
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(url = %url))
+)]
+async fn read_arrow(url: &str, compression: UrlCompression) -> Result<Arc<DataFrame>> {
+    // Read file contents from local file or url.
+    let buffer = read_url_bytes(url, compression).await?;
     let reader = std::io::Cursor::new(buffer);
 
     // Try parsing file as both File and IPC formats
@@ -0,0 +1,421 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+/*!
+Conversion of TopoJSON topologies to GeoJSON features, following the TopoJSON
+specification (https://github.com/topojson/topojson-specification). This supports
+the `feature` and `mesh` options of `format: {type: "topojson"}` data urls.
+ */
+use serde_json::{json, Value};
+use vegafusion_core::error::{Result, ResultWithContext, VegaFusionError};
+
+/// Decoded transform parameters from a topology's `transform` member
+struct TopoTransform {
+    scale: [f64; 2],
+    translate: [f64; 2],
+}
+
+impl TopoTransform {
+    fn from_topology(topology: &Value) -> Result<Option<Self>> {
+        let transform = match topology.get("transform") {
+            Some(transform) => transform,
+            None => return Ok(None),
+        };
+        let scale = extract_point(transform.get("scale"))
+            .with_context(|| "TopoJSON transform is missing a valid scale".to_string())?;
+        let translate = extract_point(transform.get("translate"))
+            .with_context(|| "TopoJSON transform is missing a valid translate".to_string())?;
+        Ok(Some(Self { scale, translate }))
+    }
+}
+
+fn extract_point(value: Option<&Value>) -> Option<[f64; 2]> {
+    let values = value?.as_array()?;
+    Some([values.first()?.as_f64()?, values.get(1)?.as_f64()?])
+}
+
+/// Decode the topology's arcs into absolute positions, applying the delta
+/// decoding and coordinate transform when a transform is present
+fn decode_arcs(topology: &Value) -> Result<Vec<Vec<[f64; 2]>>> {
+    let transform = TopoTransform::from_topology(topology)?;
+    let arcs = topology
+        .get("arcs")
+        .and_then(|arcs| arcs.as_array())
+        .with_context(|| "TopoJSON topology is missing the arcs member".to_string())?;
+
+    let mut decoded: Vec<Vec<[f64; 2]>> = Vec::with_capacity(arcs.len());
+    for arc in arcs {
+        let positions = arc
+            .as_array()
+            .with_context(|| "TopoJSON arc is not an array".to_string())?;
+        let mut decoded_arc: Vec<[f64; 2]> = Vec::with_capacity(positions.len());
+        let mut x = 0.0;
+        let mut y = 0.0;
+        for position in positions {
+            let position = extract_point(Some(position))
+                .with_context(|| "TopoJSON arc position is not a number pair".to_string())?;
+            let point = if let Some(transform) = &transform {
+                // Quantized topology: positions are delta encoded integers
+                x += position[0];
+                y += position[1];
+                [
+                    x * transform.scale[0] + transform.translate[0],
+                    y * transform.scale[1] + transform.translate[1],
+                ]
+            } else {
+                position
+            };
+            decoded_arc.push(point);
+        }
+        decoded.push(decoded_arc);
+    }
+    Ok(decoded)
+}
+
+/// Transform a point geometry's coordinates (points are not arc encoded)
+fn transform_position(position: [f64; 2], transform: &Option<TopoTransform>) -> [f64; 2] {
+    if let Some(transform) = transform {
+        [
+            position[0] * transform.scale[0] + transform.translate[0],
+            position[1] * transform.scale[1] + transform.translate[1],
+        ]
+    } else {
+        position
+    }
+}
+
+/// Stitch a sequence of arc indices into a continuous line. A negative index ~i
+/// indicates that arc i should be traversed in reverse order. Junction points
+/// shared by consecutive arcs are deduplicated
+fn stitch_arcs(indices: &[Value], arcs: &[Vec<[f64; 2]>]) -> Result<Vec<[f64; 2]>> {
+    let mut line: Vec<[f64; 2]> = Vec::new();
+    for index in indices {
+        let index = index
+            .as_i64()
+            .with_context(|| "TopoJSON arc index is not an integer".to_string())?;
+        let (arc_index, reversed) = if index < 0 {
+            ((!index) as usize, true)
+        } else {
+            (index as usize, false)
+        };
+        let arc = arcs
+            .get(arc_index)
+            .with_context(|| format!("TopoJSON arc index {} out of bounds", arc_index))?;
+
+        let mut arc = arc.clone();
+        if reversed {
+            arc.reverse();
+        }
+
+        // Skip the first point when it repeats the previous arc's endpoint
+        if let (Some(last), Some(first)) = (line.last(), arc.first()) {
+            if last == first {
+                arc.remove(0);
+            }
+        }
+        line.extend(arc);
+    }
+    Ok(line)
+}
+
+fn positions_to_json(positions: &[[f64; 2]]) -> Value {
+    Value::Array(
+        positions
+            .iter()
+            .map(|p| json!([p[0], p[1]]))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Convert a TopoJSON geometry object to the equivalent GeoJSON geometry
+fn geometry_to_geojson(
+    geometry: &Value,
+    arcs: &[Vec<[f64; 2]>],
+    transform: &Option<TopoTransform>,
+) -> Result<Value> {
+    let geom_type = geometry
+        .get("type")
+        .and_then(|t| t.as_str())
+        .with_context(|| "TopoJSON geometry is missing the type member".to_string())?;
+
+    let geom_arcs = || -> Result<&Vec<Value>> {
+        geometry
+            .get("arcs")
+            .and_then(|arcs| arcs.as_array())
+            .with_context(|| format!("TopoJSON {} is missing the arcs member", geom_type))
+    };
+
+    let coordinates = match geom_type {
+        "Point" => {
+            let position = extract_point(geometry.get("coordinates"))
+                .with_context(|| "TopoJSON Point is missing coordinates".to_string())?;
+            let p = transform_position(position, transform);
+            json!([p[0], p[1]])
+        }
+        "MultiPoint" => {
+            let positions = geometry
+                .get("coordinates")
+                .and_then(|c| c.as_array())
+                .with_context(|| "TopoJSON MultiPoint is missing coordinates".to_string())?;
+            let positions = positions
+                .iter()
+                .map(|p| {
+                    let position = extract_point(Some(p)).with_context(|| {
+                        "TopoJSON MultiPoint position is not a number pair".to_string()
+                    })?;
+                    Ok(transform_position(position, transform))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            positions_to_json(&positions)
+        }
+        "LineString" => positions_to_json(&stitch_arcs(geom_arcs()?, arcs)?),
+        "MultiLineString" | "Polygon" => {
+            let lines = geom_arcs()?
+                .iter()
+                .map(|line| {
+                    let line = line
+                        .as_array()
+                        .with_context(|| "TopoJSON arc list is not an array".to_string())?;
+                    Ok(positions_to_json(&stitch_arcs(line, arcs)?))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Value::Array(lines)
+        }
+        "MultiPolygon" => {
+            let polygons = geom_arcs()?
+                .iter()
+                .map(|polygon| {
+                    let rings = polygon
+                        .as_array()
+                        .with_context(|| "TopoJSON polygon is not an array".to_string())?
+                        .iter()
+                        .map(|ring| {
+                            let ring = ring.as_array().with_context(|| {
+                                "TopoJSON ring is not an array".to_string()
+                            })?;
+                            Ok(positions_to_json(&stitch_arcs(ring, arcs)?))
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    Ok(Value::Array(rings))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Value::Array(polygons)
+        }
+        "GeometryCollection" => {
+            let geometries = geometry
+                .get("geometries")
+                .and_then(|g| g.as_array())
+                .with_context(|| {
+                    "TopoJSON GeometryCollection is missing the geometries member".to_string()
+                })?
+                .iter()
+                .map(|geometry| geometry_to_geojson(geometry, arcs, transform))
+                .collect::<Result<Vec<_>>>()?;
+            return Ok(json!({
+                "type": "GeometryCollection",
+                "geometries": geometries,
+            }));
+        }
+        unsupported => {
+            return Err(VegaFusionError::parse(&format!(
+                "Unsupported TopoJSON geometry type: {}",
+                unsupported
+            )))
+        }
+    };
+
+    Ok(json!({
+        "type": geom_type,
+        "coordinates": coordinates,
+    }))
+}
+
+/// Convert a TopoJSON geometry object to a GeoJSON Feature, preserving the
+/// geometry's id and properties
+fn geometry_to_feature(
+    geometry: &Value,
+    arcs: &[Vec<[f64; 2]>],
+    transform: &Option<TopoTransform>,
+) -> Result<Value> {
+    let geojson_geometry = geometry_to_geojson(geometry, arcs, transform)?;
+    let mut feature = json!({
+        "type": "Feature",
+        "geometry": geojson_geometry,
+        "properties": geometry.get("properties").cloned().unwrap_or(json!({})),
+    });
+    if let Some(id) = geometry.get("id") {
+        feature["id"] = id.clone();
+    }
+    Ok(feature)
+}
+
+fn named_object<'a>(topology: &'a Value, name: &str) -> Result<&'a Value> {
+    topology
+        .get("objects")
+        .and_then(|objects| objects.get(name))
+        .with_context(|| format!("TopoJSON topology has no object named {}", name))
+}
+
+/// Extract the named object from a topology as an array of GeoJSON Features.
+/// This matches the behavior of `format: {type: "topojson", feature: name}`
+pub fn feature_to_geojson(topology: &Value, name: &str) -> Result<Value> {
+    let arcs = decode_arcs(topology)?;
+    let transform = TopoTransform::from_topology(topology)?;
+    let object = named_object(topology, name)?;
+
+    let features = if object.get("type").and_then(|t| t.as_str()) == Some("GeometryCollection") {
+        object
+            .get("geometries")
+            .and_then(|g| g.as_array())
+            .with_context(|| {
+                "TopoJSON GeometryCollection is missing the geometries member".to_string()
+            })?
+            .iter()
+            .map(|geometry| geometry_to_feature(geometry, &arcs, &transform))
+            .collect::<Result<Vec<_>>>()?
+    } else {
+        vec![geometry_to_feature(object, &arcs, &transform)?]
+    };
+
+    Ok(Value::Array(features))
+}
+
+/// Extract the mesh of the named object as a single GeoJSON MultiLineString
+/// Feature. This matches `format: {type: "topojson", mesh: name}`, except that
+/// arcs are not filtered or merged
+pub fn mesh_to_geojson(topology: &Value, name: &str) -> Result<Value> {
+    let arcs = decode_arcs(topology)?;
+
+    // Collect the indices of the arcs referenced by the named object
+    let object = named_object(topology, name)?;
+    let mut arc_indices: Vec<usize> = Vec::new();
+    collect_arc_indices(object, &mut arc_indices)?;
+    arc_indices.sort_unstable();
+    arc_indices.dedup();
+
+    let lines = arc_indices
+        .into_iter()
+        .map(|index| {
+            let arc = arcs
+                .get(index)
+                .with_context(|| format!("TopoJSON arc index {} out of bounds", index))?;
+            Ok(positions_to_json(arc))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(json!([{
+        "type": "Feature",
+        "geometry": {
+            "type": "MultiLineString",
+            "coordinates": lines,
+        },
+        "properties": {},
+    }]))
+}
+
+/// Recursively collect the (non-negative) arc indices referenced by a geometry
+fn collect_arc_indices(geometry: &Value, indices: &mut Vec<usize>) -> Result<()> {
+    if let Some(geometries) = geometry.get("geometries").and_then(|g| g.as_array()) {
+        for geometry in geometries {
+            collect_arc_indices(geometry, indices)?;
+        }
+    }
+    if let Some(arcs) = geometry.get("arcs") {
+        collect_arc_values(arcs, indices)?;
+    }
+    Ok(())
+}
+
+fn collect_arc_values(value: &Value, indices: &mut Vec<usize>) -> Result<()> {
+    match value {
+        Value::Array(values) => {
+            for value in values {
+                collect_arc_values(value, indices)?;
+            }
+            Ok(())
+        }
+        Value::Number(_) => {
+            let index = value
+                .as_i64()
+                .with_context(|| "TopoJSON arc index is not an integer".to_string())?;
+            let index = if index < 0 { !index } else { index };
+            indices.push(index as usize);
+            Ok(())
+        }
+        _ => Err(VegaFusionError::parse(
+            "TopoJSON arc index is not an integer",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn example_topology() -> Value {
+        // Quantized topology in the style of the TopoJSON specification examples.
+        // The transform uses power-of-two scales so the expected coordinates are
+        // exact floating point values
+        json!({
+            "type": "Topology",
+            "transform": {"scale": [2.0, 2.0], "translate": [100.0, 0.0]},
+            "objects": {
+                "example": {
+                    "type": "GeometryCollection",
+                    "geometries": [
+                        {
+                            "type": "Point",
+                            "properties": {"prop0": "value0"},
+                            "coordinates": [5, 5]
+                        },
+                        {
+                            "type": "LineString",
+                            "id": "line0",
+                            "properties": {"prop0": "value0"},
+                            "arcs": [0]
+                        }
+                    ]
+                }
+            },
+            "arcs": [
+                [[1, 1], [2, 2], [3, 3]]
+            ]
+        })
+    }
+
+    #[test]
+    fn test_feature_to_geojson() {
+        let features = feature_to_geojson(&example_topology(), "example").unwrap();
+        let features = features.as_array().unwrap();
+        assert_eq!(features.len(), 2);
+
+        // Point coordinates are transformed
+        assert_eq!(features[0]["geometry"]["type"], json!("Point"));
+        assert_eq!(features[0]["geometry"]["coordinates"], json!([110.0, 10.0]));
+        assert_eq!(features[0]["properties"]["prop0"], json!("value0"));
+
+        // Arc positions are delta decoded and transformed
+        assert_eq!(features[1]["id"], json!("line0"));
+        assert_eq!(features[1]["geometry"]["type"], json!("LineString"));
+        assert_eq!(
+            features[1]["geometry"]["coordinates"],
+            json!([[102.0, 2.0], [106.0, 6.0], [112.0, 12.0]])
+        );
+    }
+
+    #[test]
+    fn test_mesh_to_geojson() {
+        let mesh = mesh_to_geojson(&example_topology(), "example").unwrap();
+        let mesh = mesh.as_array().unwrap();
+        assert_eq!(mesh.len(), 1);
+        assert_eq!(mesh[0]["geometry"]["type"], json!("MultiLineString"));
+        let lines = mesh[0]["geometry"]["coordinates"].as_array().unwrap();
+        assert_eq!(lines.len(), 1);
+    }
+}
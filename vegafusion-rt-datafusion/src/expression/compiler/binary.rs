@@ -6,12 +6,17 @@
  * Please consult the license documentation provided alongside
  * this program the details of the active license.
  */
+use crate::expression::compiler::builtin_functions::math::bitwise::{
+    make_bitwise_and_udf, make_bitwise_or_udf, make_bitwise_xor_udf, make_shift_left_udf,
+    make_shift_right_udf, make_shift_right_unsigned_udf,
+};
 use crate::expression::compiler::utils::{
     cast_to, data_type, is_null_literal, is_numeric_datatype, is_string_datatype, to_numeric,
     to_string,
 };
 use crate::expression::compiler::{compile, config::CompilationConfig};
 use datafusion::logical_plan::{concat, lit, DFSchema, Expr, Operator};
+use std::sync::Arc;
 use vegafusion_core::arrow::datatypes::DataType;
 use vegafusion_core::error::Result;
 use vegafusion_core::proto::gen::expression::{BinaryExpression, BinaryOperator};
@@ -171,6 +176,30 @@ pub fn compile_binary(
             }
             // TODO: if both null, then equal. If one null, then not equal
         }
+        BinaryOperator::BitwiseAnd => Expr::ScalarUDF {
+            fun: Arc::new(make_bitwise_and_udf()),
+            args: vec![lhs_numeric, rhs_numeric],
+        },
+        BinaryOperator::BitwiseOr => Expr::ScalarUDF {
+            fun: Arc::new(make_bitwise_or_udf()),
+            args: vec![lhs_numeric, rhs_numeric],
+        },
+        BinaryOperator::BitwiseXor => Expr::ScalarUDF {
+            fun: Arc::new(make_bitwise_xor_udf()),
+            args: vec![lhs_numeric, rhs_numeric],
+        },
+        BinaryOperator::LeftShift => Expr::ScalarUDF {
+            fun: Arc::new(make_shift_left_udf()),
+            args: vec![lhs_numeric, rhs_numeric],
+        },
+        BinaryOperator::RightShift => Expr::ScalarUDF {
+            fun: Arc::new(make_shift_right_udf()),
+            args: vec![lhs_numeric, rhs_numeric],
+        },
+        BinaryOperator::UnsignedRightShift => Expr::ScalarUDF {
+            fun: Arc::new(make_shift_right_unsigned_udf()),
+            args: vec![lhs_numeric, rhs_numeric],
+        },
     };
 
     Ok(new_expr)
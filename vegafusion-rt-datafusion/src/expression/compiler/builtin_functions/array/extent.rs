@@ -0,0 +1,83 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion::physical_plan::ColumnarValue;
+use datafusion::scalar::ScalarValue;
+use datafusion_expr::{ReturnTypeFunction, ScalarFunctionImplementation, Signature, Volatility};
+use std::sync::Arc;
+use vegafusion_core::data::scalar::ScalarValueHelpers;
+
+/// `extent(array)`
+///
+/// Returns a new [min, max] array with the minimum and maximum values of the input array,
+/// ignoring null, undefined, and NaN values.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#extent
+pub fn make_extent_udf() -> ScalarUDF {
+    let extent_fn: ScalarFunctionImplementation = Arc::new(|args: &[ColumnarValue]| {
+        // Signature ensures there is a single argument
+        let arg = &args[0];
+        Ok(match arg {
+            ColumnarValue::Scalar(value) => match value {
+                ScalarValue::List(Some(arr), _) => {
+                    let mut min = f64::NAN;
+                    let mut max = f64::NAN;
+                    for el in arr.iter() {
+                        if let Ok(v) = el.to_f64() {
+                            if !v.is_nan() {
+                                if !(min <= v) {
+                                    min = v;
+                                }
+                                if !(max >= v) {
+                                    max = v;
+                                }
+                            }
+                        }
+                    }
+                    let elements = if min.is_nan() {
+                        // No valid elements, extent is [null, null]
+                        vec![
+                            ScalarValue::Float64(None),
+                            ScalarValue::Float64(None),
+                        ]
+                    } else {
+                        vec![ScalarValue::from(min), ScalarValue::from(max)]
+                    };
+                    ColumnarValue::Scalar(ScalarValue::List(
+                        Some(elements),
+                        Box::new(DataType::Float64),
+                    ))
+                }
+                _ => ColumnarValue::Scalar(ScalarValue::List(
+                    None,
+                    Box::new(DataType::Float64),
+                )),
+            },
+            ColumnarValue::Array(_array) => {
+                todo!("extent on column not yet implemented")
+            }
+        })
+    });
+
+    let return_type: ReturnTypeFunction = Arc::new(move |_| {
+        Ok(Arc::new(DataType::List(Box::new(Field::new(
+            "item",
+            DataType::Float64,
+            true,
+        )))))
+    });
+
+    ScalarUDF::new(
+        "extent",
+        &Signature::any(1, Volatility::Immutable),
+        &return_type,
+        &extent_fn,
+    )
+}
@@ -0,0 +1,103 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use datafusion::arrow::datatypes::DataType;
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion::physical_plan::ColumnarValue;
+use datafusion::scalar::ScalarValue;
+use datafusion_expr::{ReturnTypeFunction, ScalarFunctionImplementation, Signature, Volatility};
+use std::sync::Arc;
+use vegafusion_core::data::scalar::ScalarValueHelpers;
+
+/// Whether two scalar elements are equal under JavaScript `===` semantics, as used
+/// by Array.prototype.indexOf
+fn elements_equal(a: &ScalarValue, b: &ScalarValue) -> bool {
+    if let (Ok(a), Ok(b)) = (a.to_f64(), b.to_f64()) {
+        // Compare numerically so e.g. Int32(2) matches Float64(2.0)
+        a == b
+    } else if let (Ok(a), Ok(b)) = (a.to_scalar_string(), b.to_scalar_string()) {
+        a == b
+    } else {
+        a == b
+    }
+}
+
+fn make_index_udf(name: &'static str, last: bool) -> ScalarUDF {
+    let index_fn: ScalarFunctionImplementation = Arc::new(move |args: &[ColumnarValue]| {
+        // Signature ensures there are two arguments
+        let target = match &args[0] {
+            ColumnarValue::Scalar(value) => value.clone(),
+            ColumnarValue::Array(_) => {
+                todo!("{} on column not yet implemented", name)
+            }
+        };
+        let item = match &args[1] {
+            ColumnarValue::Scalar(value) => value.clone(),
+            ColumnarValue::Array(_) => {
+                todo!("{} on column not yet implemented", name)
+            }
+        };
+
+        let index: i32 = match &target {
+            ScalarValue::List(Some(arr), _) => {
+                let mut matches = arr.iter().enumerate().filter_map(|(i, el)| {
+                    if elements_equal(el, &item) {
+                        Some(i as i32)
+                    } else {
+                        None
+                    }
+                });
+                if last {
+                    matches.last().unwrap_or(-1)
+                } else {
+                    matches.next().unwrap_or(-1)
+                }
+            }
+            ScalarValue::Utf8(Some(s)) | ScalarValue::LargeUtf8(Some(s)) => {
+                let item = item.to_scalar_string().unwrap_or_default();
+                let byte_index = if last { s.rfind(&item) } else { s.find(&item) };
+                match byte_index {
+                    // Convert byte offset to character offset for JS parity
+                    Some(byte_index) => s[..byte_index].chars().count() as i32,
+                    None => -1,
+                }
+            }
+            _ => -1,
+        };
+
+        Ok(ColumnarValue::Scalar(ScalarValue::from(index)))
+    });
+
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Int32)));
+    ScalarUDF::new(
+        name,
+        &Signature::any(2, Volatility::Immutable),
+        &return_type,
+        &index_fn,
+    )
+}
+
+/// `indexof(array, value)` / `indexof(string, substring)`
+///
+/// Returns the first index of value in the input array, or the first index of
+/// substring in the input string. Returns -1 when not found, matching JavaScript.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#indexof
+pub fn make_indexof_udf() -> ScalarUDF {
+    make_index_udf("indexof", false)
+}
+
+/// `lastindexof(array, value)` / `lastindexof(string, substring)`
+///
+/// Returns the last index of value in the input array, or the last index of
+/// substring in the input string. Returns -1 when not found, matching JavaScript.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#lastindexof
+pub fn make_lastindexof_udf() -> ScalarUDF {
+    make_index_udf("lastindexof", true)
+}
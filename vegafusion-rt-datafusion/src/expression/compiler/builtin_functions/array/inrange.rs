@@ -0,0 +1,133 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use datafusion::arrow::array::{BooleanArray, Float64Array};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion::physical_plan::ColumnarValue;
+use datafusion::scalar::ScalarValue;
+use datafusion_expr::{ReturnTypeFunction, ScalarFunctionImplementation, Signature, Volatility};
+use std::sync::Arc;
+use vegafusion_core::data::scalar::ScalarValueHelpers;
+
+/// `inrange(value, range)`
+///
+/// Tests whether value lies within (or is equal to either of) the first and
+/// last values of the range array.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#inrange
+pub fn make_inrange_udf() -> ScalarUDF {
+    let inrange_fn: ScalarFunctionImplementation = Arc::new(|args: &[ColumnarValue]| {
+        // Signature ensures there are two arguments
+        let (low, high) = match &args[1] {
+            ColumnarValue::Scalar(ScalarValue::List(Some(elements), _)) if elements.len() >= 2 => {
+                let first = elements.first().unwrap().to_f64().unwrap_or(f64::NAN);
+                let last = elements.last().unwrap().to_f64().unwrap_or(f64::NAN);
+                (first.min(last), first.max(last))
+            }
+            _ => (f64::NAN, f64::NAN),
+        };
+
+        Ok(match &args[0] {
+            ColumnarValue::Scalar(value) => {
+                let in_range = value
+                    .to_f64()
+                    .map(|v| v >= low && v <= high)
+                    .unwrap_or(false);
+                ColumnarValue::Scalar(ScalarValue::Boolean(Some(in_range)))
+            }
+            ColumnarValue::Array(array) => {
+                let array = cast(array, &DataType::Float64).expect(
+                    "Failed to cast inrange argument to Float64",
+                );
+                let array = array.as_any().downcast_ref::<Float64Array>().unwrap();
+                let in_range: BooleanArray = array
+                    .iter()
+                    .map(|v| v.map(|v| v >= low && v <= high))
+                    .collect();
+                ColumnarValue::Array(Arc::new(in_range))
+            }
+        })
+    });
+
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Boolean)));
+    ScalarUDF::new(
+        "inrange",
+        &Signature::any(2, Volatility::Immutable),
+        &return_type,
+        &inrange_fn,
+    )
+}
+
+/// `clampRange(range, min, max)`
+///
+/// Clamps a two-element range array in a span-preserving manner. If the span of
+/// the input range is less than (max - min) and an endpoint exceeds either the
+/// min or max value, the range is translated such that the span is preserved
+/// and one of the endpoints touches the boundary of the [min, max] range.
+/// If the span exceeds (max - min), the range [min, max] is returned.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#clampRange
+pub fn make_clamp_range_udf() -> ScalarUDF {
+    let clamp_range_fn: ScalarFunctionImplementation = Arc::new(|args: &[ColumnarValue]| {
+        // Signature ensures there are three arguments
+        let (lo, hi) = match &args[0] {
+            ColumnarValue::Scalar(ScalarValue::List(Some(elements), _)) if elements.len() >= 2 => {
+                let first = elements.first().unwrap().to_f64().unwrap_or(f64::NAN);
+                let last = elements.last().unwrap().to_f64().unwrap_or(f64::NAN);
+                (first.min(last), first.max(last))
+            }
+            _ => {
+                panic!("The first argument to clampRange must be an array")
+            }
+        };
+        let min = extract_f64(&args[1], "clampRange");
+        let max = extract_f64(&args[2], "clampRange");
+
+        let span = hi - lo;
+        let (lo, hi) = if span >= max - min {
+            (min, max)
+        } else if lo < min {
+            (min, min + span)
+        } else if hi > max {
+            (max - span, max)
+        } else {
+            (lo, hi)
+        };
+
+        Ok(ColumnarValue::Scalar(ScalarValue::List(
+            Some(vec![
+                ScalarValue::Float64(Some(lo)),
+                ScalarValue::Float64(Some(hi)),
+            ]),
+            Box::new(DataType::Float64),
+        )))
+    });
+
+    let return_type: ReturnTypeFunction = Arc::new(move |_| {
+        Ok(Arc::new(DataType::List(Box::new(
+            datafusion::arrow::datatypes::Field::new("item", DataType::Float64, true),
+        ))))
+    });
+    ScalarUDF::new(
+        "clampRange",
+        &Signature::any(3, Volatility::Immutable),
+        &return_type,
+        &clamp_range_fn,
+    )
+}
+
+fn extract_f64(value: &ColumnarValue, fn_name: &str) -> f64 {
+    match value {
+        ColumnarValue::Scalar(scalar) => scalar.to_f64().unwrap_or(f64::NAN),
+        ColumnarValue::Array(_) => {
+            panic!("The min and max arguments to {} must be scalars", fn_name)
+        }
+    }
+}
@@ -0,0 +1,55 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use datafusion::arrow::datatypes::DataType;
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion::physical_plan::ColumnarValue;
+use datafusion::scalar::ScalarValue;
+use datafusion_expr::{ReturnTypeFunction, ScalarFunctionImplementation, Signature, Volatility};
+use std::sync::Arc;
+use vegafusion_core::data::scalar::ScalarValueHelpers;
+
+/// `join(array[, separator])`
+///
+/// Returns a new string by concatenating all of the elements of the input array,
+/// separated by commas or a specified separator string.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#join
+pub fn make_join_udf() -> ScalarUDF {
+    let join_fn: ScalarFunctionImplementation = Arc::new(|args: &[ColumnarValue]| {
+        let separator = match args.get(1) {
+            Some(ColumnarValue::Scalar(ScalarValue::Utf8(Some(sep)))) => sep.clone(),
+            _ => ",".to_string(),
+        };
+
+        let arg = &args[0];
+        Ok(match arg {
+            ColumnarValue::Scalar(value) => match value {
+                ScalarValue::List(Some(arr), _) => {
+                    let elements: Vec<_> = arr
+                        .iter()
+                        .map(|el| el.to_scalar_string().unwrap_or_default())
+                        .collect();
+                    ColumnarValue::Scalar(ScalarValue::from(elements.join(&separator).as_str()))
+                }
+                _ => ColumnarValue::Scalar(ScalarValue::Utf8(None)),
+            },
+            ColumnarValue::Array(_array) => {
+                todo!("join on column not yet implemented")
+            }
+        })
+    });
+
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Utf8)));
+    ScalarUDF::new(
+        "join",
+        &Signature::variadic_any(Volatility::Immutable),
+        &return_type,
+        &join_fn,
+    )
+}
@@ -6,8 +6,9 @@
  * Please consult the license documentation provided alongside
  * this program the details of the active license.
  */
-use datafusion::arrow::array::{new_null_array, Array, Int32Array, ListArray};
-use datafusion::arrow::compute::kernels;
+use datafusion::arrow::array::{
+    new_null_array, Array, Int32Array, LargeStringArray, ListArray, StringArray,
+};
 use datafusion::arrow::datatypes::DataType;
 use datafusion::physical_plan::udf::ScalarUDF;
 use datafusion::physical_plan::ColumnarValue;
@@ -18,7 +19,9 @@ use std::sync::Arc;
 
 /// `length(array)`
 ///
-/// Returns the length of the input array or string
+/// Returns the length of the input array, string, or dataset reference (row count for
+/// `data('name')` results, which compile to arrays of objects). String lengths are
+/// measured in characters to match JavaScript, not in encoded bytes
 ///
 /// See https://vega.github.io/vega/docs/expressions/#length
 pub fn make_length_udf() -> ScalarUDF {
@@ -32,7 +35,7 @@ pub fn make_length_udf() -> ScalarUDF {
                         ColumnarValue::Scalar(ScalarValue::from(arr.len() as i32))
                     }
                     ScalarValue::Utf8(Some(s)) | ScalarValue::LargeUtf8(Some(s)) => {
-                        ColumnarValue::Scalar(ScalarValue::from(s.len() as i32))
+                        ColumnarValue::Scalar(ScalarValue::from(s.chars().count() as i32))
                     }
                     _ => {
                         // Scalar i32 null
@@ -42,9 +45,22 @@ pub fn make_length_udf() -> ScalarUDF {
             }
             ColumnarValue::Array(array) => {
                 match array.data_type() {
-                    DataType::Utf8 | DataType::LargeUtf8 => {
-                        // String length
-                        ColumnarValue::Array(kernels::length::length(array.as_ref()).unwrap())
+                    DataType::Utf8 => {
+                        // String length in characters
+                        let array = array.as_any().downcast_ref::<StringArray>().unwrap();
+                        let lengths: Int32Array = array
+                            .iter()
+                            .map(|s| s.map(|s| s.chars().count() as i32))
+                            .collect();
+                        ColumnarValue::Array(Arc::new(lengths))
+                    }
+                    DataType::LargeUtf8 => {
+                        let array = array.as_any().downcast_ref::<LargeStringArray>().unwrap();
+                        let lengths: Int32Array = array
+                            .iter()
+                            .map(|s| s.map(|s| s.chars().count() as i32))
+                            .collect();
+                        ColumnarValue::Array(Arc::new(lengths))
                     }
                     DataType::FixedSizeList(_, n) => {
                         // Use scalar length
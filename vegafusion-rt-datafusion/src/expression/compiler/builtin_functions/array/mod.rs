@@ -12,5 +12,14 @@ Functions for working with arrays of values.
 
 See https://vega.github.io/vega/docs/expressions/#array-functions
  */
+pub mod extent;
+pub mod indexof;
+pub mod inrange;
+pub mod join;
 pub mod length;
+pub mod pluck;
+pub mod reverse;
+pub mod sequence;
+pub mod slice;
+pub mod sort;
 pub mod span;
@@ -0,0 +1,77 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use datafusion::arrow::datatypes::DataType;
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion::physical_plan::ColumnarValue;
+use datafusion::scalar::ScalarValue;
+use datafusion_expr::{ReturnTypeFunction, ScalarFunctionImplementation, Signature, Volatility};
+use std::sync::Arc;
+
+/// `pluck(array, field)`
+///
+/// Retrieves the value for the specified field from a given array of objects.
+/// The input array should consist of objects (structs); elements without the
+/// field, or null elements, contribute null to the result.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#pluck
+pub fn make_pluck_udf() -> ScalarUDF {
+    let pluck_fn: ScalarFunctionImplementation = Arc::new(|args: &[ColumnarValue]| {
+        // Signature ensures there are two arguments
+        let field = match &args[1] {
+            ColumnarValue::Scalar(ScalarValue::Utf8(Some(field))) => field.clone(),
+            _ => {
+                panic!("The second argument to pluck must be a string")
+            }
+        };
+
+        Ok(match &args[0] {
+            ColumnarValue::Scalar(ScalarValue::List(Some(elements), _)) => {
+                let mut plucked: Vec<ScalarValue> = Vec::new();
+                for element in elements.iter() {
+                    let value = if let ScalarValue::Struct(Some(struct_values), struct_fields) =
+                        element
+                    {
+                        struct_fields
+                            .iter()
+                            .position(|f| f.name() == &field)
+                            .and_then(|index| struct_values.get(index).cloned())
+                    } else {
+                        None
+                    };
+                    plucked.push(value.unwrap_or(ScalarValue::Float64(None)));
+                }
+
+                let dtype = plucked
+                    .iter()
+                    .find(|v| !v.is_null())
+                    .map(|v| v.get_datatype())
+                    .unwrap_or(DataType::Float64);
+                ColumnarValue::Scalar(ScalarValue::List(Some(plucked), Box::new(dtype)))
+            }
+            ColumnarValue::Scalar(_) => {
+                ColumnarValue::Scalar(ScalarValue::List(None, Box::new(DataType::Float64)))
+            }
+            ColumnarValue::Array(_array) => {
+                todo!("pluck on column not yet implemented")
+            }
+        })
+    });
+
+    let return_type: ReturnTypeFunction = Arc::new(move |_| {
+        Ok(Arc::new(DataType::List(Box::new(
+            datafusion::arrow::datatypes::Field::new("item", DataType::Float64, true),
+        ))))
+    });
+    ScalarUDF::new(
+        "pluck",
+        &Signature::any(2, Volatility::Immutable),
+        &return_type,
+        &pluck_fn,
+    )
+}
@@ -0,0 +1,50 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion::physical_plan::ColumnarValue;
+use datafusion::scalar::ScalarValue;
+use datafusion_expr::{ReturnTypeFunction, ScalarFunctionImplementation, Signature, Volatility};
+use std::sync::Arc;
+
+/// `reverse(array)`
+///
+/// Returns a new array with elements in a reverse order of the input array.
+/// The input array is unchanged.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#reverse
+pub fn make_reverse_udf() -> ScalarUDF {
+    let reverse_fn: ScalarFunctionImplementation = Arc::new(|args: &[ColumnarValue]| {
+        // Signature ensures there is a single argument
+        let arg = &args[0];
+        Ok(match arg {
+            ColumnarValue::Scalar(value) => match value {
+                ScalarValue::List(Some(arr), element_type) => {
+                    let mut elements = arr.clone();
+                    elements.reverse();
+                    ColumnarValue::Scalar(ScalarValue::List(
+                        Some(elements),
+                        element_type.clone(),
+                    ))
+                }
+                _ => ColumnarValue::Scalar(value.clone()),
+            },
+            ColumnarValue::Array(_array) => {
+                todo!("reverse on column not yet implemented")
+            }
+        })
+    });
+
+    let return_type: ReturnTypeFunction = Arc::new(move |dtype| Ok(Arc::new(dtype[0].clone())));
+    ScalarUDF::new(
+        "reverse",
+        &Signature::any(1, Volatility::Immutable),
+        &return_type,
+        &reverse_fn,
+    )
+}
@@ -0,0 +1,80 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion::physical_plan::ColumnarValue;
+use datafusion::scalar::ScalarValue;
+use datafusion_expr::{ReturnTypeFunction, ScalarFunctionImplementation, Signature, Volatility};
+use std::sync::Arc;
+use vegafusion_core::data::scalar::ScalarValueHelpers;
+
+/// `sequence([start, ]stop[, step])`
+///
+/// Returns an array containing an arithmetic sequence of numbers. If step is omitted,
+/// it defaults to 1. If start is omitted, it defaults to 0. The stop value is exclusive;
+/// it is not included in the result. If step is positive, the last element is the largest
+/// start + i * step less than stop; if step is negative, the last element is the smallest
+/// start + i * step greater than stop.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#sequence
+pub fn make_sequence_udf() -> ScalarUDF {
+    let sequence_fn: ScalarFunctionImplementation = Arc::new(|args: &[ColumnarValue]| {
+        let mut scalar_args: Vec<f64> = Vec::new();
+        for arg in args {
+            match arg {
+                ColumnarValue::Scalar(value) => {
+                    scalar_args.push(value.to_f64().unwrap_or(f64::NAN));
+                }
+                ColumnarValue::Array(_) => {
+                    todo!("sequence on column not yet implemented")
+                }
+            }
+        }
+
+        let (start, stop, step) = match scalar_args.len() {
+            1 => (0.0, scalar_args[0], 1.0),
+            2 => (scalar_args[0], scalar_args[1], 1.0),
+            3 => (scalar_args[0], scalar_args[1], scalar_args[2]),
+            _ => {
+                panic!("Unexpected number of arguments to sequence")
+            }
+        };
+
+        let mut elements: Vec<ScalarValue> = Vec::new();
+        if step != 0.0 && step.is_finite() {
+            let n = ((stop - start) / step).ceil().max(0.0) as usize;
+            for i in 0..n {
+                elements.push(ScalarValue::from(start + i as f64 * step));
+            }
+        }
+
+        Ok(ColumnarValue::Scalar(ScalarValue::List(
+            Some(elements),
+            Box::new(DataType::Float64),
+        )))
+    });
+
+    let return_type: ReturnTypeFunction = Arc::new(move |_| {
+        Ok(Arc::new(DataType::List(Box::new(Field::new(
+            "item",
+            DataType::Float64,
+            true,
+        )))))
+    });
+
+    ScalarUDF::new(
+        "sequence",
+        &Signature::variadic(
+            vec![DataType::Float64, DataType::Int64, DataType::Int32],
+            Volatility::Immutable,
+        ),
+        &return_type,
+        &sequence_fn,
+    )
+}
@@ -0,0 +1,88 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion::physical_plan::ColumnarValue;
+use datafusion::scalar::ScalarValue;
+use datafusion_expr::{ReturnTypeFunction, ScalarFunctionImplementation, Signature, Volatility};
+use std::sync::Arc;
+use vegafusion_core::data::scalar::ScalarValueHelpers;
+
+/// Resolve a JavaScript-style slice index (which may be negative or out of bounds)
+/// against a length
+fn resolve_index(index: Option<f64>, default: usize, len: usize) -> usize {
+    match index {
+        Some(i) if i < 0.0 => ((len as f64 + i).max(0.0)) as usize,
+        Some(i) => (i as usize).min(len),
+        None => default,
+    }
+}
+
+/// `slice(array, start[, end])`
+///
+/// Returns a section of array between the start and end indices. If the end argument
+/// is negative, it is treated as an offset from the end of the array
+/// (length(array) + end). Also applies to string values.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#slice
+pub fn make_slice_udf() -> ScalarUDF {
+    let slice_fn: ScalarFunctionImplementation = Arc::new(|args: &[ColumnarValue]| {
+        let index_arg = |i: usize| -> Option<f64> {
+            match args.get(i) {
+                Some(ColumnarValue::Scalar(value)) => value.to_f64().ok(),
+                _ => None,
+            }
+        };
+        let start = index_arg(1);
+        let end = index_arg(2);
+
+        let arg = &args[0];
+        Ok(match arg {
+            ColumnarValue::Scalar(value) => match value {
+                ScalarValue::List(Some(arr), element_type) => {
+                    let len = arr.len();
+                    let start = resolve_index(start, 0, len);
+                    let end = resolve_index(end, len, len);
+                    let elements: Vec<_> = if start < end {
+                        arr[start..end].to_vec()
+                    } else {
+                        Vec::new()
+                    };
+                    ColumnarValue::Scalar(ScalarValue::List(
+                        Some(elements),
+                        element_type.clone(),
+                    ))
+                }
+                ScalarValue::Utf8(Some(s)) | ScalarValue::LargeUtf8(Some(s)) => {
+                    let chars: Vec<char> = s.chars().collect();
+                    let len = chars.len();
+                    let start = resolve_index(start, 0, len);
+                    let end = resolve_index(end, len, len);
+                    let sliced: String = if start < end {
+                        chars[start..end].iter().collect()
+                    } else {
+                        String::new()
+                    };
+                    ColumnarValue::Scalar(ScalarValue::from(sliced.as_str()))
+                }
+                _ => ColumnarValue::Scalar(value.clone()),
+            },
+            ColumnarValue::Array(_array) => {
+                todo!("slice on column not yet implemented")
+            }
+        })
+    });
+
+    let return_type: ReturnTypeFunction = Arc::new(move |dtype| Ok(Arc::new(dtype[0].clone())));
+    ScalarUDF::new(
+        "slice",
+        &Signature::variadic_any(Volatility::Immutable),
+        &return_type,
+        &slice_fn,
+    )
+}
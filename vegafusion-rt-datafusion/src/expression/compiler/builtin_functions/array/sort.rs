@@ -0,0 +1,60 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion::physical_plan::ColumnarValue;
+use datafusion::scalar::ScalarValue;
+use datafusion_expr::{ReturnTypeFunction, ScalarFunctionImplementation, Signature, Volatility};
+use std::cmp::Ordering;
+use std::sync::Arc;
+use vegafusion_core::data::scalar::ScalarValueHelpers;
+
+/// `sort(array)`
+///
+/// Sorts the array in ascending order. Numeric elements compare numerically;
+/// other elements compare by their string representation, matching JavaScript's
+/// default comparator closely enough for the numeric arrays Vega specs produce.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#sort
+pub fn make_sort_udf() -> ScalarUDF {
+    let sort_fn: ScalarFunctionImplementation = Arc::new(|args: &[ColumnarValue]| {
+        // Signature ensures there is a single argument
+        let arg = &args[0];
+        Ok(match arg {
+            ColumnarValue::Scalar(value) => match value {
+                ScalarValue::List(Some(arr), element_type) => {
+                    let mut elements = arr.clone();
+                    elements.sort_by(|a, b| match (a.to_f64(), b.to_f64()) {
+                        (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+                        _ => {
+                            let a = a.to_scalar_string().unwrap_or_default();
+                            let b = b.to_scalar_string().unwrap_or_default();
+                            a.cmp(&b)
+                        }
+                    });
+                    ColumnarValue::Scalar(ScalarValue::List(
+                        Some(elements),
+                        element_type.clone(),
+                    ))
+                }
+                _ => ColumnarValue::Scalar(value.clone()),
+            },
+            ColumnarValue::Array(_array) => {
+                todo!("sort on column not yet implemented")
+            }
+        })
+    });
+
+    let return_type: ReturnTypeFunction = Arc::new(move |dtype| Ok(Arc::new(dtype[0].clone())));
+    ScalarUDF::new(
+        "sort",
+        &Signature::any(1, Volatility::Immutable),
+        &return_type,
+        &sort_fn,
+    )
+}
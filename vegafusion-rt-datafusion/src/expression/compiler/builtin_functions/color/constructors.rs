@@ -0,0 +1,165 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use datafusion::arrow::datatypes::DataType;
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion::physical_plan::ColumnarValue;
+use datafusion::scalar::ScalarValue;
+use datafusion_expr::{ReturnTypeFunction, ScalarFunctionImplementation, Signature, Volatility};
+use std::sync::Arc;
+use vegafusion_core::data::scalar::ScalarValueHelpers;
+
+/// Format a floating point rgb triple as a CSS rgb() string, clamping channels to
+/// [0, 255] the way d3-color does
+pub fn format_rgb(r: f64, g: f64, b: f64) -> String {
+    let clamp = |v: f64| (v.round().max(0.0)).min(255.0) as u8;
+    format!("rgb({}, {}, {})", clamp(r), clamp(g), clamp(b))
+}
+
+/// Convert hsl channels (h in degrees, s and l in [0, 1]) to rgb channels in [0, 255]
+pub fn hsl_to_rgb(h: f64, s: f64, l: f64) -> (f64, f64, f64) {
+    let h = h.rem_euclid(360.0);
+    let m2 = if l <= 0.5 { l * (1.0 + s) } else { l + s - l * s };
+    let m1 = 2.0 * l - m2;
+    let hue = |h: f64| -> f64 {
+        let h = h.rem_euclid(360.0);
+        if h < 60.0 {
+            m1 + (m2 - m1) * h / 60.0
+        } else if h < 180.0 {
+            m2
+        } else if h < 240.0 {
+            m1 + (m2 - m1) * (240.0 - h) / 60.0
+        } else {
+            m1
+        }
+    };
+    (
+        hue(h + 120.0) * 255.0,
+        hue(h) * 255.0,
+        hue(h - 120.0) * 255.0,
+    )
+}
+
+// D65 standard referent, as used by d3-color
+const XN: f64 = 0.96422;
+const YN: f64 = 1.0;
+const ZN: f64 = 0.82521;
+const T0: f64 = 4.0 / 29.0;
+const T1: f64 = 6.0 / 29.0;
+const T2: f64 = 3.0 * T1 * T1;
+const T3: f64 = T1 * T1 * T1;
+
+/// Convert CIE L*a*b* channels to rgb channels in [0, 255]
+pub fn lab_to_rgb(l: f64, a: f64, b: f64) -> (f64, f64, f64) {
+    let y = (l + 16.0) / 116.0;
+    let x = y + a / 500.0;
+    let z = y - b / 200.0;
+
+    let lab_xyz = |t: f64| {
+        if t > T1 {
+            t * t * t
+        } else {
+            T2 * (t - T0)
+        }
+    };
+    let x = XN * lab_xyz(x);
+    let y = YN * lab_xyz(y);
+    let z = ZN * lab_xyz(z);
+
+    let xyz_rgb = |v: f64| {
+        255.0
+            * (if v <= 0.0031308 {
+                12.92 * v
+            } else {
+                1.055 * v.powf(1.0 / 2.4) - 0.055
+            })
+    };
+    (
+        xyz_rgb(3.1338561 * x - 1.6168667 * y - 0.4906146 * z),
+        xyz_rgb(-0.9787684 * x + 1.9161415 * y + 0.0334540 * z),
+        xyz_rgb(0.0719453 * x - 0.2289914 * y + 1.4052427 * z),
+    )
+}
+
+/// Convert CIE LCh(ab) channels (hcl) to rgb channels in [0, 255]
+pub fn hcl_to_rgb(h: f64, c: f64, l: f64) -> (f64, f64, f64) {
+    let h = h.to_radians();
+    lab_to_rgb(l, c * h.cos(), c * h.sin())
+}
+
+fn make_color_udf(
+    name: &'static str,
+    to_rgb: fn(f64, f64, f64) -> (f64, f64, f64),
+) -> ScalarUDF {
+    let color_fn: ScalarFunctionImplementation = Arc::new(move |args: &[ColumnarValue]| {
+        let mut channels: Vec<f64> = Vec::new();
+        for arg in args.iter().take(3) {
+            match arg {
+                ColumnarValue::Scalar(value) => {
+                    channels.push(value.to_f64().unwrap_or(f64::NAN));
+                }
+                ColumnarValue::Array(_) => {
+                    todo!("{} on column not yet implemented", name)
+                }
+            }
+        }
+        if channels.len() != 3 {
+            panic!("{} requires three numeric arguments", name)
+        }
+        let (r, g, b) = to_rgb(channels[0], channels[1], channels[2]);
+        Ok(ColumnarValue::Scalar(ScalarValue::from(
+            format_rgb(r, g, b).as_str(),
+        )))
+    });
+
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Utf8)));
+    ScalarUDF::new(
+        name,
+        &Signature::any(3, Volatility::Immutable),
+        &return_type,
+        &color_fn,
+    )
+}
+
+/// `rgb(r, g, b)`
+///
+/// Constructs a color with the specified red, green, and blue channels, serialized
+/// as a CSS rgb() string.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#rgb
+pub fn make_rgb_udf() -> ScalarUDF {
+    make_color_udf("rgb", |r, g, b| (r, g, b))
+}
+
+/// `hsl(h, s, l)`
+///
+/// Constructs a color with the specified hue, saturation, and lightness channels,
+/// serialized as a CSS rgb() string.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#hsl
+pub fn make_hsl_udf() -> ScalarUDF {
+    make_color_udf("hsl", hsl_to_rgb)
+}
+
+/// `lab(l, a, b)`
+///
+/// Constructs a color in the CIE L*a*b* color space, serialized as a CSS rgb() string.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#lab
+pub fn make_lab_udf() -> ScalarUDF {
+    make_color_udf("lab", |l, a, b| lab_to_rgb(l, a, b))
+}
+
+/// `hcl(h, c, l)`
+///
+/// Constructs a color in the CIE LCh(ab) color space, serialized as a CSS rgb() string.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#hcl
+pub fn make_hcl_udf() -> ScalarUDF {
+    make_color_udf("hcl", hcl_to_rgb)
+}
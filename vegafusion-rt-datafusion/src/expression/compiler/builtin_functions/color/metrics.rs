@@ -0,0 +1,129 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use datafusion::arrow::datatypes::DataType;
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion::physical_plan::ColumnarValue;
+use datafusion::scalar::ScalarValue;
+use datafusion_expr::{ReturnTypeFunction, ScalarFunctionImplementation, Signature, Volatility};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::sync::Arc;
+
+lazy_static! {
+    static ref RGB_RE: Regex =
+        Regex::new(r"^rgba?\(\s*(\d+)\s*,\s*(\d+)\s*,\s*(\d+)").unwrap();
+}
+
+/// Parse a CSS color string into rgb channels in [0, 255]. Supports the `#rgb` and
+/// `#rrggbb` hex forms and the `rgb()`/`rgba()` functional forms produced by the
+/// color constructor functions. Returns None for unsupported formats.
+pub fn parse_color(color: &str) -> Option<(f64, f64, f64)> {
+    let color = color.trim();
+    if let Some(hex) = color.strip_prefix('#') {
+        match hex.len() {
+            3 => {
+                let mut channels = hex.chars().filter_map(|c| c.to_digit(16));
+                let r = channels.next()?;
+                let g = channels.next()?;
+                let b = channels.next()?;
+                Some(((r * 17) as f64, (g * 17) as f64, (b * 17) as f64))
+            }
+            6 => {
+                let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
+                let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
+                let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+                Some((r as f64, g as f64, b as f64))
+            }
+            _ => None,
+        }
+    } else if let Some(captures) = RGB_RE.captures(color) {
+        let channel = |i: usize| -> Option<f64> { captures.get(i)?.as_str().parse().ok() };
+        Some((channel(1)?, channel(2)?, channel(3)?))
+    } else {
+        None
+    }
+}
+
+/// Relative luminance of rgb channels per the WCAG 2 definition, as computed by
+/// d3-color's color.luminance
+pub fn relative_luminance(r: f64, g: f64, b: f64) -> f64 {
+    let channel = |v: f64| {
+        let v = v / 255.0;
+        if v <= 0.03928 {
+            v / 12.92
+        } else {
+            ((v + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * channel(r) + 0.7152 * channel(g) + 0.0722 * channel(b)
+}
+
+fn scalar_color(arg: &ColumnarValue, name: &str) -> Option<(f64, f64, f64)> {
+    match arg {
+        ColumnarValue::Scalar(ScalarValue::Utf8(Some(s)))
+        | ColumnarValue::Scalar(ScalarValue::LargeUtf8(Some(s))) => parse_color(s),
+        ColumnarValue::Scalar(_) => None,
+        ColumnarValue::Array(_) => {
+            todo!("{} on column not yet implemented", name)
+        }
+    }
+}
+
+/// `luminance(specifier)`
+///
+/// Returns the luminance in [0, 1] of the color defined by the input color specifier.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#luminance
+pub fn make_luminance_udf() -> ScalarUDF {
+    let luminance_fn: ScalarFunctionImplementation = Arc::new(|args: &[ColumnarValue]| {
+        Ok(match scalar_color(&args[0], "luminance") {
+            Some((r, g, b)) => {
+                ColumnarValue::Scalar(ScalarValue::from(relative_luminance(r, g, b)))
+            }
+            None => ColumnarValue::Scalar(ScalarValue::Float64(None)),
+        })
+    });
+
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Float64)));
+    ScalarUDF::new(
+        "luminance",
+        &Signature::any(1, Volatility::Immutable),
+        &return_type,
+        &luminance_fn,
+    )
+}
+
+/// `contrast(specifier1, specifier2)`
+///
+/// Returns the contrast ratio between the input color specifiers as a value between
+/// 1 and 21, following the WCAG 2 definition.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#contrast
+pub fn make_contrast_udf() -> ScalarUDF {
+    let contrast_fn: ScalarFunctionImplementation = Arc::new(|args: &[ColumnarValue]| {
+        let lum1 = scalar_color(&args[0], "contrast").map(|(r, g, b)| relative_luminance(r, g, b));
+        let lum2 = scalar_color(&args[1], "contrast").map(|(r, g, b)| relative_luminance(r, g, b));
+        Ok(match (lum1, lum2) {
+            (Some(lum1), Some(lum2)) => {
+                let lighter = lum1.max(lum2);
+                let darker = lum1.min(lum2);
+                ColumnarValue::Scalar(ScalarValue::from((lighter + 0.05) / (darker + 0.05)))
+            }
+            _ => ColumnarValue::Scalar(ScalarValue::Float64(None)),
+        })
+    });
+
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Float64)));
+    ScalarUDF::new(
+        "contrast",
+        &Signature::any(2, Volatility::Immutable),
+        &return_type,
+        &contrast_fn,
+    )
+}
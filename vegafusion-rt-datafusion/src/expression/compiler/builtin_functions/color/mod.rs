@@ -0,0 +1,19 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+/*!
+## Color Functions
+Functions for representing colors in various color spaces and computing color metrics.
+
+Color values are represented as CSS color strings, which is how they are serialized
+when Vega datasets are materialized.
+
+See https://vega.github.io/vega/docs/expressions/#color-functions
+ */
+pub mod constructors;
+pub mod metrics;
@@ -0,0 +1,100 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use std::sync::{Arc, Mutex};
+use vegafusion_core::error::Result;
+use vegafusion_core::proto::gen::expression::expression::Expr;
+use vegafusion_core::proto::gen::expression::{Expression, Literal};
+
+/// Severity of a logging expression function
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
+    Warn,
+    Info,
+    Debug,
+}
+
+impl LogLevel {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<LogLevel> {
+        match name {
+            "warn" => Some(LogLevel::Warn),
+            "info" => Some(LogLevel::Info),
+            "debug" => Some(LogLevel::Debug),
+            _ => None,
+        }
+    }
+}
+
+/// A warning emitted by a `warn()` / `info()` / `debug()` call in an expression
+#[derive(Debug, Clone)]
+pub struct ExpressionWarning {
+    pub level: LogLevel,
+    pub message: String,
+}
+
+/// Collector for the warnings produced by logging expression functions during a
+/// single request. Each request creates its own collector and threads it through
+/// task evaluation via CompilationConfig, so concurrent requests don't see each
+/// other's warnings
+#[derive(Debug, Clone, Default)]
+pub struct ExpressionWarningCollector {
+    warnings: Arc<Mutex<Vec<ExpressionWarning>>>,
+}
+
+impl ExpressionWarningCollector {
+    pub fn push(&self, warning: ExpressionWarning) {
+        self.warnings.lock().unwrap().push(warning);
+    }
+
+    /// Drain the warnings collected so far. The runtime includes these in its
+    /// response rather than writing to a log
+    pub fn take(&self) -> Vec<ExpressionWarning> {
+        std::mem::take(&mut *self.warnings.lock().unwrap())
+    }
+}
+
+/// `warn(value1[, value2, …])`, `info(...)`, `debug(...)`
+///
+/// Logs a message and returns the last argument value. The Vega runtime writes the
+/// message to the JavaScript console; here it is recorded as a structured warning
+/// that the runtime attaches to its response. The logged message is the textual
+/// form of the arguments since their row values are not available until execution.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#warn
+pub fn log_fn(
+    level: LogLevel,
+    arguments: &[Expression],
+    warnings: &ExpressionWarningCollector,
+) -> Result<Expression> {
+    let message = arguments
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    warnings.push(ExpressionWarning {
+        level,
+        message: format!("{}({})", level.name(), message),
+    });
+
+    // Evaluates to the last argument, or null when called without arguments
+    Ok(match arguments.last() {
+        Some(expression) => expression.clone(),
+        None => Expression {
+            expr: Some(Expr::Literal(Literal::null())),
+            span: None,
+        },
+    })
+}
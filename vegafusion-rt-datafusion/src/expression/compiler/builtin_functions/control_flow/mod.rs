@@ -11,3 +11,4 @@
 See https://vega.github.io/vega/docs/expressions/#control-flow-functions
 */
 pub mod if_fn;
+pub mod logging;
@@ -0,0 +1,85 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use crate::expression::compiler::compile;
+use crate::expression::compiler::utils::cast_to;
+use datafusion::logical_plan::{DFSchema, Expr};
+use datafusion::prelude::lit;
+use datafusion::scalar::ScalarValue;
+use std::collections::HashSet;
+use vegafusion_core::data::table::VegaFusionTable;
+use vegafusion_core::error::{Result, ResultWithContext, VegaFusionError};
+use vegafusion_core::proto::gen::expression::{expression, literal, Expression, Literal};
+
+/// `indata(name, field, value)`
+///
+/// Tests if the dataset with the given name contains a datum with a field value that
+/// matches the input value. Compiles to a membership test against the distinct values
+/// of the referenced column, so it can be applied to filter and formula expressions
+/// in the server task graph.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#indata
+pub fn indata_fn(table: &VegaFusionTable, args: &[Expression], schema: &DFSchema) -> Result<Expr> {
+    // Validate arguments. The first (dataset name) argument has already been consumed
+    // to look up the table.
+    if args.len() != 2 {
+        return Err(VegaFusionError::internal(&format!(
+            "indata requires three arguments. Received {}",
+            args.len() + 1
+        )));
+    }
+
+    // Field argument must be a literal string
+    let field = match args[0].expr() {
+        expression::Expr::Literal(Literal {
+            value: Some(literal::Value::String(field)),
+            ..
+        }) => field.clone(),
+        _ => {
+            return Err(VegaFusionError::internal(
+                "The second argument to the indata function must be a literal \
+                string with the name of a field",
+            ))
+        }
+    };
+
+    // Compile the value argument against the active schema so datum references work
+    let value = compile(&args[1], &Default::default(), Some(schema))?;
+
+    // Collect the distinct values of the field column
+    let batch = table.to_record_batch()?;
+    let field_index = batch.schema().index_of(&field).with_context(|| {
+        format!(
+            "No field named {} in dataset passed to indata",
+            field
+        )
+    })?;
+    let column = batch.column(field_index).clone();
+
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut in_list: Vec<Expr> = Vec::new();
+    for i in 0..column.len() {
+        let element = ScalarValue::try_from_array(&column, i)?;
+        if !element.is_null() && seen.insert(format!("{:?}", element)) {
+            in_list.push(Expr::Literal(element));
+        }
+    }
+
+    if in_list.is_empty() {
+        return Ok(lit(false));
+    }
+
+    // Cast the value to the column's type so the membership test is well-typed
+    let value = cast_to(value, column.data_type(), schema)?;
+
+    Ok(Expr::InList {
+        expr: Box::new(value),
+        list: in_list,
+        negated: false,
+    })
+}
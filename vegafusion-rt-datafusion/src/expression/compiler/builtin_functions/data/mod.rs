@@ -7,5 +7,8 @@
  * this program the details of the active license.
  */
 pub mod data_fn;
+pub mod indata;
+pub mod modify;
+pub mod vl_selection_id_test;
 pub mod vl_selection_resolve;
 pub mod vl_selection_test;
@@ -0,0 +1,142 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use crate::expression::compiler::compile;
+use crate::expression::compiler::config::CompilationConfig;
+use crate::expression::compiler::utils::ExprHelpers;
+use datafusion::logical_plan::{lit, DFSchema, Expr};
+use datafusion::scalar::ScalarValue;
+use serde_json::Value;
+use std::collections::HashMap;
+use vegafusion_core::data::scalar::ScalarValueHelpers;
+use vegafusion_core::data::table::VegaFusionTable;
+use vegafusion_core::error::{Result, VegaFusionError};
+use vegafusion_core::expression::visitors::ExpressionVisitor;
+use vegafusion_core::proto::gen::expression::{
+    expression, literal, CallExpression, Expression, Identifier, Literal,
+};
+use vegafusion_core::task_graph::task_value::TaskValue;
+
+/// `modify(name, insert[, remove])`
+///
+/// As a compiled expression, modify evaluates to true (the Vega implementation
+/// returns the number of modified tuples, which is used for truthiness). The dataset
+/// mutation itself is interpreted at the signal-task level by [eval_modify], which
+/// produces the updated store as a task output value.
+pub fn modify_fn(
+    _table: &VegaFusionTable,
+    _args: &[Expression],
+    _schema: &DFSchema,
+) -> Result<Expr> {
+    Ok(lit(true))
+}
+
+/// Visitor that collects the modify() calls in a signal update expression
+#[derive(Clone, Default)]
+struct ModifyCallsVisitor {
+    calls: Vec<(String, Vec<Expression>)>,
+}
+
+impl ExpressionVisitor for ModifyCallsVisitor {
+    fn visit_called_identifier(&mut self, node: &Identifier, args: &[Expression]) {
+        if node.name == "modify" {
+            if let Some(Expression {
+                expr:
+                    Some(expression::Expr::Literal(Literal {
+                        value: Some(literal::Value::String(name)),
+                        ..
+                    })),
+                ..
+            }) = args.get(0)
+            {
+                self.calls.push((name.clone(), Vec::from(&args[1..])));
+            }
+        }
+    }
+}
+
+/// Convert an evaluated modify argument into a vector of JSON tuples
+fn scalar_to_tuples(value: &ScalarValue) -> Result<Vec<Value>> {
+    Ok(match value {
+        ScalarValue::List(Some(elements), _) => elements
+            .iter()
+            .map(|el| el.to_json())
+            .collect::<Result<Vec<_>>>()?,
+        value if value.is_null() => Vec::new(),
+        value => vec![value.to_json()?],
+    })
+}
+
+/// Interpret the modify() calls within a signal update expression against the store
+/// datasets in the compilation config, returning the updated dataset for each modified
+/// store in the order given by the expression's update variables.
+pub fn eval_modify(expression: &Expression, config: &CompilationConfig) -> Result<Vec<TaskValue>> {
+    let mut visitor = ModifyCallsVisitor::default();
+    expression.walk(&mut visitor);
+
+    let mut updated: HashMap<String, VegaFusionTable> = HashMap::new();
+    for (name, args) in visitor.calls {
+        let table = config.data_scope.get(&name).ok_or_else(|| {
+            VegaFusionError::internal(&format!("No dataset named {} for modify", name))
+        })?;
+
+        // Start from the current store tuples
+        let mut tuples: Vec<Value> = match table.to_json() {
+            Value::Array(tuples) => tuples,
+            _ => Vec::new(),
+        };
+
+        // Evaluate the insert and remove arguments as scalars
+        let eval_arg = |arg: Option<&Expression>| -> Result<Option<ScalarValue>> {
+            match arg {
+                Some(arg) => {
+                    let compiled = compile(arg, config, None)?;
+                    Ok(Some(compiled.eval_to_scalar()?))
+                }
+                None => Ok(None),
+            }
+        };
+        let insert = eval_arg(args.get(0))?;
+        let remove = eval_arg(args.get(1))?;
+
+        // Apply removals first, matching Vega's changeset ordering
+        match &remove {
+            Some(ScalarValue::Boolean(Some(true))) => {
+                tuples.clear();
+            }
+            Some(remove) if !remove.is_null() => {
+                let remove_tuples = scalar_to_tuples(remove)?;
+                tuples.retain(|t| !remove_tuples.contains(t));
+            }
+            _ => {}
+        }
+
+        // Then apply insertions, skipping tuples already present in the store
+        if let Some(insert) = &insert {
+            if !insert.is_null() {
+                for tuple in scalar_to_tuples(insert)? {
+                    if !tuples.contains(&tuple) {
+                        tuples.push(tuple);
+                    }
+                }
+            }
+        }
+
+        updated.insert(name, VegaFusionTable::from_json(&Value::Array(tuples), 1024)?);
+    }
+
+    // Order output values to match the expression's update variables
+    let mut output_values: Vec<TaskValue> = Vec::new();
+    for var in expression.update_vars() {
+        let table = updated.remove(&var.name).ok_or_else(|| {
+            VegaFusionError::internal(&format!("No modify result for dataset {}", &var.name))
+        })?;
+        output_values.push(TaskValue::Table(table));
+    }
+    Ok(output_values)
+}
@@ -0,0 +1,103 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use datafusion::logical_plan::{lit, DFSchema, Expr};
+use datafusion::prelude::col;
+use std::collections::HashMap;
+use vegafusion_core::data::scalar::ScalarValue;
+use vegafusion_core::data::table::VegaFusionTable;
+use vegafusion_core::error::{Result, VegaFusionError};
+use vegafusion_core::proto::gen::expression::expression::Expr as ProtoExpr;
+use vegafusion_core::proto::gen::expression::Expression;
+
+/// Name of the row-identifier column that Vega-Lite adds for id-based point selections
+pub const VGSID_COLUMN: &str = "_vgsid_";
+
+/// `vlSelectionIdTest(name, datum[, op])`
+///
+/// Tests whether the datum's `_vgsid_` identifier is included in the id-based
+/// selection store with the given name. Vega-Lite 5 emits this for point selections
+/// with empty `encodings`, where store tuples carry `_vgsid_` values rather than
+/// field/value pairs. Compiles to a membership test on the `_vgsid_` column.
+pub fn vl_selection_id_test_fn(
+    table: &VegaFusionTable,
+    args: &[Expression],
+    schema: &DFSchema,
+) -> Result<Expr> {
+    // Validate datum argument. The op argument, if present, is irrelevant for id
+    // stores since each tuple constrains the same single column.
+    match &args.get(0).map(|a| a.expr()) {
+        Some(ProtoExpr::Identifier(ident)) if ident.name == "datum" => {
+            // All good
+        }
+        arg => {
+            return Err(VegaFusionError::internal(&format!(
+                "The second argument to vlSelectionIdTest must be datum. Received {:?}",
+                arg
+            )))
+        }
+    }
+
+    if schema.field_with_unqualified_name(VGSID_COLUMN).is_err() {
+        return Err(VegaFusionError::internal(&format!(
+            "vlSelectionIdTest requires a {} column in the input dataset",
+            VGSID_COLUMN
+        )));
+    }
+
+    // Extract vector of rows for selection dataset
+    let rows = if let ScalarValue::List(Some(elements), _) = table.to_scalar_value()? {
+        elements
+    } else {
+        unreachable!()
+    };
+
+    // Collect the union of ids across all store tuples. Each tuple either holds a
+    // scalar `_vgsid_` value or a `values` array of ids.
+    let mut in_list: Vec<Expr> = Vec::new();
+    for row in rows {
+        if let ScalarValue::Struct(Some(struct_values), struct_fields) = row {
+            let field_names: HashMap<_, _> = struct_fields
+                .iter()
+                .enumerate()
+                .map(|(ind, f)| (f.name().clone(), ind))
+                .collect();
+            let index = field_names
+                .get(VGSID_COLUMN)
+                .or_else(|| field_names.get("values"));
+            match index.and_then(|index| struct_values.get(*index)) {
+                Some(ScalarValue::List(Some(elements), _)) => {
+                    in_list.extend(elements.iter().cloned().map(Expr::Literal));
+                }
+                Some(value) if !value.is_null() => {
+                    in_list.push(Expr::Literal(value.clone()));
+                }
+                _ => {
+                    return Err(VegaFusionError::internal(&format!(
+                        "Expected selection store tuple with a {} or values property",
+                        VGSID_COLUMN
+                    )))
+                }
+            }
+        } else {
+            return Err(VegaFusionError::internal(
+                "Expected selection row specification to be an object",
+            ));
+        }
+    }
+
+    if in_list.is_empty() {
+        return Ok(lit(false));
+    }
+
+    Ok(Expr::InList {
+        expr: Box::new(col(VGSID_COLUMN)),
+        list: in_list,
+        negated: false,
+    })
+}
@@ -64,7 +64,7 @@ pub fn vl_selection_resolve_fn(
     _schema: &DFSchema,
 ) -> Result<Expr> {
     // Validate args and get operation
-    let _op = parse_args(args)?;
+    let op = parse_args(args)?;
 
     // Extract vector of rows for selection dataset
     let rows = if let ScalarValue::List(Some(elements), _) = table.to_scalar_value()? {
@@ -73,21 +73,24 @@ pub fn vl_selection_resolve_fn(
         unreachable!()
     };
 
-    // let mut prop_names: Vec<String> = Vec::new();
-    // let mut prop_values: Vec<ScalarValue> = Vec::new();
-
-    let mut props: HashMap<String, Vec<ScalarValue>> = HashMap::new();
+    // Per-field enum values, one entry per selection row, and per-field interval ranges
+    let mut enums: HashMap<String, Vec<Vec<ScalarValue>>> = HashMap::new();
+    let mut intervals: HashMap<String, Vec<(f64, f64)>> = HashMap::new();
 
     for row in rows {
         let row_spec = SelectionRow::try_from(row)?;
         for (field, value) in row_spec.fields.iter().zip(&row_spec.values) {
-            let value = match field.typ {
+            match field.typ {
                 SelectionType::Enum => {
-                    if let ScalarValue::List(Some(elements), _) = value {
+                    let value = if let ScalarValue::List(Some(elements), _) = value {
                         elements.clone()
                     } else {
                         vec![value.clone()]
-                    }
+                    };
+                    enums
+                        .entry(field.field.clone())
+                        .or_insert_with(Vec::new)
+                        .push(value);
                 }
                 _ => {
                     match &value {
@@ -101,10 +104,10 @@ pub fn vl_selection_resolve_fn(
                             } else {
                                 (second, first)
                             };
-                            vec![ScalarValue::from(low), ScalarValue::from(high)]
-                            // ScalarValue::List(Some(Box::new(vec![
-                            //     ScalarValue::from(low), ScalarValue::from(high)
-                            // ])), Box::new(DataType::Float64))
+                            intervals
+                                .entry(field.field.clone())
+                                .or_insert_with(Vec::new)
+                                .push((low, high));
                         }
                         v => {
                             return Err(VegaFusionError::internal(&format!(
@@ -115,10 +118,52 @@ pub fn vl_selection_resolve_fn(
                     }
                 }
             };
+        }
+    }
+
+    let mut props: HashMap<String, Vec<ScalarValue>> = HashMap::new();
+
+    // Resolve interval fields across selection rows according to the operation:
+    // union takes the overall [min, max] envelope, intersect the overlapping region
+    for (name, ranges) in intervals {
+        let resolved = match op {
+            Op::Union => ranges
+                .iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |acc, r| {
+                    (acc.0.min(r.0), acc.1.max(r.1))
+                }),
+            Op::Intersect => ranges
+                .iter()
+                .fold((f64::NEG_INFINITY, f64::INFINITY), |acc, r| {
+                    (acc.0.max(r.0), acc.1.min(r.1))
+                }),
+        };
+        props.insert(
+            name,
+            vec![ScalarValue::from(resolved.0), ScalarValue::from(resolved.1)],
+        );
+    }
 
-            let values = props.entry(field.field.clone()).or_insert_with(Vec::new);
-            values.extend(value.clone());
+    // Resolve enum fields: union concatenates the distinct values across rows,
+    // intersect keeps only values present in every selection row
+    for (name, row_values) in enums {
+        let num_rows = row_values.len();
+        let mut resolved: Vec<ScalarValue> = Vec::new();
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for values in &row_values {
+            for value in values {
+                let key = format!("{:?}", value);
+                let count = counts.entry(key).or_insert(0);
+                *count += 1;
+                if *count == 1 {
+                    resolved.push(value.clone());
+                }
+            }
+        }
+        if matches!(op, Op::Intersect) {
+            resolved.retain(|v| counts.get(&format!("{:?}", v)).copied().unwrap_or(0) == num_rows);
         }
+        props.insert(name, resolved);
     }
 
     let props: Vec<_> = props
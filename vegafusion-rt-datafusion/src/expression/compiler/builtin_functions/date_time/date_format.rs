@@ -6,10 +6,13 @@
  * Please consult the license documentation provided alongside
  * this program the details of the active license.
  */
+use crate::expression::compiler::builtin_functions::date_time::label_formats::{
+    get_time_locale, TimeLocale,
+};
 use crate::expression::compiler::builtin_functions::date_time::process_input_datetime;
 use crate::task_graph::timezone::RuntimeTzConfig;
 use chrono::TimeZone;
-use chrono::{DateTime, NaiveDateTime};
+use chrono::{DateTime, Datelike, NaiveDateTime, Timelike};
 use datafusion::arrow::array::{ArrayRef, Int64Array, StringArray};
 use datafusion::arrow::datatypes::{DataType, TimeUnit};
 use datafusion::logical_plan::{DFSchema, Expr};
@@ -100,6 +103,49 @@ pub fn extract_format_str(args: &[Expr]) -> Result<String> {
     Ok(format_str)
 }
 
+/// Replace the locale-dependent format specifiers (%A, %a, %B, %b, %p) with
+/// the corresponding labels from the time locale for the provided datetime
+fn localize_format_str(
+    format_str: &str,
+    datetime: &DateTime<chrono_tz::Tz>,
+    locale: &TimeLocale,
+) -> String {
+    let mut localized = String::with_capacity(format_str.len());
+    let mut chars = format_str.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            localized.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('A') => {
+                let day = datetime.weekday().num_days_from_sunday() as usize;
+                localized.push_str(&locale.days[day]);
+            }
+            Some('a') => {
+                let day = datetime.weekday().num_days_from_sunday() as usize;
+                localized.push_str(&locale.short_days[day]);
+            }
+            Some('B') => {
+                localized.push_str(&locale.months[datetime.month0() as usize]);
+            }
+            Some('b') | Some('h') => {
+                localized.push_str(&locale.short_months[datetime.month0() as usize]);
+            }
+            Some('p') => {
+                let period = if datetime.hour() < 12 { 0 } else { 1 };
+                localized.push_str(&locale.periods[period]);
+            }
+            Some(other) => {
+                localized.push('%');
+                localized.push(other);
+            }
+            None => localized.push('%'),
+        }
+    }
+    localized
+}
+
 pub fn make_time_format_udf(
     default_input_tz: &chrono_tz::Tz,
     output_tz: &chrono_tz::Tz,
@@ -108,6 +154,7 @@ pub fn make_time_format_udf(
     let default_input_tz = *default_input_tz;
     let output_tz = *output_tz;
     let format_str = format_str.to_string();
+    let time_locale = get_time_locale();
     let time_fn = move |args: &[ArrayRef]| {
         // Signature ensures there is a single argument
         let arg = &args[0];
@@ -126,6 +173,11 @@ pub fn make_time_format_udf(
                 let datetime: DateTime<chrono_tz::Tz> =
                     output_tz.from_utc_datetime(&naive_utc_datetime);
 
+                // Substitute locale-dependent specifiers before formatting so
+                // weekday, month and day-period names follow the configured time
+                // locale rather than chrono's built-in English names
+                let format_str = localize_format_str(&format_str, &datetime, &time_locale);
+
                 // Format as string
                 let formatted = datetime.format(&format_str);
                 formatted.to_string()
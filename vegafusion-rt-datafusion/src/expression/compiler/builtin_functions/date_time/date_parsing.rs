@@ -367,6 +367,79 @@ pub fn make_date_str_to_millis_udf(
     )
 }
 
+/// Translate a d3-time-format specifier into the equivalent chrono strftime
+/// specifier. Most directives are shared; the millisecond and microsecond
+/// directives differ.
+pub fn d3_to_chrono_format(format: &str) -> String {
+    format.replace("%L", "%3f").replace("%f", "%6f")
+}
+
+/// Parse a datetime string with an explicit d3-time-format specifier, interpreting
+/// naive datetimes in the provided input timezone
+pub fn parse_datetime_with_format(
+    date_str: &str,
+    format: &str,
+    default_input_tz: &Option<chrono_tz::Tz>,
+) -> Option<DateTime<Utc>> {
+    let format = d3_to_chrono_format(format);
+    let mut parsed = Parsed::new();
+    parse(&mut parsed, date_str, StrftimeItems::new(&format)).ok()?;
+
+    if let Ok(datetime) = parsed.to_datetime() {
+        // Format included an explicit timezone offset
+        return Some(datetime.with_timezone(&chrono::Utc));
+    }
+
+    // Default missing time components to midnight
+    let date = parsed.to_naive_date().ok()?;
+    let time = parsed
+        .to_naive_time()
+        .unwrap_or_else(|_| NaiveTime::from_hms_milli(0, 0, 0, 0));
+    let datetime = NaiveDateTime::new(date, time);
+
+    let local_tz = (*default_input_tz)?;
+    let datetime = local_tz.from_local_datetime(&datetime).earliest()?;
+    Some(datetime.with_timezone(&chrono::Utc))
+}
+
+/// Make a UDF that parses strings to UTC milliseconds using an explicit
+/// d3-time-format specifier (as accepted by `toDate(value, format)`)
+pub fn make_date_str_to_millis_with_format_udf(
+    format: &str,
+    default_input_tz: &Option<chrono_tz::Tz>,
+) -> ScalarUDF {
+    let format = format.to_string();
+    let local_tz = *default_input_tz;
+    let to_millis_fn = move |args: &[ArrayRef]| {
+        // Signature ensures there is a single string argument
+        let arg = &args[0];
+        let date_strs = arg.as_any().downcast_ref::<StringArray>().unwrap();
+        let millis_array = Int64Array::from(
+            date_strs
+                .iter()
+                .map(|date_str| -> Option<i64> {
+                    date_str.and_then(|date_str| {
+                        parse_datetime_with_format(date_str, &format, &local_tz)
+                            .map(|dt| dt.timestamp_millis())
+                    })
+                })
+                .collect::<Vec<Option<i64>>>(),
+        );
+        Ok(Arc::new(millis_array) as ArrayRef)
+    };
+
+    let to_millis_fn = make_scalar_function(to_millis_fn);
+
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Int64)));
+
+    ScalarUDF::new(
+        "vf_datetime_with_format_to_millis",
+        &Signature::uniform(1, vec![DataType::Utf8], Volatility::Immutable),
+        &return_type,
+        &to_millis_fn,
+    )
+}
+
 pub fn datetime_strs_to_millis(
     date_strs: &StringArray,
     mode: DateParseMode,
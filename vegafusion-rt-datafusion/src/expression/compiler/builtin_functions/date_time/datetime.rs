@@ -7,7 +7,7 @@
  * this program the details of the active license.
  */
 use crate::expression::compiler::builtin_functions::date_time::date_parsing::{
-    make_date_str_to_millis_udf, DateParseMode,
+    make_date_str_to_millis_udf, make_date_str_to_millis_with_format_udf, DateParseMode,
 };
 use crate::expression::compiler::utils::{cast_to, is_string_datatype};
 use crate::task_graph::timezone::RuntimeTzConfig;
@@ -37,33 +37,43 @@ pub fn to_date_transform(
         .with_context(|| format!("Failed to infer type of expression: {:?}", arg))?;
 
     if is_string_datatype(&dtype) {
+        // The second argument, if provided, is either a d3-time-format specifier or an
+        // override local timezone string
+        let mut format: Option<String> = None;
         let default_input_tz = if args.len() == 2 {
-            // Second argument is a an override local timezone string
             let input_tz_expr = &args[1];
-            if let Expr::Literal(ScalarValue::Utf8(Some(input_tz_str))) = input_tz_expr {
-                if input_tz_str == "local" {
+            if let Expr::Literal(ScalarValue::Utf8(Some(second_str))) = input_tz_expr {
+                if second_str.contains('%') {
+                    // d3-time-format specifier
+                    format = Some(second_str.clone());
+                    tz_config.default_input_tz
+                } else if second_str == "local" {
                     tz_config.local_tz
                 } else {
-                    chrono_tz::Tz::from_str(input_tz_str)
+                    chrono_tz::Tz::from_str(second_str)
                         .ok()
                         .with_context(|| {
-                            format!("Failed to parse {} as a timezone", input_tz_str)
+                            format!("Failed to parse {} as a timezone", second_str)
                         })?
                 }
             } else {
                 return Err(VegaFusionError::parse(
-                    "Second argument to toDate must be a timezone string",
+                    "Second argument to toDate must be a format or timezone string",
                 ));
             }
         } else {
             tz_config.default_input_tz
         };
 
+        let udf = match format {
+            Some(format) => {
+                make_date_str_to_millis_with_format_udf(&format, &Some(default_input_tz))
+            }
+            None => make_date_str_to_millis_udf(DateParseMode::JavaScript, &Some(default_input_tz)),
+        };
+
         arg = Expr::ScalarUDF {
-            fun: Arc::new(make_date_str_to_millis_udf(
-                DateParseMode::JavaScript,
-                &Some(default_input_tz),
-            )),
+            fun: Arc::new(udf),
             args: vec![arg],
         }
     }
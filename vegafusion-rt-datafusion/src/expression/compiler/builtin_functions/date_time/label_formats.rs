@@ -0,0 +1,211 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use datafusion::arrow::array::{ArrayRef, Int64Array, StringArray};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::physical_plan::functions::make_scalar_function;
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion_expr::{ReturnTypeFunction, Signature, Volatility};
+use lazy_static::lazy_static;
+use serde_json::Value;
+use std::sync::{Arc, RwLock};
+use vegafusion_core::error::{Result, VegaFusionError};
+
+/// Weekday, month and day-period labels for a time locale, following the
+/// structure of d3-time-format locale definitions
+#[derive(Debug, Clone)]
+pub struct TimeLocale {
+    pub days: Vec<String>,
+    pub short_days: Vec<String>,
+    pub months: Vec<String>,
+    pub short_months: Vec<String>,
+    pub periods: Vec<String>,
+}
+
+impl Default for TimeLocale {
+    fn default() -> Self {
+        // en-US, matching d3-time-format's default locale
+        let to_strings = |labels: &[&str]| labels.iter().map(|s| s.to_string()).collect();
+        Self {
+            days: to_strings(&[
+                "Sunday",
+                "Monday",
+                "Tuesday",
+                "Wednesday",
+                "Thursday",
+                "Friday",
+                "Saturday",
+            ]),
+            short_days: to_strings(&["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"]),
+            months: to_strings(&[
+                "January",
+                "February",
+                "March",
+                "April",
+                "May",
+                "June",
+                "July",
+                "August",
+                "September",
+                "October",
+                "November",
+                "December",
+            ]),
+            short_months: to_strings(&[
+                "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+            ]),
+            periods: to_strings(&["AM", "PM"]),
+        }
+    }
+}
+
+impl TimeLocale {
+    /// Build a time locale from a d3-time-format locale definition, e.g.
+    /// https://github.com/d3/d3-time-format/blob/main/locale/fr-FR.json.
+    /// Unspecified label lists fall back to the en-US defaults
+    pub fn from_d3_json(value: &Value) -> Result<Self> {
+        if !value.is_object() {
+            return Err(VegaFusionError::parse(
+                "Time locale definition must be an object",
+            ));
+        }
+
+        fn extract_labels(
+            value: &Value,
+            key: &str,
+            expected_len: usize,
+        ) -> Result<Option<Vec<String>>> {
+            match value.get(key) {
+                None => Ok(None),
+                Some(Value::Array(labels)) => {
+                    if labels.len() != expected_len {
+                        return Err(VegaFusionError::parse(format!(
+                            "Expected {} entries for {} in time locale definition, received {}",
+                            expected_len,
+                            key,
+                            labels.len()
+                        )));
+                    }
+                    let labels = labels
+                        .iter()
+                        .map(|label| {
+                            label.as_str().map(|s| s.to_string()).ok_or_else(|| {
+                                VegaFusionError::parse(format!(
+                                    "Entries of {} in time locale definition must be strings",
+                                    key
+                                ))
+                            })
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    Ok(Some(labels))
+                }
+                Some(_) => Err(VegaFusionError::parse(format!(
+                    "{} in time locale definition must be an array of strings",
+                    key
+                ))),
+            }
+        }
+
+        let default = Self::default();
+        Ok(Self {
+            days: extract_labels(value, "days", 7)?.unwrap_or(default.days),
+            short_days: extract_labels(value, "shortDays", 7)?.unwrap_or(default.short_days),
+            months: extract_labels(value, "months", 12)?.unwrap_or(default.months),
+            short_months: extract_labels(value, "shortMonths", 12)?
+                .unwrap_or(default.short_months),
+            periods: extract_labels(value, "periods", 2)?.unwrap_or(default.periods),
+        })
+    }
+}
+
+lazy_static! {
+    static ref TIME_LOCALE: RwLock<TimeLocale> = RwLock::new(TimeLocale::default());
+}
+
+/// Set the process-wide time locale used by the timeFormat, utcFormat and
+/// weekday / month label expression functions.
+///
+/// The locale must be set before specs are planned and task graphs are built
+pub fn set_time_locale(locale: TimeLocale) {
+    let mut guard = TIME_LOCALE.write().unwrap();
+    *guard = locale;
+}
+
+/// Get a copy of the process-wide time locale
+pub fn get_time_locale() -> TimeLocale {
+    TIME_LOCALE.read().unwrap().clone()
+}
+
+fn make_label_udf(name: &'static str, labels: Vec<String>) -> ScalarUDF {
+    let label_fn = move |args: &[ArrayRef]| {
+        // Signature ensures there is a single Int64 argument
+        let indices = args[0].as_any().downcast_ref::<Int64Array>().unwrap();
+        let label_array = StringArray::from(
+            (0..indices.len())
+                .map(|i| -> Option<&str> {
+                    if indices.is_null(i) {
+                        None
+                    } else {
+                        labels
+                            .get(indices.value(i).rem_euclid(labels.len() as i64) as usize)
+                            .map(|s| s.as_str())
+                    }
+                })
+                .collect::<Vec<Option<&str>>>(),
+        );
+        Ok(Arc::new(label_array) as ArrayRef)
+    };
+    let label_fn = make_scalar_function(label_fn);
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Utf8)));
+    ScalarUDF::new(
+        name,
+        &Signature::uniform(1, vec![DataType::Int64], Volatility::Immutable),
+        &return_type,
+        &label_fn,
+    )
+}
+
+/// `dayFormat(day)`
+///
+/// Formats a (0-6) weekday number as a full week day name, according to the
+/// configured time locale. For example: dayFormat(0) -> "Sunday".
+///
+/// See: https://vega.github.io/vega/docs/expressions/#dayFormat
+pub fn make_day_format_udf(locale: &TimeLocale) -> ScalarUDF {
+    make_label_udf("dayFormat", locale.days.clone())
+}
+
+/// `dayAbbrevFormat(day)`
+///
+/// Formats a (0-6) weekday number as an abbreviated week day name, according to the
+/// configured time locale. For example: dayAbbrevFormat(0) -> "Sun".
+///
+/// See: https://vega.github.io/vega/docs/expressions/#dayAbbrevFormat
+pub fn make_day_abbrev_format_udf(locale: &TimeLocale) -> ScalarUDF {
+    make_label_udf("dayAbbrevFormat", locale.short_days.clone())
+}
+
+/// `monthFormat(month)`
+///
+/// Formats a (zero-based) month number as a full month name, according to the
+/// configured time locale. For example: monthFormat(0) -> "January".
+///
+/// See: https://vega.github.io/vega/docs/expressions/#monthFormat
+pub fn make_month_format_udf(locale: &TimeLocale) -> ScalarUDF {
+    make_label_udf("monthFormat", locale.months.clone())
+}
+
+/// `monthAbbrevFormat(month)`
+///
+/// Formats a (zero-based) month number as an abbreviated month name, according to the
+/// configured time locale. For example: monthAbbrevFormat(0) -> "Jan".
+///
+/// See: https://vega.github.io/vega/docs/expressions/#monthAbbrevFormat
+pub fn make_month_abbrev_format_udf(locale: &TimeLocale) -> ScalarUDF {
+    make_label_udf("monthAbbrevFormat", locale.short_months.clone())
+}
@@ -16,6 +16,7 @@ pub mod date_format;
 pub mod date_parsing;
 pub mod date_parts;
 pub mod datetime;
+pub mod label_formats;
 pub mod local_to_utc;
 pub mod time;
 
@@ -0,0 +1,135 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use datafusion::arrow::array::{ArrayRef, Float64Array};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::physical_plan::functions::make_scalar_function;
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion_expr::{ReturnTypeFunction, Signature, Volatility};
+use std::sync::Arc;
+
+/// Convert an f64 to an i32 following the JavaScript ToInt32 abstract operation.
+/// Non-finite values map to 0 and other values wrap modulo 2^32.
+pub fn to_int32(value: f64) -> i32 {
+    if !value.is_finite() {
+        return 0;
+    }
+    value.trunc().rem_euclid(4294967296.0) as u32 as i32
+}
+
+/// Convert an f64 to a u32 following the JavaScript ToUint32 abstract operation.
+pub fn to_uint32(value: f64) -> u32 {
+    if !value.is_finite() {
+        return 0;
+    }
+    value.trunc().rem_euclid(4294967296.0) as u32
+}
+
+/// Build a two-argument bitwise UDF. The closure receives the arguments after
+/// ToInt32 conversion and its result is returned as an f64, matching how
+/// JavaScript performs bitwise operations on 32-bit integers but yields numbers.
+fn make_bitwise_binary_udf(name: &str, op: fn(f64, f64) -> f64) -> ScalarUDF {
+    let bitwise = move |args: &[ArrayRef]| {
+        // Signature ensures there are two Float64 arguments
+        let lhs = args[0].as_any().downcast_ref::<Float64Array>().unwrap();
+        let rhs = args[1].as_any().downcast_ref::<Float64Array>().unwrap();
+
+        let result_array: Float64Array = lhs
+            .iter()
+            .zip(rhs.iter())
+            .map(|(l, r)| match (l, r) {
+                (Some(l), Some(r)) => Some(op(l, r)),
+                _ => None,
+            })
+            .collect();
+        Ok(Arc::new(result_array) as ArrayRef)
+    };
+    let bitwise = make_scalar_function(bitwise);
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Float64)));
+
+    ScalarUDF::new(
+        name,
+        &Signature::uniform(2, vec![DataType::Float64], Volatility::Immutable),
+        &return_type,
+        &bitwise,
+    )
+}
+
+/// `lhs & rhs`
+///
+/// Bitwise AND with JavaScript's 32-bit integer semantics
+pub fn make_bitwise_and_udf() -> ScalarUDF {
+    make_bitwise_binary_udf("bitwise_and", |l, r| (to_int32(l) & to_int32(r)) as f64)
+}
+
+/// `lhs | rhs`
+///
+/// Bitwise OR with JavaScript's 32-bit integer semantics
+pub fn make_bitwise_or_udf() -> ScalarUDF {
+    make_bitwise_binary_udf("bitwise_or", |l, r| (to_int32(l) | to_int32(r)) as f64)
+}
+
+/// `lhs ^ rhs`
+///
+/// Bitwise XOR with JavaScript's 32-bit integer semantics
+pub fn make_bitwise_xor_udf() -> ScalarUDF {
+    make_bitwise_binary_udf("bitwise_xor", |l, r| (to_int32(l) ^ to_int32(r)) as f64)
+}
+
+/// `lhs << rhs`
+///
+/// Left shift with JavaScript's 32-bit integer semantics. Only the low five
+/// bits of the shift count are used.
+pub fn make_shift_left_udf() -> ScalarUDF {
+    make_bitwise_binary_udf("shift_left", |l, r| {
+        (to_int32(l) << (to_uint32(r) & 31)) as f64
+    })
+}
+
+/// `lhs >> rhs`
+///
+/// Sign-propagating right shift with JavaScript's 32-bit integer semantics
+pub fn make_shift_right_udf() -> ScalarUDF {
+    make_bitwise_binary_udf("shift_right", |l, r| {
+        (to_int32(l) >> (to_uint32(r) & 31)) as f64
+    })
+}
+
+/// `lhs >>> rhs`
+///
+/// Zero-fill right shift. The left operand is treated as a u32, so the result
+/// is always non-negative.
+pub fn make_shift_right_unsigned_udf() -> ScalarUDF {
+    make_bitwise_binary_udf("shift_right_unsigned", |l, r| {
+        (to_uint32(l) >> (to_uint32(r) & 31)) as f64
+    })
+}
+
+/// `~value`
+///
+/// Bitwise NOT with JavaScript's 32-bit integer semantics
+pub fn make_bitwise_not_udf() -> ScalarUDF {
+    let bitwise_not = |args: &[ArrayRef]| {
+        // Signature ensures there is a single Float64 argument
+        let arg = args[0].as_any().downcast_ref::<Float64Array>().unwrap();
+        let result_array: Float64Array = arg
+            .iter()
+            .map(|v| v.map(|v| !to_int32(v) as f64))
+            .collect();
+        Ok(Arc::new(result_array) as ArrayRef)
+    };
+    let bitwise_not = make_scalar_function(bitwise_not);
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Float64)));
+
+    ScalarUDF::new(
+        "bitwise_not",
+        &Signature::uniform(1, vec![DataType::Float64], Volatility::Immutable),
+        &return_type,
+        &bitwise_not,
+    )
+}
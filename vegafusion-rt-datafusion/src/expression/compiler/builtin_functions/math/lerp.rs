@@ -0,0 +1,61 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use datafusion::arrow::datatypes::DataType;
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion::physical_plan::ColumnarValue;
+use datafusion::scalar::ScalarValue;
+use datafusion_expr::{ReturnTypeFunction, ScalarFunctionImplementation, Signature, Volatility};
+use std::sync::Arc;
+use vegafusion_core::data::scalar::ScalarValueHelpers;
+
+/// `lerp(array, fraction)`
+///
+/// Returns the linearly interpolated value between the first and last entries in the
+/// array for the provided interpolation fraction (typically between 0 and 1). For
+/// example, lerp([0, 50], 0.5) returns 25.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#lerp
+pub fn make_lerp_udf() -> ScalarUDF {
+    let lerp_fn: ScalarFunctionImplementation = Arc::new(|args: &[ColumnarValue]| {
+        // Signature ensures there are two arguments
+        let fraction = match &args[1] {
+            ColumnarValue::Scalar(value) => value.to_f64().unwrap_or(f64::NAN),
+            ColumnarValue::Array(_) => {
+                todo!("lerp on column not yet implemented")
+            }
+        };
+
+        Ok(match &args[0] {
+            ColumnarValue::Scalar(ScalarValue::List(Some(arr), _)) => {
+                match (arr.first(), arr.last()) {
+                    (Some(first), Some(last)) => {
+                        let first = first.to_f64().unwrap_or(f64::NAN);
+                        let last = last.to_f64().unwrap_or(f64::NAN);
+                        ColumnarValue::Scalar(ScalarValue::from(
+                            first + (last - first) * fraction,
+                        ))
+                    }
+                    _ => ColumnarValue::Scalar(ScalarValue::Float64(None)),
+                }
+            }
+            ColumnarValue::Scalar(_) => ColumnarValue::Scalar(ScalarValue::Float64(None)),
+            ColumnarValue::Array(_) => {
+                todo!("lerp on column not yet implemented")
+            }
+        })
+    });
+
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Float64)));
+    ScalarUDF::new(
+        "lerp",
+        &Signature::any(2, Volatility::Immutable),
+        &return_type,
+        &lerp_fn,
+    )
+}
@@ -12,6 +12,9 @@ Basic mathematical functions.
 
 See https://vega.github.io/vega/docs/expressions/#math-functions
  */
+pub mod bitwise;
 pub mod isfinite;
 pub mod isnan;
+pub mod lerp;
 pub mod pow;
+pub mod random;
@@ -0,0 +1,55 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use datafusion::arrow::datatypes::DataType;
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion::physical_plan::ColumnarValue;
+use datafusion::scalar::ScalarValue;
+use datafusion_expr::{ReturnTypeFunction, ScalarFunctionImplementation, Signature, Volatility};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// `random()`
+///
+/// Returns a pseudo-random number in the range [0, 1). When a seed is provided the
+/// sequence is deterministic, which keeps cached task values stable across repeated
+/// evaluations of the same task graph.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#random
+pub fn make_random_udf(seed: Option<u64>) -> ScalarUDF {
+    // Use splitmix64 so we don't need an external RNG dependency. When no seed is
+    // provided, initialize from the system clock.
+    let init = seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("System time before UNIX epoch")
+            .as_nanos() as u64
+    });
+    let state = Mutex::new(init);
+
+    let random_fn: ScalarFunctionImplementation = Arc::new(move |_args: &[ColumnarValue]| {
+        let mut state = state.lock().unwrap();
+        *state = state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^= z >> 31;
+
+        // Map the top 53 bits to [0, 1) like JavaScript's Math.random
+        let value = (z >> 11) as f64 / (1u64 << 53) as f64;
+        Ok(ColumnarValue::Scalar(ScalarValue::from(value)))
+    });
+
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Float64)));
+    ScalarUDF::new(
+        "random",
+        &Signature::exact(Vec::new(), Volatility::Volatile),
+        &return_type,
+        &random_fn,
+    )
+}
@@ -7,9 +7,14 @@
  * this program the details of the active license.
  */
 pub mod array;
+pub mod color;
 pub mod control_flow;
 pub mod data;
 pub mod date_time;
 pub mod math;
+pub mod object;
+pub mod scale;
+pub mod statistics;
+pub mod string;
 pub mod type_checking;
 pub mod type_coercion;
@@ -0,0 +1,109 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use datafusion::arrow::array::{ArrayRef, StructArray};
+use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion::logical_plan::{DFSchema, Expr, ExprSchemable};
+use datafusion::physical_plan::functions::make_scalar_function;
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion_expr::{ReturnTypeFunction, Signature, Volatility};
+use std::collections::HashMap;
+use std::sync::Arc;
+use vegafusion_core::error::{Result, VegaFusionError};
+
+/// `merge(object1[, object2, …])`
+///
+/// Merges the input objects into a combined object. If a key is defined by
+/// more than one of the inputs, the value from the last input object with
+/// that key is used. A key first appears in the output at the position it
+/// first appears among the inputs.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#merge
+pub fn merge_fn(args: &[Expr], schema: &DFSchema) -> Result<Expr> {
+    if args.is_empty() {
+        return Err(VegaFusionError::compilation(
+            "The merge function requires at least one argument",
+        ));
+    }
+
+    // Collect the struct fields of each argument
+    let mut arg_fields: Vec<Vec<Field>> = Vec::new();
+    for arg in args {
+        match arg.get_type(schema)? {
+            DataType::Struct(fields) => arg_fields.push(fields),
+            dtype => {
+                return Err(VegaFusionError::compilation(&format!(
+                    "All arguments to the merge function must be objects, not {}",
+                    dtype
+                )))
+            }
+        }
+    }
+
+    // Determine the merged fields: a key's position is that of its first
+    // appearance, while its value comes from the last argument defining it
+    let mut key_order: Vec<String> = Vec::new();
+    let mut field_sources: HashMap<String, (usize, Field)> = HashMap::new();
+    for (arg_index, fields) in arg_fields.iter().enumerate() {
+        for field in fields {
+            if !field_sources.contains_key(field.name()) {
+                key_order.push(field.name().clone());
+            }
+            field_sources.insert(field.name().clone(), (arg_index, field.clone()));
+        }
+    }
+
+    let merged: Vec<(usize, Field)> = key_order
+        .iter()
+        .map(|key| field_sources.get(key).cloned().unwrap())
+        .collect();
+
+    let udf = make_merge_udf(merged, args.len());
+    Ok(Expr::ScalarUDF {
+        fun: Arc::new(udf),
+        args: Vec::from(args),
+    })
+}
+
+fn make_merge_udf(merged: Vec<(usize, Field)>, num_args: usize) -> ScalarUDF {
+    let struct_dtype = DataType::Struct(merged.iter().map(|(_, f)| f.clone()).collect());
+
+    let merged_for_fn = merged.clone();
+    let merge = move |args: &[ArrayRef]| {
+        let structs: Vec<&StructArray> = args
+            .iter()
+            .map(|arg| arg.as_any().downcast_ref::<StructArray>().unwrap())
+            .collect();
+        let pairs: Vec<_> = merged_for_fn
+            .iter()
+            .map(|(arg_index, field)| {
+                let column = structs[*arg_index]
+                    .column_by_name(field.name())
+                    .unwrap()
+                    .clone();
+                (field.clone(), column)
+            })
+            .collect();
+        Ok(Arc::new(StructArray::from(pairs)) as ArrayRef)
+    };
+    let merge = make_scalar_function(merge);
+
+    let return_type: ReturnTypeFunction = Arc::new(move |_args| Ok(Arc::new(struct_dtype.clone())));
+
+    let name_csv: Vec<_> = merged
+        .iter()
+        .map(|(_, field)| format!("{}: {}", field.name(), field.data_type()))
+        .collect();
+
+    ScalarUDF::new(
+        &format!("merge{{{}}}", name_csv.join(",")),
+        &Signature::any(num_args, Volatility::Immutable),
+        &return_type,
+        &merge,
+    )
+}
@@ -0,0 +1,15 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+/*!
+## Object Functions
+Functions for working with objects (Arrow struct values).
+
+See https://vega.github.io/vega/docs/expressions/#object-functions
+ */
+pub mod merge;
@@ -0,0 +1,102 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use crate::expression::compiler::builtin_functions::scale::ScaleState;
+use crate::expression::compiler::utils::to_numeric;
+use datafusion::logical_plan::{DFSchema, Expr};
+use datafusion::prelude::lit;
+use datafusion::scalar::ScalarValue;
+use datafusion_expr::BuiltinScalarFunction;
+use vegafusion_core::data::scalar::ScalarValueHelpers;
+use vegafusion_core::error::{Result, VegaFusionError};
+
+/// Invert a single numeric range value through the scale, returning the data-space value
+fn invert_value(state: &ScaleState, value: f64) -> Result<f64> {
+    let (r0, r1) = state.numeric_range()?;
+    let fraction = if r1 != r0 {
+        (value - r0) / (r1 - r0)
+    } else {
+        0.0
+    };
+    match state.scale_type.as_str() {
+        "linear" => {
+            let (d0, d1) = state.numeric_domain()?;
+            Ok(d0 + fraction * (d1 - d0))
+        }
+        "log" => {
+            let (d0, d1) = state.numeric_domain()?;
+            if d0 <= 0.0 || d1 <= 0.0 {
+                return Err(VegaFusionError::compilation(
+                    "log scale domain must be positive",
+                ));
+            }
+            Ok((d0.ln() + fraction * (d1.ln() - d0.ln())).exp())
+        }
+        scale_type => Err(VegaFusionError::compilation(&format!(
+            "invert() not yet supported for scale type: {}",
+            scale_type
+        ))),
+    }
+}
+
+/// `invert(name, value)`
+///
+/// Inverts the named scale, mapping a value (or array of values, such as a brush
+/// pixel extent) from the scale's range back to its domain. Currently supports
+/// linear and log scales with static domains and ranges.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#invert
+pub fn invert_fn(state: &ScaleState, args: &[Expr], schema: &DFSchema) -> Result<Expr> {
+    // The name argument has already been consumed to look up the scale state
+    let value = args
+        .get(0)
+        .cloned()
+        .ok_or_else(|| VegaFusionError::compilation("invert requires a value argument"))?;
+
+    // When the argument is a literal array (e.g. a pixel extent from an interval
+    // selection signal), invert each element and return a new array literal
+    if let Expr::Literal(ScalarValue::List(Some(elements), _)) = &value {
+        let inverted: Vec<ScalarValue> = elements
+            .iter()
+            .map(|el| Ok(ScalarValue::from(invert_value(state, el.to_f64()?)?)))
+            .collect::<Result<Vec<_>>>()?;
+        return Ok(Expr::Literal(ScalarValue::List(
+            Some(inverted),
+            Box::new(datafusion::arrow::datatypes::DataType::Float64),
+        )));
+    }
+
+    let (r0, r1) = state.numeric_range()?;
+    let inv_slope = if r1 != r0 { 1.0 / (r1 - r0) } else { 0.0 };
+    let value = to_numeric(value, schema)?;
+    let fraction = (value - lit(r0)) * lit(inv_slope);
+
+    match state.scale_type.as_str() {
+        "linear" => {
+            let (d0, d1) = state.numeric_domain()?;
+            Ok(lit(d0) + fraction * lit(d1 - d0))
+        }
+        "log" => {
+            let (d0, d1) = state.numeric_domain()?;
+            if d0 <= 0.0 || d1 <= 0.0 {
+                return Err(VegaFusionError::compilation(
+                    "log scale domain must be positive",
+                ));
+            }
+            let log_value = lit(d0.ln()) + fraction * lit(d1.ln() - d0.ln());
+            Ok(Expr::ScalarFunction {
+                fun: BuiltinScalarFunction::Exp,
+                args: vec![log_value],
+            })
+        }
+        scale_type => Err(VegaFusionError::compilation(&format!(
+            "invert() not yet supported for scale type: {}",
+            scale_type
+        ))),
+    }
+}
@@ -0,0 +1,60 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use crate::expression::compiler::builtin_functions::scale::ScaleState;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::logical_plan::{DFSchema, Expr};
+use datafusion::prelude::lit;
+use datafusion::scalar::ScalarValue;
+use vegafusion_core::error::Result;
+
+/// Build a list literal from scale state elements, inferring the element type from
+/// the first element
+fn list_literal(elements: &[ScalarValue]) -> Expr {
+    let element_type = elements
+        .first()
+        .map(|el| el.get_datatype())
+        .unwrap_or(DataType::Float64);
+    Expr::Literal(ScalarValue::List(
+        Some(elements.to_vec()),
+        Box::new(element_type),
+    ))
+}
+
+/// `domain(name)`
+///
+/// Returns the scale domain of the named scale as an array literal.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#domain
+pub fn domain_fn(state: &ScaleState, _args: &[Expr], _schema: &DFSchema) -> Result<Expr> {
+    Ok(list_literal(&state.domain))
+}
+
+/// `range(name)`
+///
+/// Returns the scale range of the named scale as an array literal.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#range
+pub fn range_fn(state: &ScaleState, _args: &[Expr], _schema: &DFSchema) -> Result<Expr> {
+    Ok(list_literal(&state.range))
+}
+
+/// `bandwidth(name)`
+///
+/// Returns the current band width of the named band scale, or zero if the scale is
+/// not a band scale.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#bandwidth
+pub fn bandwidth_fn(state: &ScaleState, _args: &[Expr], _schema: &DFSchema) -> Result<Expr> {
+    if state.scale_type == "band" && !state.domain.is_empty() {
+        let (r0, r1) = state.numeric_range()?;
+        Ok(lit((r1 - r0) / state.domain.len() as f64))
+    } else {
+        Ok(lit(0.0))
+    }
+}
@@ -0,0 +1,93 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+/*!
+## Scale and Projection Functions
+Functions for working with Vega scales inside expressions.
+
+Scale state (type, domain, range) is carried in the compilation config's scale scope.
+
+See https://vega.github.io/vega/docs/expressions/#scale-and-projection-functions
+ */
+pub mod invert_fn;
+pub mod metadata;
+pub mod scale_fn;
+
+use vegafusion_core::data::scalar::ScalarValueHelpers;
+use vegafusion_core::error::{Result, VegaFusionError};
+
+use datafusion::scalar::ScalarValue;
+
+/// Materialized state of a named Vega scale, as needed to apply the scale inside
+/// compiled expressions.
+#[derive(Debug, Clone)]
+pub struct ScaleState {
+    /// The scale type (e.g. "linear", "log", "band")
+    pub scale_type: String,
+
+    /// The scale domain
+    pub domain: Vec<ScalarValue>,
+
+    /// The scale range
+    pub range: Vec<ScalarValue>,
+}
+
+impl ScaleState {
+    /// Decode scale state from the scalar value of a scale value task. This is the
+    /// inverse of the encoding performed when the planner builds the task graph
+    pub fn from_scalar_value(value: &ScalarValue) -> Result<Self> {
+        let state = value.to_json()?;
+        let scale_type = state
+            .get("type")
+            .and_then(|v| v.as_str())
+            .unwrap_or("linear")
+            .to_string();
+        let domain = state_values(&state, "domain")?;
+        let range = state_values(&state, "range")?;
+        Ok(Self {
+            scale_type,
+            domain,
+            range,
+        })
+    }
+
+    /// Numeric [start, end] of the scale domain
+    pub fn numeric_domain(&self) -> Result<(f64, f64)> {
+        self.numeric_interval(&self.domain, "domain")
+    }
+
+    /// Numeric [start, end] of the scale range
+    pub fn numeric_range(&self) -> Result<(f64, f64)> {
+        self.numeric_interval(&self.range, "range")
+    }
+
+    fn numeric_interval(&self, values: &[ScalarValue], which: &str) -> Result<(f64, f64)> {
+        match (values.first(), values.last()) {
+            (Some(first), Some(last)) if values.len() >= 2 => {
+                Ok((first.to_f64()?, last.to_f64()?))
+            }
+            _ => Err(VegaFusionError::compilation(&format!(
+                "Scale {} must have at least two elements for {} scales",
+                which, self.scale_type
+            ))),
+        }
+    }
+}
+
+/// Decode a domain or range array from the JSON representation of scale state
+fn state_values(state: &serde_json::Value, key: &str) -> Result<Vec<ScalarValue>> {
+    match state.get(key) {
+        Some(serde_json::Value::Array(values)) => {
+            values.iter().map(ScalarValue::from_json).collect()
+        }
+        _ => Err(VegaFusionError::internal(&format!(
+            "Scale state is missing the {} array",
+            key
+        ))),
+    }
+}
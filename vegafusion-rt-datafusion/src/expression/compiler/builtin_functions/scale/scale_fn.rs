@@ -0,0 +1,91 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use crate::expression::compiler::builtin_functions::scale::ScaleState;
+use crate::expression::compiler::utils::to_numeric;
+use datafusion::logical_plan::{DFSchema, Expr};
+use datafusion::prelude::lit;
+use datafusion::scalar::ScalarValue;
+use datafusion_expr::BuiltinScalarFunction;
+use vegafusion_core::error::{Result, VegaFusionError};
+
+/// `scale(name, value)`
+///
+/// Applies the named scale transform to the specified value. Currently supports
+/// linear, log, and band scales with static domains and ranges.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#scale
+pub fn scale_fn(state: &ScaleState, args: &[Expr], schema: &DFSchema) -> Result<Expr> {
+    // The name argument has already been consumed to look up the scale state
+    let value = args
+        .get(0)
+        .cloned()
+        .ok_or_else(|| VegaFusionError::compilation("scale requires a value argument"))?;
+
+    match state.scale_type.as_str() {
+        "linear" => {
+            let (d0, d1) = state.numeric_domain()?;
+            let (r0, r1) = state.numeric_range()?;
+            let slope = if d1 != d0 { (r1 - r0) / (d1 - d0) } else { 0.0 };
+            let value = to_numeric(value, schema)?;
+            Ok(lit(r0) + (value - lit(d0)) * lit(slope))
+        }
+        "log" => {
+            let (d0, d1) = state.numeric_domain()?;
+            let (r0, r1) = state.numeric_range()?;
+            if d0 <= 0.0 || d1 <= 0.0 {
+                return Err(VegaFusionError::compilation(
+                    "log scale domain must be positive",
+                ));
+            }
+            let log_d0 = d0.ln();
+            let log_span = d1.ln() - log_d0;
+            let slope = if log_span != 0.0 {
+                (r1 - r0) / log_span
+            } else {
+                0.0
+            };
+            let value = to_numeric(value, schema)?;
+            let log_value = Expr::ScalarFunction {
+                fun: BuiltinScalarFunction::Ln,
+                args: vec![value],
+            };
+            Ok(lit(r0) + (log_value - lit(log_d0)) * lit(slope))
+        }
+        "band" => {
+            let (r0, r1) = state.numeric_range()?;
+            let n = state.domain.len();
+            if n == 0 {
+                return Ok(lit(f64::NAN));
+            }
+            let step = (r1 - r0) / n as f64;
+
+            // Build a CASE expression mapping each domain value to its band position
+            let when_then: Vec<(Box<Expr>, Box<Expr>)> = state
+                .domain
+                .iter()
+                .enumerate()
+                .map(|(i, domain_value)| {
+                    let when = value.clone().eq(Expr::Literal(domain_value.clone()));
+                    let then = lit(r0 + step * i as f64);
+                    (Box::new(when), Box::new(then))
+                })
+                .collect();
+
+            Ok(Expr::Case {
+                expr: None,
+                when_then_expr: when_then,
+                else_expr: Some(Box::new(lit(ScalarValue::Float64(None)))),
+            })
+        }
+        scale_type => Err(VegaFusionError::compilation(&format!(
+            "scale() not yet supported for scale type: {}",
+            scale_type
+        ))),
+    }
+}
@@ -0,0 +1,325 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use datafusion::arrow::array::Float64Array;
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion::physical_plan::ColumnarValue;
+use datafusion::scalar::ScalarValue;
+use datafusion_expr::{ReturnTypeFunction, ScalarFunctionImplementation, Signature, Volatility};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use vegafusion_core::data::scalar::ScalarValueHelpers;
+
+/// Statistical distribution functions: the `cumulative*`, `density*`, `quantile*`, and
+/// `sample*` families for the normal, log-normal, and uniform distributions. These back
+/// theoretical-distribution overlays and QQ plots computed in formula expressions.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#statistical-functions
+
+/// Resolution of a distribution's two parameters from the optional trailing arguments
+type DistParamsFn = fn(&[f64]) -> (f64, f64);
+
+/// Normal and log-normal take an optional (mean, stdev), defaulting to (0, 1)
+fn location_scale_params(params: &[f64]) -> (f64, f64) {
+    let mean = params.first().copied().unwrap_or(0.0);
+    let stdev = params.get(1).copied().unwrap_or(1.0);
+    (mean, stdev)
+}
+
+/// Uniform takes optional (min, max) arguments: with no arguments the interval is
+/// [0, 1); with a single argument it is [0, max)
+fn uniform_params(params: &[f64]) -> (f64, f64) {
+    match *params {
+        [] => (0.0, 1.0),
+        [max] => (0.0, max),
+        [min, max, ..] => (min, max),
+    }
+}
+
+// Abramowitz and Stegun approximation 7.1.26, accurate to ~1.5e-7
+fn erf(x: f64) -> f64 {
+    let sign = x.signum();
+    let x = x.abs();
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let y = 1.0
+        - (((((1.061405429 * t - 1.453152027) * t) + 1.421413741) * t - 0.284496736) * t
+            + 0.254829592)
+            * t
+            * (-x * x).exp();
+    sign * y
+}
+
+fn normal_density(value: f64, mean: f64, stdev: f64) -> f64 {
+    let z = (value - mean) / stdev;
+    (-0.5 * z * z).exp() / (stdev * (2.0 * std::f64::consts::PI).sqrt())
+}
+
+fn normal_cumulative(value: f64, mean: f64, stdev: f64) -> f64 {
+    0.5 * (1.0 + erf((value - mean) / (stdev * std::f64::consts::SQRT_2)))
+}
+
+// Acklam's rational approximation to the standard normal quantile function
+fn normal_quantile(p: f64, mean: f64, stdev: f64) -> f64 {
+    if !(0.0..=1.0).contains(&p) {
+        return f64::NAN;
+    }
+    if p == 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p == 1.0 {
+        return f64::INFINITY;
+    }
+
+    const A: [f64; 6] = [
+        -3.969683028665376e+01,
+        2.209460984245205e+02,
+        -2.759285104469687e+02,
+        1.383577518672690e+02,
+        -3.066479806614716e+01,
+        2.506628277459239e+00,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e+01,
+        1.615858368580409e+02,
+        -1.556989798598866e+02,
+        6.680131188771972e+01,
+        -1.328068155288572e+01,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-03,
+        -3.223964580411365e-01,
+        -2.400758277161838e+00,
+        -2.549732539343734e+00,
+        4.374664141464968e+00,
+        2.938163982698783e+00,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-03,
+        3.224671290700398e-01,
+        2.445134137142996e+00,
+        3.754408661907416e+00,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    let z = if p < P_LOW {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else if p <= 1.0 - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        let q = (-2.0 * (1.0 - p).ln()).sqrt();
+        -(((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    mean + stdev * z
+}
+
+fn log_normal_density(value: f64, mean: f64, stdev: f64) -> f64 {
+    if value <= 0.0 {
+        0.0
+    } else {
+        normal_density(value.ln(), mean, stdev) / value
+    }
+}
+
+fn log_normal_cumulative(value: f64, mean: f64, stdev: f64) -> f64 {
+    if value <= 0.0 {
+        0.0
+    } else {
+        normal_cumulative(value.ln(), mean, stdev)
+    }
+}
+
+fn log_normal_quantile(p: f64, mean: f64, stdev: f64) -> f64 {
+    normal_quantile(p, mean, stdev).exp()
+}
+
+fn uniform_density(value: f64, min: f64, max: f64) -> f64 {
+    if value < min || value > max {
+        0.0
+    } else {
+        1.0 / (max - min)
+    }
+}
+
+fn uniform_cumulative(value: f64, min: f64, max: f64) -> f64 {
+    ((value - min) / (max - min)).clamp(0.0, 1.0)
+}
+
+fn uniform_quantile(p: f64, min: f64, max: f64) -> f64 {
+    if (0.0..=1.0).contains(&p) {
+        min + p * (max - min)
+    } else {
+        f64::NAN
+    }
+}
+
+/// Build a UDF for a distribution method that maps a value (or probability) through a
+/// distribution. The first argument may be a column; the distribution parameters
+/// must be scalars.
+fn make_dist_method_udf(
+    name: &str,
+    params_fn: DistParamsFn,
+    method: fn(f64, f64, f64) -> f64,
+) -> ScalarUDF {
+    let dist_fn: ScalarFunctionImplementation = Arc::new(move |args: &[ColumnarValue]| {
+        let params: Vec<f64> = args[1..]
+            .iter()
+            .map(|arg| match arg {
+                ColumnarValue::Scalar(scalar) => scalar.to_f64().unwrap_or(f64::NAN),
+                ColumnarValue::Array(_) => f64::NAN,
+            })
+            .collect();
+        let (p0, p1) = params_fn(params.as_slice());
+
+        Ok(match &args[0] {
+            ColumnarValue::Scalar(value) => {
+                let value = value.to_f64().unwrap_or(f64::NAN);
+                ColumnarValue::Scalar(ScalarValue::from(method(value, p0, p1)))
+            }
+            ColumnarValue::Array(array) => {
+                let array = cast(array, &DataType::Float64)
+                    .expect("Failed to cast distribution function argument to Float64");
+                let array = array.as_any().downcast_ref::<Float64Array>().unwrap();
+                let result: Float64Array = array
+                    .iter()
+                    .map(|v| v.map(|v| method(v, p0, p1)))
+                    .collect();
+                ColumnarValue::Array(Arc::new(result))
+            }
+        })
+    });
+
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Float64)));
+    ScalarUDF::new(
+        name,
+        &Signature::variadic_any(Volatility::Immutable),
+        &return_type,
+        &dist_fn,
+    )
+}
+
+/// Build a volatile UDF that draws samples from a distribution by mapping uniform
+/// pseudo-random draws through the distribution's quantile function
+fn make_dist_sample_udf(
+    name: &str,
+    params_fn: DistParamsFn,
+    quantile: fn(f64, f64, f64) -> f64,
+) -> ScalarUDF {
+    // Same splitmix64 generator as the random() function
+    let init = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("System time before UNIX epoch")
+        .as_nanos() as u64;
+    let state = Mutex::new(init);
+
+    let sample_fn: ScalarFunctionImplementation = Arc::new(move |args: &[ColumnarValue]| {
+        let params: Vec<f64> = args
+            .iter()
+            .map(|arg| match arg {
+                ColumnarValue::Scalar(scalar) => scalar.to_f64().unwrap_or(f64::NAN),
+                ColumnarValue::Array(_) => f64::NAN,
+            })
+            .collect();
+        let (p0, p1) = params_fn(params.as_slice());
+
+        let mut state = state.lock().unwrap();
+        *state = state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^= z >> 31;
+        let uniform = (z >> 11) as f64 / (1u64 << 53) as f64;
+
+        Ok(ColumnarValue::Scalar(ScalarValue::from(quantile(
+            uniform, p0, p1,
+        ))))
+    });
+
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Float64)));
+    ScalarUDF::new(
+        name,
+        &Signature::variadic_any(Volatility::Volatile),
+        &return_type,
+        &sample_fn,
+    )
+}
+
+/// `cumulativeNormal(value[, mean, stdev])`
+pub fn make_cumulative_normal_udf() -> ScalarUDF {
+    make_dist_method_udf("cumulativeNormal", location_scale_params, normal_cumulative)
+}
+
+/// `densityNormal(value[, mean, stdev])`
+pub fn make_density_normal_udf() -> ScalarUDF {
+    make_dist_method_udf("densityNormal", location_scale_params, normal_density)
+}
+
+/// `quantileNormal(probability[, mean, stdev])`
+pub fn make_quantile_normal_udf() -> ScalarUDF {
+    make_dist_method_udf("quantileNormal", location_scale_params, normal_quantile)
+}
+
+/// `sampleNormal([mean, stdev])`
+pub fn make_sample_normal_udf() -> ScalarUDF {
+    make_dist_sample_udf("sampleNormal", location_scale_params, normal_quantile)
+}
+
+/// `cumulativeLogNormal(value[, mean, stdev])`
+pub fn make_cumulative_log_normal_udf() -> ScalarUDF {
+    make_dist_method_udf(
+        "cumulativeLogNormal",
+        location_scale_params,
+        log_normal_cumulative,
+    )
+}
+
+/// `densityLogNormal(value[, mean, stdev])`
+pub fn make_density_log_normal_udf() -> ScalarUDF {
+    make_dist_method_udf("densityLogNormal", location_scale_params, log_normal_density)
+}
+
+/// `quantileLogNormal(probability[, mean, stdev])`
+pub fn make_quantile_log_normal_udf() -> ScalarUDF {
+    make_dist_method_udf(
+        "quantileLogNormal",
+        location_scale_params,
+        log_normal_quantile,
+    )
+}
+
+/// `sampleLogNormal([mean, stdev])`
+pub fn make_sample_log_normal_udf() -> ScalarUDF {
+    make_dist_sample_udf("sampleLogNormal", location_scale_params, log_normal_quantile)
+}
+
+/// `cumulativeUniform(value[, min, max])`
+pub fn make_cumulative_uniform_udf() -> ScalarUDF {
+    make_dist_method_udf("cumulativeUniform", uniform_params, uniform_cumulative)
+}
+
+/// `densityUniform(value[, min, max])`
+pub fn make_density_uniform_udf() -> ScalarUDF {
+    make_dist_method_udf("densityUniform", uniform_params, uniform_density)
+}
+
+/// `quantileUniform(probability[, min, max])`
+pub fn make_quantile_uniform_udf() -> ScalarUDF {
+    make_dist_method_udf("quantileUniform", uniform_params, uniform_quantile)
+}
+
+/// `sampleUniform([min, max])`
+pub fn make_sample_uniform_udf() -> ScalarUDF {
+    make_dist_sample_udf("sampleUniform", uniform_params, uniform_quantile)
+}
@@ -0,0 +1,16 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+/*!
+## Statistical Functions
+Functions for statistical summaries and probability distributions.
+
+See https://vega.github.io/vega/docs/expressions/#statistical-functions
+ */
+pub mod distributions;
+pub mod quantile;
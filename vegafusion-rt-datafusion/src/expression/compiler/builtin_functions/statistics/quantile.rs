@@ -0,0 +1,81 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use datafusion::arrow::datatypes::DataType;
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion::physical_plan::ColumnarValue;
+use datafusion::scalar::ScalarValue;
+use datafusion_expr::{ReturnTypeFunction, ScalarFunctionImplementation, Signature, Volatility};
+use std::cmp::Ordering;
+use std::sync::Arc;
+use vegafusion_core::data::scalar::ScalarValueHelpers;
+
+/// Compute the p-quantile of sorted values using linear interpolation between
+/// adjacent order statistics (the R-7 method used by d3.quantile and Vega)
+pub fn quantile_sorted(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return f64::NAN;
+    }
+    if p <= 0.0 || sorted.len() == 1 {
+        return sorted[0];
+    }
+    if p >= 1.0 {
+        return sorted[sorted.len() - 1];
+    }
+    let h = (sorted.len() - 1) as f64 * p;
+    let lo = h.floor() as usize;
+    let value = sorted[lo];
+    value + (sorted[lo + 1] - value) * (h - lo as f64)
+}
+
+/// `quantile(array, p)`
+///
+/// Returns the quantile value of the input array at probability p, using linear
+/// interpolation between adjacent elements like d3.quantile. Null, undefined, and
+/// NaN values are ignored.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#quantile
+pub fn make_quantile_udf() -> ScalarUDF {
+    let quantile_fn: ScalarFunctionImplementation = Arc::new(|args: &[ColumnarValue]| {
+        // Signature ensures there are two arguments
+        let p = match &args[1] {
+            ColumnarValue::Scalar(value) => value.to_f64().unwrap_or(f64::NAN),
+            ColumnarValue::Array(_) => {
+                todo!("quantile on column not yet implemented")
+            }
+        };
+
+        Ok(match &args[0] {
+            ColumnarValue::Scalar(ScalarValue::List(Some(arr), _)) => {
+                let mut values: Vec<f64> = arr
+                    .iter()
+                    .filter_map(|el| el.to_f64().ok())
+                    .filter(|v| !v.is_nan())
+                    .collect();
+                if values.is_empty() || p.is_nan() {
+                    ColumnarValue::Scalar(ScalarValue::Float64(None))
+                } else {
+                    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+                    ColumnarValue::Scalar(ScalarValue::from(quantile_sorted(&values, p)))
+                }
+            }
+            ColumnarValue::Scalar(_) => ColumnarValue::Scalar(ScalarValue::Float64(None)),
+            ColumnarValue::Array(_) => {
+                todo!("quantile on column not yet implemented")
+            }
+        })
+    });
+
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Float64)));
+    ScalarUDF::new(
+        "quantile",
+        &Signature::any(2, Volatility::Immutable),
+        &return_type,
+        &quantile_fn,
+    )
+}
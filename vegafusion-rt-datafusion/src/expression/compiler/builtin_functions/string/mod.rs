@@ -0,0 +1,16 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+/*!
+## String Functions
+Functions for modifying text values.
+
+See https://vega.github.io/vega/docs/expressions/#string-functions
+ */
+pub mod pad;
+pub mod truncate;
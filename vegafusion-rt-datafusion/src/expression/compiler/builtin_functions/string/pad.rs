@@ -0,0 +1,92 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use datafusion::arrow::array::{ArrayRef, StringArray};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion::physical_plan::ColumnarValue;
+use datafusion::scalar::ScalarValue;
+use datafusion_expr::{ReturnTypeFunction, ScalarFunctionImplementation, Signature, Volatility};
+use std::sync::Arc;
+use vegafusion_core::data::scalar::ScalarValueHelpers;
+
+/// Pad a single string value to the given character length
+pub fn pad_string(value: &str, length: usize, character: &str, align: &str) -> String {
+    let current = value.chars().count();
+    if current >= length || character.is_empty() {
+        return value.to_string();
+    }
+    let fill: String = character.chars().cycle().take(length - current).collect();
+    match align {
+        "left" => format!("{}{}", fill, value),
+        "center" => {
+            let before = (length - current) / 2;
+            let (before_fill, after_fill) = fill.split_at(
+                fill.char_indices()
+                    .nth(before)
+                    .map(|(i, _)| i)
+                    .unwrap_or(fill.len()),
+            );
+            format!("{}{}{}", before_fill, value, after_fill)
+        }
+        // "right" (the default)
+        _ => format!("{}{}", value, fill),
+    }
+}
+
+/// `pad(string, length[, character, align])`
+///
+/// Pads a string value with repeated instances of a character up to a specified
+/// length. If character is not specified, a space (' ') is used. By default, padding
+/// is added to the end of a string. An optional align parameter specifies if padding
+/// should be added to the 'left' (beginning), 'center', or 'right' (end) of the
+/// input string.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#pad
+pub fn make_pad_udf() -> ScalarUDF {
+    let pad_fn: ScalarFunctionImplementation = Arc::new(|args: &[ColumnarValue]| {
+        let length = match args.get(1) {
+            Some(ColumnarValue::Scalar(value)) => value.to_f64().unwrap_or(0.0).max(0.0) as usize,
+            _ => 0,
+        };
+        let character = match args.get(2) {
+            Some(ColumnarValue::Scalar(ScalarValue::Utf8(Some(c)))) => c.clone(),
+            _ => " ".to_string(),
+        };
+        let align = match args.get(3) {
+            Some(ColumnarValue::Scalar(ScalarValue::Utf8(Some(a)))) => a.clone(),
+            _ => "right".to_string(),
+        };
+
+        Ok(match &args[0] {
+            ColumnarValue::Scalar(ScalarValue::Utf8(Some(s)))
+            | ColumnarValue::Scalar(ScalarValue::LargeUtf8(Some(s))) => ColumnarValue::Scalar(
+                ScalarValue::from(pad_string(s, length, &character, &align).as_str()),
+            ),
+            ColumnarValue::Scalar(_) => ColumnarValue::Scalar(ScalarValue::Utf8(None)),
+            ColumnarValue::Array(array) => {
+                let array = array.as_any().downcast_ref::<StringArray>().unwrap();
+                let padded = StringArray::from(
+                    array
+                        .iter()
+                        .map(|s| s.map(|s| pad_string(s, length, &character, &align)))
+                        .collect::<Vec<Option<String>>>(),
+                );
+                ColumnarValue::Array(Arc::new(padded) as ArrayRef)
+            }
+        })
+    });
+
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Utf8)));
+    ScalarUDF::new(
+        "pad",
+        &Signature::variadic_any(Volatility::Immutable),
+        &return_type,
+        &pad_fn,
+    )
+}
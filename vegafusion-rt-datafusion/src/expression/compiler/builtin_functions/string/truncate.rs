@@ -0,0 +1,96 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use datafusion::arrow::array::{ArrayRef, StringArray};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion::physical_plan::ColumnarValue;
+use datafusion::scalar::ScalarValue;
+use datafusion_expr::{ReturnTypeFunction, ScalarFunctionImplementation, Signature, Volatility};
+use std::sync::Arc;
+use vegafusion_core::data::scalar::ScalarValueHelpers;
+
+/// Truncate a single string value to the given character length
+pub fn truncate_string(value: &str, length: usize, align: &str, ellipsis: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() <= length {
+        return value.to_string();
+    }
+    let ellipsis_len = ellipsis.chars().count();
+    let keep = length.saturating_sub(ellipsis_len);
+    match align {
+        "left" => {
+            let tail: String = chars[chars.len() - keep..].iter().collect();
+            format!("{}{}", ellipsis, tail)
+        }
+        "center" => {
+            let head_len = keep / 2 + keep % 2;
+            let tail_len = keep / 2;
+            let head: String = chars[..head_len].iter().collect();
+            let tail: String = chars[chars.len() - tail_len..].iter().collect();
+            format!("{}{}{}", head, ellipsis, tail)
+        }
+        // "right" (the default)
+        _ => {
+            let head: String = chars[..keep].iter().collect();
+            format!("{}{}", head, ellipsis)
+        }
+    }
+}
+
+/// `truncate(string, length[, align, ellipsis])`
+///
+/// Truncates a string value to a specified length. The optional align argument
+/// indicates what part of the string should be truncated: 'left' (the beginning),
+/// 'center', or 'right' (the end, the default). An optional ellipsis argument
+/// indicates the string to use to indicate truncated content; by default the
+/// ellipsis character '…' (…) is used.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#truncate
+pub fn make_truncate_udf() -> ScalarUDF {
+    let truncate_fn: ScalarFunctionImplementation = Arc::new(|args: &[ColumnarValue]| {
+        let length = match args.get(1) {
+            Some(ColumnarValue::Scalar(value)) => value.to_f64().unwrap_or(0.0).max(0.0) as usize,
+            _ => 0,
+        };
+        let align = match args.get(2) {
+            Some(ColumnarValue::Scalar(ScalarValue::Utf8(Some(a)))) => a.clone(),
+            _ => "right".to_string(),
+        };
+        let ellipsis = match args.get(3) {
+            Some(ColumnarValue::Scalar(ScalarValue::Utf8(Some(e)))) => e.clone(),
+            _ => "\u{2026}".to_string(),
+        };
+
+        Ok(match &args[0] {
+            ColumnarValue::Scalar(ScalarValue::Utf8(Some(s)))
+            | ColumnarValue::Scalar(ScalarValue::LargeUtf8(Some(s))) => ColumnarValue::Scalar(
+                ScalarValue::from(truncate_string(s, length, &align, &ellipsis).as_str()),
+            ),
+            ColumnarValue::Scalar(_) => ColumnarValue::Scalar(ScalarValue::Utf8(None)),
+            ColumnarValue::Array(array) => {
+                let array = array.as_any().downcast_ref::<StringArray>().unwrap();
+                let truncated = StringArray::from(
+                    array
+                        .iter()
+                        .map(|s| s.map(|s| truncate_string(s, length, &align, &ellipsis)))
+                        .collect::<Vec<Option<String>>>(),
+                );
+                ColumnarValue::Array(Arc::new(truncated) as ArrayRef)
+            }
+        })
+    });
+
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Utf8)));
+    ScalarUDF::new(
+        "truncate",
+        &Signature::variadic_any(Volatility::Immutable),
+        &return_type,
+        &truncate_fn,
+    )
+}
@@ -0,0 +1,101 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use datafusion::logical_plan::{DFSchema, Expr, ExprSchemable};
+
+use datafusion::prelude::lit;
+use vegafusion_core::arrow::datatypes::DataType;
+use vegafusion_core::error::{Result, ResultWithContext, VegaFusionError};
+
+/// Compile a type predicate by inferring the static Arrow type of the single argument
+/// and applying `predicate` to it. Follows the same approach as `isDate`: the check is
+/// resolved at compile time from the column type rather than per-row.
+fn is_type_fn(
+    name: &str,
+    args: &[Expr],
+    schema: &DFSchema,
+    predicate: fn(&DataType) -> bool,
+) -> Result<Expr> {
+    if args.len() == 1 {
+        let arg = args[0].clone();
+        let dtype = arg
+            .get_type(schema)
+            .with_context(|| format!("Failed to infer type of expression: {:?}", arg))?;
+        Ok(lit(predicate(&dtype)))
+    } else {
+        Err(VegaFusionError::parse(format!(
+            "{} requires a single argument. Received {} arguments",
+            name,
+            args.len()
+        )))
+    }
+}
+
+/// `isArray(value)`
+///
+/// Returns true if value is an array, false otherwise.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#isArray
+pub fn is_array_fn(args: &[Expr], schema: &DFSchema) -> Result<Expr> {
+    is_type_fn("isArray", args, schema, |dtype| {
+        matches!(
+            dtype,
+            DataType::List(_) | DataType::LargeList(_) | DataType::FixedSizeList(_, _)
+        )
+    })
+}
+
+/// `isObject(value)`
+///
+/// Returns true if value is an object (including arrays and Dates), false otherwise.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#isObject
+pub fn is_object_fn(args: &[Expr], schema: &DFSchema) -> Result<Expr> {
+    is_type_fn("isObject", args, schema, |dtype| {
+        matches!(
+            dtype,
+            DataType::Struct(_)
+                | DataType::Map(_, _)
+                | DataType::List(_)
+                | DataType::LargeList(_)
+                | DataType::FixedSizeList(_, _)
+        )
+    })
+}
+
+/// `isString(value)`
+///
+/// Returns true if value is a string, false otherwise.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#isString
+pub fn is_string_fn(args: &[Expr], schema: &DFSchema) -> Result<Expr> {
+    is_type_fn("isString", args, schema, |dtype| {
+        matches!(dtype, DataType::Utf8 | DataType::LargeUtf8)
+    })
+}
+
+/// `isNumber(value)`
+///
+/// Returns true if value is a number, false otherwise. NaN and Infinity are
+/// considered numbers.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#isNumber
+pub fn is_number_fn(args: &[Expr], schema: &DFSchema) -> Result<Expr> {
+    is_type_fn("isNumber", args, schema, DataType::is_numeric)
+}
+
+/// `isBoolean(value)`
+///
+/// Returns true if value is a boolean (true or false), false otherwise.
+///
+/// See: https://vega.github.io/vega/docs/expressions/#isBoolean
+pub fn is_boolean_fn(args: &[Expr], schema: &DFSchema) -> Result<Expr> {
+    is_type_fn("isBoolean", args, schema, |dtype| {
+        matches!(dtype, DataType::Boolean)
+    })
+}
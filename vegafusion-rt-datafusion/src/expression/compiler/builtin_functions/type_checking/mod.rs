@@ -13,4 +13,5 @@ Predicate functions for checking value types.
 See https://vega.github.io/vega/docs/expressions/#type-checking-functions
  */
 pub mod isdate;
+pub mod istype;
 pub mod isvalid;
@@ -6,6 +6,7 @@
  * Please consult the license documentation provided alongside
  * this program the details of the active license.
  */
+pub mod number_to_string;
 pub mod to_boolean;
 pub mod to_number;
 pub mod to_string;
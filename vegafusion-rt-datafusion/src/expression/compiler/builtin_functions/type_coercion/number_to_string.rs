@@ -0,0 +1,73 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use datafusion::arrow::array::{Float64Array, StringArray};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::physical_plan::udf::ScalarUDF;
+use datafusion::physical_plan::ColumnarValue;
+use datafusion::scalar::ScalarValue;
+use datafusion_expr::{ReturnTypeFunction, ScalarFunctionImplementation, Signature, Volatility};
+use std::sync::Arc;
+use vegafusion_core::data::scalar::ScalarValueHelpers;
+
+/// Format a float the way JavaScript's number-to-string coercion does: integral values
+/// are written without a decimal point (`1` rather than `1.0`), and the non-finite
+/// values use JavaScript's names
+pub fn js_number_to_string(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value == f64::INFINITY {
+        "Infinity".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Infinity".to_string()
+    } else if value.fract() == 0.0 && value.abs() < 1e21 {
+        format!("{:.0}", value)
+    } else {
+        value.to_string()
+    }
+}
+
+/// UDF that converts float values to strings using JavaScript's coercion rules.
+/// Used when compiling string concatenation with the `+` operator so that label
+/// strings match those produced by the Vega JavaScript runtime
+pub fn make_number_to_string_udf() -> ScalarUDF {
+    let number_to_string_fn: ScalarFunctionImplementation =
+        Arc::new(|args: &[ColumnarValue]| {
+            // Signature ensures there is a single argument
+            Ok(match &args[0] {
+                ColumnarValue::Scalar(value) => {
+                    let formatted = if value.is_null() {
+                        ScalarValue::Utf8(None)
+                    } else {
+                        let value = value.to_f64().unwrap_or(f64::NAN);
+                        ScalarValue::from(js_number_to_string(value).as_str())
+                    };
+                    ColumnarValue::Scalar(formatted)
+                }
+                ColumnarValue::Array(array) => {
+                    let array = cast(array, &DataType::Float64)
+                        .expect("Failed to cast number_to_string argument to Float64");
+                    let array = array.as_any().downcast_ref::<Float64Array>().unwrap();
+                    let formatted: StringArray = array
+                        .iter()
+                        .map(|v| v.map(js_number_to_string))
+                        .collect();
+                    ColumnarValue::Array(Arc::new(formatted))
+                }
+            })
+        });
+
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Utf8)));
+    ScalarUDF::new(
+        "number_to_string",
+        &Signature::any(1, Volatility::Immutable),
+        &return_type,
+        &number_to_string_fn,
+    )
+}
@@ -6,9 +6,30 @@
  * Please consult the license documentation provided alongside
  * this program the details of the active license.
  */
+use crate::expression::compiler::builtin_functions::array::extent::make_extent_udf;
+use crate::expression::compiler::builtin_functions::array::indexof::{
+    make_indexof_udf, make_lastindexof_udf,
+};
+use crate::expression::compiler::builtin_functions::array::join::make_join_udf;
 use crate::expression::compiler::builtin_functions::array::length::make_length_udf;
+use crate::expression::compiler::builtin_functions::array::inrange::{
+    make_clamp_range_udf, make_inrange_udf,
+};
+use crate::expression::compiler::builtin_functions::array::pluck::make_pluck_udf;
+use crate::expression::compiler::builtin_functions::object::merge::merge_fn;
+use crate::expression::compiler::builtin_functions::array::reverse::make_reverse_udf;
+use crate::expression::compiler::builtin_functions::array::sequence::make_sequence_udf;
+use crate::expression::compiler::builtin_functions::array::slice::make_slice_udf;
+use crate::expression::compiler::builtin_functions::array::sort::make_sort_udf;
 use crate::expression::compiler::builtin_functions::array::span::make_span_udf;
+use crate::expression::compiler::builtin_functions::color::constructors::{
+    make_hcl_udf, make_hsl_udf, make_lab_udf, make_rgb_udf,
+};
+use crate::expression::compiler::builtin_functions::color::metrics::{
+    make_contrast_udf, make_luminance_udf,
+};
 use crate::expression::compiler::builtin_functions::control_flow::if_fn::if_fn;
+use crate::expression::compiler::builtin_functions::control_flow::logging::{log_fn, LogLevel};
 use crate::expression::compiler::builtin_functions::date_time::date_parts::{
     DATE_TRANSFORM, DAYOFYEAR_TRANSFORM, DAY_TRANSFORM, HOURS_TRANSFORM, MILLISECONDS_TRANSFORM,
     MINUTES_TRANSFORM, MONTH_TRANSFORM, QUARTER_TRANSFORM, SECONDS_TRANSFORM, UTCDATE_UDF,
@@ -20,7 +41,24 @@ use crate::expression::compiler::builtin_functions::date_time::datetime::{
 };
 use crate::expression::compiler::builtin_functions::math::isfinite::make_is_finite_udf;
 use crate::expression::compiler::builtin_functions::math::isnan::make_is_nan_udf;
+use crate::expression::compiler::builtin_functions::math::lerp::make_lerp_udf;
 use crate::expression::compiler::builtin_functions::math::pow::make_pow_udf;
+use crate::expression::compiler::builtin_functions::math::random::make_random_udf;
+use crate::expression::compiler::builtin_functions::scale::invert_fn::invert_fn;
+use crate::expression::compiler::builtin_functions::scale::metadata::{
+    bandwidth_fn, domain_fn, range_fn,
+};
+use crate::expression::compiler::builtin_functions::scale::scale_fn::scale_fn;
+use crate::expression::compiler::builtin_functions::scale::ScaleState;
+use crate::expression::compiler::builtin_functions::statistics::distributions::{
+    make_cumulative_log_normal_udf, make_cumulative_normal_udf, make_cumulative_uniform_udf,
+    make_density_log_normal_udf, make_density_normal_udf, make_density_uniform_udf,
+    make_quantile_log_normal_udf, make_quantile_normal_udf, make_quantile_uniform_udf,
+    make_sample_log_normal_udf, make_sample_normal_udf, make_sample_uniform_udf,
+};
+use crate::expression::compiler::builtin_functions::statistics::quantile::make_quantile_udf;
+use crate::expression::compiler::builtin_functions::string::pad::make_pad_udf;
+use crate::expression::compiler::builtin_functions::string::truncate::make_truncate_udf;
 use crate::expression::compiler::builtin_functions::type_checking::isvalid::make_is_valid_udf;
 use crate::expression::compiler::compile;
 use crate::expression::compiler::config::CompilationConfig;
@@ -32,21 +70,32 @@ use datafusion_expr::BuiltinScalarFunction;
 use std::collections::HashMap;
 use std::ops::Deref;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use vegafusion_core::data::table::VegaFusionTable;
 use vegafusion_core::error::{Result, ResultWithContext, VegaFusionError};
+use vegafusion_core::expression::supported::register_custom_expression_fn;
 use vegafusion_core::proto::gen::expression::{
     expression, literal, CallExpression, Expression, Literal,
 };
 
 use crate::expression::compiler::builtin_functions::data::data_fn::data_fn;
+use crate::expression::compiler::builtin_functions::data::indata::indata_fn;
+use crate::expression::compiler::builtin_functions::data::modify::modify_fn;
+use crate::expression::compiler::builtin_functions::data::vl_selection_id_test::vl_selection_id_test_fn;
 use crate::expression::compiler::builtin_functions::data::vl_selection_resolve::vl_selection_resolve_fn;
 use crate::expression::compiler::builtin_functions::data::vl_selection_test::vl_selection_test_fn;
 use crate::expression::compiler::builtin_functions::date_time::date_format::{
     time_format_fn, utc_format_fn,
 };
+use crate::expression::compiler::builtin_functions::date_time::label_formats::{
+    get_time_locale, make_day_abbrev_format_udf, make_day_format_udf,
+    make_month_abbrev_format_udf, make_month_format_udf,
+};
 use crate::expression::compiler::builtin_functions::date_time::time::time_fn;
 use crate::expression::compiler::builtin_functions::type_checking::isdate::is_date_fn;
+use crate::expression::compiler::builtin_functions::type_checking::istype::{
+    is_array_fn, is_boolean_fn, is_number_fn, is_object_fn, is_string_fn,
+};
 use crate::expression::compiler::builtin_functions::type_coercion::to_boolean::to_boolean_transform;
 use crate::expression::compiler::builtin_functions::type_coercion::to_number::to_number_transform;
 use crate::expression::compiler::builtin_functions::type_coercion::to_string::to_string_transform;
@@ -58,6 +107,7 @@ pub type LocalTransformFn =
     Arc<dyn Fn(&RuntimeTzConfig, &[Expr], &DFSchema) -> Result<Expr> + Send + Sync>;
 pub type DataFn =
     Arc<dyn Fn(&VegaFusionTable, &[Expression], &DFSchema) -> Result<Expr> + Send + Sync>;
+pub type ScaleFn = Arc<dyn Fn(&ScaleState, &[Expr], &DFSchema) -> Result<Expr> + Send + Sync>;
 
 #[derive(Clone)]
 pub enum VegaFusionCallable {
@@ -90,10 +140,10 @@ pub enum VegaFusionCallable {
     /// e.g. `data('brush')` or  `vlSelectionTest('brush', datum, true)`
     Data(DataFn),
 
-    /// A custom runtime function that operates on a scale dataset
+    /// A custom function that operates on the state of a named scale
     ///
-    /// Placeholder for now
-    Scale,
+    /// e.g. `scale('x', datum.value)`
+    Scale(ScaleFn),
 }
 
 pub fn compile_scalar_arguments(
@@ -119,6 +169,13 @@ pub fn compile_call(
     config: &CompilationConfig,
     schema: &DFSchema,
 ) -> Result<Expr> {
+    // Logging functions are handled here rather than through the callable scope so
+    // they can record their message on the request's warning collector
+    if let Some(level) = LogLevel::from_name(&node.callee) {
+        let new_expr = log_fn(level, &node.arguments, &config.expression_warnings)?;
+        return compile(&new_expr, config, Some(schema));
+    }
+
     let callable = config.callable_scope.get(&node.callee).ok_or_else(|| {
         VegaFusionError::compilation(&format!("No global function named {}", &node.callee))
     })?;
@@ -185,8 +242,41 @@ pub fn compile_call(
                 .with_context(|| "No local timezone info provided".to_string())?;
             callable(&tz_config, &args, schema)
         }
-        _ => {
-            todo!()
+        VegaFusionCallable::Scale(callable) => {
+            if let Some(v) = node.arguments.get(0) {
+                match v.expr() {
+                    expression::Expr::Literal(Literal {
+                        value: Some(literal::Value::String(name)),
+                        ..
+                    }) => {
+                        if let Some(scale) = config.scale_scope.get(name) {
+                            // Compile the remaining arguments
+                            let mut args: Vec<Expr> = Vec::new();
+                            for arg in node.arguments.iter().skip(1) {
+                                args.push(compile(arg, config, Some(schema))?);
+                            }
+                            callable(scale, &args, schema)
+                        } else {
+                            Err(VegaFusionError::internal(&format!(
+                                "No scale named {}. Available: {:?}",
+                                name,
+                                config.scale_scope.keys()
+                            )))
+                        }
+                    }
+                    _ => Err(VegaFusionError::internal(&format!(
+                        "The first argument to the {} function must be a literal \
+                                string with the name of a scale",
+                        &node.callee
+                    ))),
+                }
+            } else {
+                Err(VegaFusionError::internal(&format!(
+                    "The first argument to the {} function must be a literal \
+                                string with the name of a scale",
+                    &node.callee
+                )))
+            }
         }
     }
 }
@@ -228,6 +318,14 @@ pub fn default_callables() -> HashMap<String, VegaFusionCallable> {
         },
     );
 
+    callables.insert(
+        "random".to_string(),
+        VegaFusionCallable::ScalarUDF {
+            udf: make_random_udf(None),
+            cast: None,
+        },
+    );
+
     callables.insert(
         "isNaN".to_string(),
         VegaFusionCallable::ScalarUDF {
@@ -257,6 +355,37 @@ pub fn default_callables() -> HashMap<String, VegaFusionCallable> {
         VegaFusionCallable::Transform(Arc::new(is_date_fn)),
     );
 
+    callables.insert(
+        "isArray".to_string(),
+        VegaFusionCallable::Transform(Arc::new(is_array_fn)),
+    );
+
+    callables.insert(
+        "isObject".to_string(),
+        VegaFusionCallable::Transform(Arc::new(is_object_fn)),
+    );
+
+    callables.insert(
+        "isString".to_string(),
+        VegaFusionCallable::Transform(Arc::new(is_string_fn)),
+    );
+
+    callables.insert(
+        "isNumber".to_string(),
+        VegaFusionCallable::Transform(Arc::new(is_number_fn)),
+    );
+
+    callables.insert(
+        "isBoolean".to_string(),
+        VegaFusionCallable::Transform(Arc::new(is_boolean_fn)),
+    );
+
+    // Object functions
+    callables.insert(
+        "merge".to_string(),
+        VegaFusionCallable::Transform(Arc::new(merge_fn)),
+    );
+
     callables.insert(
         "length".to_string(),
         VegaFusionCallable::ScalarUDF {
@@ -273,6 +402,192 @@ pub fn default_callables() -> HashMap<String, VegaFusionCallable> {
         },
     );
 
+    callables.insert(
+        "sequence".to_string(),
+        VegaFusionCallable::ScalarUDF {
+            udf: make_sequence_udf(),
+            cast: Some(DataType::Float64),
+        },
+    );
+
+    callables.insert(
+        "extent".to_string(),
+        VegaFusionCallable::ScalarUDF {
+            udf: make_extent_udf(),
+            cast: None,
+        },
+    );
+
+    callables.insert(
+        "join".to_string(),
+        VegaFusionCallable::ScalarUDF {
+            udf: make_join_udf(),
+            cast: None,
+        },
+    );
+
+    callables.insert(
+        "reverse".to_string(),
+        VegaFusionCallable::ScalarUDF {
+            udf: make_reverse_udf(),
+            cast: None,
+        },
+    );
+
+    callables.insert(
+        "sort".to_string(),
+        VegaFusionCallable::ScalarUDF {
+            udf: make_sort_udf(),
+            cast: None,
+        },
+    );
+
+    callables.insert(
+        "slice".to_string(),
+        VegaFusionCallable::ScalarUDF {
+            udf: make_slice_udf(),
+            cast: None,
+        },
+    );
+
+    callables.insert(
+        "lerp".to_string(),
+        VegaFusionCallable::ScalarUDF {
+            udf: make_lerp_udf(),
+            cast: None,
+        },
+    );
+
+    callables.insert(
+        "quantile".to_string(),
+        VegaFusionCallable::ScalarUDF {
+            udf: make_quantile_udf(),
+            cast: None,
+        },
+    );
+
+    // Statistical distribution functions
+    for udf in [
+        make_cumulative_normal_udf(),
+        make_density_normal_udf(),
+        make_quantile_normal_udf(),
+        make_sample_normal_udf(),
+        make_cumulative_log_normal_udf(),
+        make_density_log_normal_udf(),
+        make_quantile_log_normal_udf(),
+        make_sample_log_normal_udf(),
+        make_cumulative_uniform_udf(),
+        make_density_uniform_udf(),
+        make_quantile_uniform_udf(),
+        make_sample_uniform_udf(),
+    ] {
+        callables.insert(
+            udf.name.clone(),
+            VegaFusionCallable::ScalarUDF { udf, cast: None },
+        );
+    }
+
+    callables.insert(
+        "indexof".to_string(),
+        VegaFusionCallable::ScalarUDF {
+            udf: make_indexof_udf(),
+            cast: None,
+        },
+    );
+
+    callables.insert(
+        "lastindexof".to_string(),
+        VegaFusionCallable::ScalarUDF {
+            udf: make_lastindexof_udf(),
+            cast: None,
+        },
+    );
+
+    callables.insert(
+        "pluck".to_string(),
+        VegaFusionCallable::ScalarUDF {
+            udf: make_pluck_udf(),
+            cast: None,
+        },
+    );
+
+    callables.insert(
+        "inrange".to_string(),
+        VegaFusionCallable::ScalarUDF {
+            udf: make_inrange_udf(),
+            cast: None,
+        },
+    );
+
+    callables.insert(
+        "clampRange".to_string(),
+        VegaFusionCallable::ScalarUDF {
+            udf: make_clamp_range_udf(),
+            cast: None,
+        },
+    );
+
+    // String functions
+    callables.insert(
+        "pad".to_string(),
+        VegaFusionCallable::ScalarUDF {
+            udf: make_pad_udf(),
+            cast: None,
+        },
+    );
+
+    callables.insert(
+        "truncate".to_string(),
+        VegaFusionCallable::ScalarUDF {
+            udf: make_truncate_udf(),
+            cast: None,
+        },
+    );
+
+    // Color functions
+    callables.insert(
+        "rgb".to_string(),
+        VegaFusionCallable::ScalarUDF {
+            udf: make_rgb_udf(),
+            cast: Some(DataType::Float64),
+        },
+    );
+    callables.insert(
+        "hsl".to_string(),
+        VegaFusionCallable::ScalarUDF {
+            udf: make_hsl_udf(),
+            cast: Some(DataType::Float64),
+        },
+    );
+    callables.insert(
+        "lab".to_string(),
+        VegaFusionCallable::ScalarUDF {
+            udf: make_lab_udf(),
+            cast: Some(DataType::Float64),
+        },
+    );
+    callables.insert(
+        "hcl".to_string(),
+        VegaFusionCallable::ScalarUDF {
+            udf: make_hcl_udf(),
+            cast: Some(DataType::Float64),
+        },
+    );
+    callables.insert(
+        "luminance".to_string(),
+        VegaFusionCallable::ScalarUDF {
+            udf: make_luminance_udf(),
+            cast: None,
+        },
+    );
+    callables.insert(
+        "contrast".to_string(),
+        VegaFusionCallable::ScalarUDF {
+            udf: make_contrast_udf(),
+            cast: None,
+        },
+    );
+
     // Date parts
     callables.insert(
         "year".to_string(),
@@ -406,6 +721,37 @@ pub fn default_callables() -> HashMap<String, VegaFusionCallable> {
         "timeFormat".to_string(),
         VegaFusionCallable::LocalTransform(Arc::new(time_format_fn)),
     );
+
+    // Weekday / month label functions with the configured time locale
+    let time_locale = get_time_locale();
+    callables.insert(
+        "dayFormat".to_string(),
+        VegaFusionCallable::ScalarUDF {
+            udf: make_day_format_udf(&time_locale),
+            cast: Some(DataType::Int64),
+        },
+    );
+    callables.insert(
+        "dayAbbrevFormat".to_string(),
+        VegaFusionCallable::ScalarUDF {
+            udf: make_day_abbrev_format_udf(&time_locale),
+            cast: Some(DataType::Int64),
+        },
+    );
+    callables.insert(
+        "monthFormat".to_string(),
+        VegaFusionCallable::ScalarUDF {
+            udf: make_month_format_udf(&time_locale),
+            cast: Some(DataType::Int64),
+        },
+    );
+    callables.insert(
+        "monthAbbrevFormat".to_string(),
+        VegaFusionCallable::ScalarUDF {
+            udf: make_month_abbrev_format_udf(&time_locale),
+            cast: Some(DataType::Int64),
+        },
+    );
     callables.insert(
         "utcFormat".to_string(),
         VegaFusionCallable::LocalTransform(Arc::new(utc_format_fn)),
@@ -435,15 +781,84 @@ pub fn default_callables() -> HashMap<String, VegaFusionCallable> {
         VegaFusionCallable::Data(Arc::new(data_fn)),
     );
 
+    callables.insert(
+        "indata".to_string(),
+        VegaFusionCallable::Data(Arc::new(indata_fn)),
+    );
+
     callables.insert(
         "vlSelectionTest".to_string(),
         VegaFusionCallable::Data(Arc::new(vl_selection_test_fn)),
     );
 
+    callables.insert(
+        "modify".to_string(),
+        VegaFusionCallable::Data(Arc::new(modify_fn)),
+    );
+
+    callables.insert(
+        "vlSelectionIdTest".to_string(),
+        VegaFusionCallable::Data(Arc::new(vl_selection_id_test_fn)),
+    );
+
     callables.insert(
         "vlSelectionResolve".to_string(),
         VegaFusionCallable::Data(Arc::new(vl_selection_resolve_fn)),
     );
 
+    // scales
+    callables.insert(
+        "scale".to_string(),
+        VegaFusionCallable::Scale(Arc::new(scale_fn)),
+    );
+
+    callables.insert(
+        "invert".to_string(),
+        VegaFusionCallable::Scale(Arc::new(invert_fn)),
+    );
+
+    callables.insert(
+        "domain".to_string(),
+        VegaFusionCallable::Scale(Arc::new(domain_fn)),
+    );
+
+    callables.insert(
+        "range".to_string(),
+        VegaFusionCallable::Scale(Arc::new(range_fn)),
+    );
+
+    callables.insert(
+        "bandwidth".to_string(),
+        VegaFusionCallable::Scale(Arc::new(bandwidth_fn)),
+    );
+
+    // Include custom callables registered by downstream applications
+    let custom_callables = CUSTOM_CALLABLES.read().unwrap();
+    for (name, callable) in custom_callables.iter() {
+        callables.insert(name.clone(), callable.clone());
+    }
+
     callables
 }
+
+lazy_static! {
+    static ref CUSTOM_CALLABLES: RwLock<HashMap<String, VegaFusionCallable>> = Default::default();
+}
+
+/// Register a custom callable under the provided function name. The name is also
+/// registered with the planner so that expressions calling the function are planned
+/// for server-side evaluation rather than falling back to the client.
+///
+/// Registration must happen before specs are planned and task graphs are built.
+pub fn register_callable(name: &str, callable: VegaFusionCallable) {
+    register_custom_expression_fn(name);
+    let mut custom_callables = CUSTOM_CALLABLES.write().unwrap();
+    custom_callables.insert(name.to_string(), callable);
+}
+
+/// Register a custom DataFusion scalar UDF as an expression function. The UDF's name
+/// is used as the expression function name.
+pub fn register_udf(udf: ScalarUDF) {
+    let name = udf.name.clone();
+    register_callable(&name, VegaFusionCallable::ScalarUDF { udf, cast: None });
+}
@@ -6,6 +6,9 @@
  * Please consult the license documentation provided alongside
  * this program the details of the active license.
  */
+use crate::expression::compiler::builtin_functions::control_flow::logging::ExpressionWarningCollector;
+use crate::expression::compiler::builtin_functions::math::random::make_random_udf;
+use crate::expression::compiler::builtin_functions::scale::ScaleState;
 use crate::expression::compiler::call::{default_callables, VegaFusionCallable};
 use crate::task_graph::timezone::RuntimeTzConfig;
 use datafusion::scalar::ScalarValue;
@@ -17,9 +20,14 @@ use vegafusion_core::data::table::VegaFusionTable;
 pub struct CompilationConfig {
     pub signal_scope: HashMap<String, ScalarValue>,
     pub data_scope: HashMap<String, VegaFusionTable>,
+    pub scale_scope: HashMap<String, ScaleState>,
     pub callable_scope: HashMap<String, VegaFusionCallable>,
     pub constants: HashMap<String, ScalarValue>,
     pub tz_config: Option<RuntimeTzConfig>,
+
+    /// Per-request collector for the warnings produced by warn/info/debug
+    /// expression functions
+    pub expression_warnings: ExpressionWarningCollector,
 }
 
 impl Default for CompilationConfig {
@@ -27,13 +35,31 @@ impl Default for CompilationConfig {
         Self {
             signal_scope: Default::default(),
             data_scope: Default::default(),
+            scale_scope: Default::default(),
             callable_scope: default_callables(),
             constants: default_constants(),
             tz_config: None,
+            expression_warnings: Default::default(),
         }
     }
 }
 
+impl CompilationConfig {
+    /// Replace the `random` callable with one that produces a deterministic sequence
+    /// seeded with `seed`. This makes repeated evaluation of specs that use `random()`
+    /// reproducible, and keeps task value cache fingerprints stable.
+    pub fn with_random_seed(mut self, seed: u64) -> Self {
+        self.callable_scope.insert(
+            "random".to_string(),
+            VegaFusionCallable::ScalarUDF {
+                udf: make_random_udf(Some(seed)),
+                cast: None,
+            },
+        );
+        self
+    }
+}
+
 /// ## Constants
 /// Constant values that can be referenced by name within expressions.
 ///
@@ -6,7 +6,9 @@
  * Please consult the license documentation provided alongside
  * this program the details of the active license.
  */
-use crate::expression::compiler::utils::{cast_to, data_type, is_numeric_datatype, to_boolean};
+use crate::expression::compiler::utils::{
+    cast_to, data_type, is_numeric_datatype, is_string_datatype, to_boolean, to_string,
+};
 use crate::expression::compiler::{compile, config::CompilationConfig};
 use datafusion::arrow::datatypes::DataType;
 use datafusion::logical_plan::{DFSchema, Expr, Operator};
@@ -52,6 +54,13 @@ pub fn compile_logical(
                 compiled_rhs = cast_to(compiled_rhs, &lhs_dtype, schema)?;
             } else if is_numeric_datatype(&rhs_dtype) && lhs_dtype == DataType::Boolean {
                 compiled_lhs = cast_to(compiled_lhs, &rhs_dtype, schema)?;
+            } else if is_string_datatype(&lhs_dtype) && is_numeric_datatype(&rhs_dtype) {
+                // When one side is a string the branches can't share a numeric type, so
+                // convert the numeric side to a string. Truthiness was evaluated above on
+                // the original value
+                compiled_rhs = to_string(compiled_rhs, schema)?;
+            } else if is_numeric_datatype(&lhs_dtype) && is_string_datatype(&rhs_dtype) {
+                compiled_lhs = to_string(compiled_lhs, schema)?;
             }
 
             match node.to_operator() {
@@ -155,7 +155,27 @@ pub fn make_get_object_member_udf(
         let object = &args[0];
 
         let struct_array = object.as_any().downcast_ref::<StructArray>().unwrap();
-        Ok(struct_array.column(field_index).clone())
+        let column = struct_array.column(field_index).clone();
+        if struct_array.null_count() > 0 {
+            // Propagate the struct-level validity into the extracted column so that
+            // missing nested objects produce null rather than the child's stored value.
+            // This comes up with chained member access (e.g. datum.a.b) on datasets
+            // loaded from nested JSON
+            let mut take_index_builder = Int32Array::builder(struct_array.len());
+            for i in 0..struct_array.len() {
+                if struct_array.is_null(i) {
+                    take_index_builder.append_null().unwrap();
+                } else {
+                    take_index_builder.append_value(i as i32).unwrap();
+                }
+            }
+            Ok(
+                kernels::take::take(column.as_ref(), &take_index_builder.finish(), Default::default())
+                    .unwrap(),
+            )
+        } else {
+            Ok(column)
+        }
     };
     let get = make_scalar_function(get);
 
@@ -39,6 +39,10 @@ use vegafusion_core::proto::gen::expression::{expression::Expr as vfExpr, Expres
 
 /// Function to compile a parsed expression into a CompiledExpression, given a scope containing
 /// a SignalValue for every unbound identifier in the expression.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(name = "compile_expression", level = "debug", skip_all, fields(expr = %expr))
+)]
 pub fn compile(
     expr: &Expression,
     config: &CompilationConfig,
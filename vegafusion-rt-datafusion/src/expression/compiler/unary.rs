@@ -6,9 +6,11 @@
  * Please consult the license documentation provided alongside
  * this program the details of the active license.
  */
+use crate::expression::compiler::builtin_functions::math::bitwise::make_bitwise_not_udf;
 use crate::expression::compiler::utils::{to_boolean, to_numeric};
 use crate::expression::compiler::{compile, config::CompilationConfig};
 use datafusion::logical_plan::{DFSchema, Expr};
+use std::sync::Arc;
 use vegafusion_core::error::Result;
 use vegafusion_core::proto::gen::expression::{UnaryExpression, UnaryOperator};
 
@@ -26,6 +28,10 @@ pub fn compile_unary(
             // Cast to boolean if not already
             Expr::Not(Box::new(to_boolean(argument, schema)?))
         }
+        UnaryOperator::BitwiseNot => Expr::ScalarUDF {
+            fun: Arc::new(make_bitwise_not_udf()),
+            args: vec![to_numeric(argument, schema)?],
+        },
     };
     Ok(new_expr)
 }
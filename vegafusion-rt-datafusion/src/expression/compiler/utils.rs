@@ -6,10 +6,11 @@
  * Please consult the license documentation provided alongside
  * this program the details of the active license.
  */
+use crate::expression::compiler::builtin_functions::type_coercion::number_to_string::make_number_to_string_udf;
 use datafusion::arrow::array::{ArrayRef, BooleanArray};
 use datafusion::arrow::datatypes::{DataType, Schema};
 use datafusion::arrow::record_batch::RecordBatch;
-use datafusion::logical_plan::{and, Column, DFSchema, Expr, ExprSchemable};
+use datafusion::logical_plan::{and, lit, Column, DFSchema, Expr, ExprSchemable, Operator};
 use datafusion::physical_plan::planner::DefaultPhysicalPlanner;
 use datafusion::physical_plan::{ColumnarValue, PhysicalExpr, PhysicalPlanner};
 use datafusion::scalar::ScalarValue;
@@ -84,15 +85,35 @@ pub fn data_type(value: &Expr, schema: &DFSchema) -> Result<DataType> {
         .with_context(|| format!("Failed to infer datatype of expression: {:?}", value))
 }
 
-/// Cast an expression to boolean if not already boolean
+/// Cast an expression to boolean using JavaScript truthiness semantics:
+/// null, 0, NaN, and the empty string are all falsey
 pub fn to_boolean(value: Expr, schema: &DFSchema) -> Result<Expr> {
     let dtype = data_type(&value, schema)?;
     let boolean_value = if matches!(dtype, DataType::Boolean) {
         and(Expr::IsNotNull(Box::new(value.clone())), value)
+    } else if is_numeric_datatype(&dtype) {
+        // 0 and NaN are falsey. Comparing the value with itself filters out NaN
+        // because NaN != NaN
+        let non_zero = Expr::BinaryExpr {
+            left: Box::new(value.clone()),
+            op: Operator::NotEq,
+            right: Box::new(lit(0.0)),
+        };
+        let non_nan = Expr::BinaryExpr {
+            left: Box::new(value.clone()),
+            op: Operator::Eq,
+            right: Box::new(value.clone()),
+        };
+        and(and(Expr::IsNotNull(Box::new(value)), non_zero), non_nan)
+    } else if is_string_datatype(&dtype) {
+        // The empty string is falsey
+        let non_empty = Expr::BinaryExpr {
+            left: Box::new(value.clone()),
+            op: Operator::NotEq,
+            right: Box::new(lit("")),
+        };
+        and(Expr::IsNotNull(Box::new(value)), non_empty)
     } else {
-        // TODO: JavaScript falsey cast
-        //  - empty string to false
-        //  - NaN to false
         and(
             Expr::Cast {
                 expr: Box::new(value.clone()),
@@ -111,10 +132,22 @@ pub fn to_numeric(value: Expr, schema: &DFSchema) -> Result<Expr> {
     let numeric_value = if is_numeric_datatype(&dtype) {
         value
     } else if matches!(dtype, DataType::Timestamp(_, _)) {
-        // Convert to milliseconds
-        Expr::ScalarFunction {
-            fun: BuiltinScalarFunction::ToTimestampMillis,
-            args: vec![value],
+        // Convert to integer UTC milliseconds, matching JavaScript's date-to-number coercion
+        Expr::Cast {
+            expr: Box::new(Expr::ScalarFunction {
+                fun: BuiltinScalarFunction::ToTimestampMillis,
+                args: vec![value],
+            }),
+            data_type: DataType::Int64,
+        }
+    } else if matches!(dtype, DataType::Date32 | DataType::Date64) {
+        // Date64 casts to Int64 as UTC milliseconds, Date32 is first widened from days
+        Expr::Cast {
+            expr: Box::new(Expr::Cast {
+                expr: Box::new(value),
+                data_type: DataType::Date64,
+            }),
+            data_type: DataType::Int64,
         }
     } else {
         // Cast non-numeric types (like UTF-8) to Float64
@@ -132,6 +165,13 @@ pub fn to_string(value: Expr, schema: &DFSchema) -> Result<Expr> {
     let dtype = data_type(&value, schema)?;
     let utf8_value = if dtype == DataType::Utf8 || dtype == DataType::LargeUtf8 {
         value
+    } else if is_float_datatype(&dtype) {
+        // Format floats the way JavaScript does (e.g. "1" rather than "1.0") so that
+        // concatenated label strings match the client
+        Expr::ScalarUDF {
+            fun: Arc::new(make_number_to_string_udf()),
+            args: vec![value],
+        }
     } else {
         Expr::Cast {
             expr: Box::new(value),
@@ -11,6 +11,7 @@ extern crate lazy_static;
 
 pub mod data;
 pub mod expression;
+pub mod session;
 pub mod signal;
 pub mod task_graph;
 pub mod tokio_runtime;
@@ -0,0 +1,84 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use datafusion::execution::disk_manager::DiskManagerConfig;
+use datafusion::execution::memory_manager::MemoryManagerConfig;
+use datafusion::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
+use datafusion::prelude::{SessionConfig, SessionContext};
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use vegafusion_core::error::Result;
+
+/// Configuration of the DataFusion execution environment used to evaluate
+/// transforms. When a memory limit is set, memory intensive operators
+/// (aggregations, sorts, joins) spill to disk instead of exhausting process
+/// memory
+#[derive(Debug, Clone)]
+pub struct ExecutionConfig {
+    /// Maximum number of bytes of execution memory, or None for no limit
+    /// (the default)
+    pub memory_limit: Option<usize>,
+
+    /// Fraction of the memory limit that operators may occupy before spilling
+    pub memory_fraction: f64,
+
+    /// Directory used for spill files, or None to use the operating system's
+    /// temporary directory
+    pub spill_dir: Option<PathBuf>,
+}
+
+impl Default for ExecutionConfig {
+    fn default() -> Self {
+        Self {
+            memory_limit: None,
+            memory_fraction: 1.0,
+            spill_dir: None,
+        }
+    }
+}
+
+lazy_static! {
+    static ref EXECUTION_CONFIG: RwLock<ExecutionConfig> = RwLock::new(ExecutionConfig::default());
+}
+
+/// Replace the process-wide execution configuration
+pub fn set_execution_config(config: ExecutionConfig) {
+    let mut guard = EXECUTION_CONFIG.write().unwrap();
+    *guard = config;
+}
+
+/// Get a copy of the current process-wide execution configuration
+pub fn get_execution_config() -> ExecutionConfig {
+    EXECUTION_CONFIG.read().unwrap().clone()
+}
+
+/// Create a SessionContext that honors the process-wide execution configuration
+pub fn make_session_context() -> Result<SessionContext> {
+    let config = get_execution_config();
+    if config.memory_limit.is_none() && config.spill_dir.is_none() {
+        return Ok(SessionContext::new());
+    }
+
+    let mut runtime_config = RuntimeConfig::new();
+    if let Some(memory_limit) = config.memory_limit {
+        runtime_config = runtime_config.with_memory_manager(MemoryManagerConfig::try_new_limit(
+            memory_limit,
+            config.memory_fraction,
+        )?);
+    }
+    if let Some(spill_dir) = &config.spill_dir {
+        runtime_config = runtime_config
+            .with_disk_manager(DiskManagerConfig::new_specified(vec![spill_dir.clone()]));
+    }
+
+    let runtime = Arc::new(RuntimeEnv::new(runtime_config)?);
+    Ok(SessionContext::with_config_rt(
+        SessionConfig::new(),
+        runtime,
+    ))
+}
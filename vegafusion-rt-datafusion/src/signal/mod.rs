@@ -7,6 +7,8 @@
  * this program the details of the active license.
  */
 use crate::data::tasks::build_compilation_config;
+use crate::expression::compiler::builtin_functions::control_flow::logging::ExpressionWarningCollector;
+use crate::expression::compiler::builtin_functions::data::modify::eval_modify;
 use crate::expression::compiler::compile;
 use crate::expression::compiler::utils::ExprHelpers;
 use crate::task_graph::task::TaskCall;
@@ -27,12 +29,19 @@ impl TaskCall for SignalTask {
         values: &[TaskValue],
         tz_config: &Option<RuntimeTzConfig>,
         _inline_datasets: HashMap<String, VegaFusionDataset>,
+        expression_warnings: ExpressionWarningCollector,
     ) -> Result<(TaskValue, Vec<TaskValue>)> {
-        let config = build_compilation_config(&self.input_vars(), values, tz_config);
+        let config =
+            build_compilation_config(&self.input_vars(), values, tz_config, expression_warnings)?;
         let expression = self.expr.as_ref().unwrap();
         let expr = compile(expression, &config, None)?;
         let value = expr.eval_to_scalar()?;
         let task_value = TaskValue::Scalar(value);
-        Ok((task_value, Default::default()))
+
+        // Interpret modify() calls against their store datasets, producing the updated
+        // stores as output values
+        let output_values = eval_modify(expression, &config)?;
+
+        Ok((task_value, output_values))
     }
 }
@@ -6,23 +6,28 @@
  * Please consult the license documentation provided alongside
  * this program the details of the active license.
  */
-use async_lock::{Mutex, MutexGuard, RwLock};
+use async_lock::{Mutex, MutexGuard, RwLock, RwLockWriteGuard};
 use futures::FutureExt;
 use lru::LruCache;
+use prost::bytes::Buf;
+use prost::Message;
 
 use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::future::Future;
 use std::panic::{resume_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
 use vegafusion_core::error::{DuplicateResult, Result, ToExternalError, VegaFusionError};
+use vegafusion_core::proto::gen::tasks::TaskValue as ProtoTaskValue;
 use vegafusion_core::task_graph::task_value::TaskValue;
 
 #[derive(Debug, Clone)]
 struct CachedValue {
     value: NodeValue,
-    _calculation_millis: u128,
+    calculation_millis: u128,
 }
 
 impl CachedValue {
@@ -34,10 +39,44 @@ impl CachedValue {
 type NodeValue = (TaskValue, Vec<TaskValue>);
 type Initializer = Arc<RwLock<Option<Result<NodeValue>>>>;
 
+/// Snapshot of cache usage and effectiveness
+#[derive(Debug, Clone, Default)]
+pub struct CacheStats {
+    /// Number of values currently held in memory
+    pub entries: usize,
+
+    /// Estimated number of bytes of values currently held in memory
+    pub memory: usize,
+
+    /// Number of lookups served from the cache or from an in-flight computation
+    pub hits: usize,
+
+    /// Number of lookups that triggered a new computation
+    pub misses: usize,
+}
+
+impl CacheStats {
+    /// Fraction of lookups that were hits, or 0.0 before any lookups
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+/// Number of least-recently-used entries considered on each eviction. The cheapest
+/// entry (by calculation time) within the window is evicted, so values that are
+/// expensive to recompute survive longer than pure recency would allow
+const EVICTION_WINDOW: usize = 8;
+
 /// The VegaFusionCache uses a Segmented LRU (SLRU) cache policy
 /// (https://en.wikipedia.org/wiki/Cache_replacement_policies#Segmented_LRU_(SLRU)) where both the
 /// protected and probationary LRU caches are limited by capacity (number of entries) and memory
-/// limit.
+/// limit. Eviction is cost-aware: among the least-recently-used entries, the one that was
+/// cheapest to calculate is evicted first.
 #[derive(Debug, Clone)]
 pub struct VegaFusionCache {
     protected_cache: Arc<Mutex<LruCache<u64, CachedValue>>>,
@@ -47,8 +86,11 @@ pub struct VegaFusionCache {
     size: Arc<AtomicUsize>,
     protected_memory: Arc<AtomicUsize>,
     probationary_memory: Arc<AtomicUsize>,
+    hits: Arc<AtomicUsize>,
+    misses: Arc<AtomicUsize>,
     capacity: Option<usize>,
     memory_limit: Option<usize>,
+    disk_cache_dir: Option<PathBuf>,
 }
 
 impl VegaFusionCache {
@@ -63,6 +105,20 @@ impl VegaFusionCache {
             size: Arc::new(AtomicUsize::new(0)),
             protected_memory: Arc::new(AtomicUsize::new(0)),
             probationary_memory: Arc::new(AtomicUsize::new(0)),
+            hits: Arc::new(AtomicUsize::new(0)),
+            misses: Arc::new(AtomicUsize::new(0)),
+            disk_cache_dir: None,
+        }
+    }
+
+    /// Enable an on-disk cache in the provided directory. Values are written as
+    /// length-delimited protobuf task values keyed by state fingerprint, and
+    /// survive process restarts. Disk read/write failures are treated as cache
+    /// misses rather than errors
+    pub fn with_disk_cache(self, cache_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            disk_cache_dir: Some(cache_dir.into()),
+            ..self
         }
     }
 
@@ -98,6 +154,93 @@ impl VegaFusionCache {
         self.protected_memory.store(0, Ordering::Relaxed);
         self.probationary_memory.store(0, Ordering::Relaxed);
         self.size.store(0, Ordering::Relaxed);
+
+        // Clear persisted values from the disk cache (if any)
+        if let Some(cache_dir) = &self.disk_cache_dir {
+            if let Ok(entries) = std::fs::read_dir(cache_dir) {
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    if path.extension().map(|ext| ext == "bin").unwrap_or(false) {
+                        let _ = std::fs::remove_file(path);
+                    }
+                }
+            }
+        }
+    }
+
+    fn disk_cache_path(&self, state_fingerprint: u64) -> Option<PathBuf> {
+        self.disk_cache_dir
+            .as_ref()
+            .map(|cache_dir| cache_dir.join(format!("{}.bin", state_fingerprint)))
+    }
+
+    /// Look up a value in the disk cache, treating unreadable or corrupt
+    /// files as misses
+    fn get_from_disk(&self, state_fingerprint: u64) -> Option<NodeValue> {
+        let path = self.disk_cache_path(state_fingerprint)?;
+        match read_node_value(&path) {
+            Ok(value) => Some(value),
+            Err(_) => {
+                // Remove corrupt entry so it isn't retried on every miss
+                let _ = std::fs::remove_file(path);
+                None
+            }
+        }
+    }
+
+    /// Persist a value to the disk cache, ignoring write failures
+    fn write_to_disk(&self, state_fingerprint: u64, value: &NodeValue) {
+        if let Some(path) = self.disk_cache_path(state_fingerprint) {
+            let _ = write_node_value(&path, value);
+        }
+    }
+
+    /// The value for the given fingerprint, if it is currently held in memory.
+    /// Does not consult the disk cache and does not count as a hit or miss
+    pub async fn get_value(
+        &self,
+        state_fingerprint: u64,
+    ) -> Option<(TaskValue, Vec<TaskValue>)> {
+        self.get(state_fingerprint).await.map(|cached| cached.value)
+    }
+
+    /// Whether a value for the given fingerprint is currently held in memory.
+    /// Does not update entry recency and does not consult the disk cache
+    pub async fn value_cached(&self, state_fingerprint: u64) -> bool {
+        let protected = self.protected_cache.lock().await;
+        let probationary = self.probationary_cache.lock().await;
+        protected.contains(&state_fingerprint) || probationary.contains(&state_fingerprint)
+    }
+
+    /// Snapshot of current cache usage and hit rate. Hit and miss counts
+    /// accumulate over the lifetime of the cache and are not reset by clear
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.size(),
+            memory: self.total_memory(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Remove the value for the given fingerprint from the memory and disk caches
+    pub async fn remove(&self, state_fingerprint: u64) {
+        let mut protected = self.protected_cache.lock().await;
+        let mut probationary = self.probationary_cache.lock().await;
+        if let Some(value) = protected.pop(&state_fingerprint) {
+            self.protected_memory
+                .fetch_sub(value.size_of(), Ordering::Relaxed);
+        }
+        if let Some(value) = probationary.pop(&state_fingerprint) {
+            self.probationary_memory
+                .fetch_sub(value.size_of(), Ordering::Relaxed);
+        }
+        self.size
+            .store(protected.len() + probationary.len(), Ordering::Relaxed);
+
+        if let Some(path) = self.disk_cache_path(state_fingerprint) {
+            let _ = std::fs::remove_file(path);
+        }
     }
 
     async fn get(&self, state_fingerprint: u64) -> Option<CachedValue> {
@@ -126,13 +269,33 @@ impl VegaFusionCache {
         }
     }
 
+    /// Pick the eviction victim from a cache: the entry with the smallest
+    /// calculation time among the EVICTION_WINDOW least-recently-used entries.
+    /// Ties go to the least recently used entry
+    fn pop_eviction_victim(
+        cache: &mut MutexGuard<LruCache<u64, CachedValue>>,
+    ) -> (u64, CachedValue) {
+        // iter() yields entries from most to least recently used
+        let window_start = cache.len().saturating_sub(EVICTION_WINDOW);
+        let mut victim: Option<(u64, u128)> = None;
+        for (key, value) in cache.iter().skip(window_start) {
+            match &victim {
+                Some((_, cost)) if value.calculation_millis > *cost => {}
+                _ => victim = Some((*key, value.calculation_millis)),
+            }
+        }
+        let (key, _) = victim.unwrap();
+        let value = cache.pop(&key).unwrap();
+        (key, value)
+    }
+
     fn pop_protected_lru(
         &self,
         protected: &mut MutexGuard<LruCache<u64, CachedValue>>,
         probationary: &mut MutexGuard<LruCache<u64, CachedValue>>,
     ) {
-        // Remove one protected LRU entry
-        let (key, popped_value) = protected.pop_lru().unwrap();
+        // Demote the cheapest entry among the least-recently-used protected entries
+        let (key, popped_value) = Self::pop_eviction_victim(protected);
         let popped_memory = popped_value.size_of();
 
         // Decrement protected memory
@@ -148,10 +311,10 @@ impl VegaFusionCache {
     }
 
     fn pop_probationary_lru(&self, probationary: &mut MutexGuard<LruCache<u64, CachedValue>>) {
-        let (_, popped_value) = probationary.pop_lru().unwrap();
+        let (_, popped_value) = Self::pop_eviction_victim(probationary);
         let popped_memory = popped_value.size_of();
 
-        // Decrement protected memory
+        // Decrement probationary memory
         self.probationary_memory
             .fetch_sub(popped_memory, Ordering::Relaxed);
     }
@@ -227,25 +390,29 @@ impl VegaFusionCache {
     async fn set_value(&self, state_fingerprint: u64, value: NodeValue, calculation_millis: u128) {
         let cache_value = CachedValue {
             value,
-            _calculation_millis: calculation_millis,
+            calculation_millis,
         };
         let value_memory = cache_value.size_of();
 
         let mut protected = self.protected_cache.lock().await;
         let mut probationary = self.probationary_cache.lock().await;
         if protected.contains(&state_fingerprint) {
-            // Set on protected to update usage
-            protected.put(state_fingerprint, cache_value);
+            // Replace on protected to update usage, accounting for the size
+            // difference between the old and new values
+            let old_value = protected.put(state_fingerprint, cache_value).unwrap();
+            self.protected_memory
+                .fetch_sub(old_value.size_of(), Ordering::Relaxed);
+            self.protected_memory
+                .fetch_add(value_memory, Ordering::Relaxed);
+            self.balance(&mut protected, &mut probationary);
         } else if probationary.contains(&state_fingerprint) {
-            // Promote from probationary to protected
-            protected.put(
-                state_fingerprint,
-                probationary.pop(&state_fingerprint).unwrap(),
-            );
+            // Promote from probationary to protected, replacing the old value
+            let old_value = probationary.pop(&state_fingerprint).unwrap();
+            protected.put(state_fingerprint, cache_value);
             self.protected_memory
                 .fetch_add(value_memory, Ordering::Relaxed);
             self.probationary_memory
-                .fetch_sub(value_memory, Ordering::Relaxed);
+                .fetch_sub(old_value.size_of(), Ordering::Relaxed);
             self.balance(&mut protected, &mut probationary);
         } else {
             // Add to probationary and update memory usage
@@ -270,22 +437,29 @@ impl VegaFusionCache {
     {
         // Check if present in the values cache
         if let Some(value) = self.get(state_fingerprint).await {
+            self.hits.fetch_add(1, Ordering::Relaxed);
             return Ok(value.value);
         }
 
-        // Check if present in initializers
-        // let mut initializers_lock = self.initializers.write().await;
-        let initializer = {
-            self.initializers
-                .write()
-                .await
-                .get(&state_fingerprint)
-                .cloned()
-        };
+        // Check the disk cache (if any), loading hits back into the memory cache
+        if let Some(value) = self.get_from_disk(state_fingerprint) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            self.set_value(state_fingerprint, value.clone(), 0).await;
+            return Ok(value);
+        }
+
+        // Atomically look up or create the initializer for this fingerprint. The
+        // initializer is created, and its write lock acquired, while the initializers
+        // map is locked, so concurrent requests for the same fingerprint always share
+        // a single computation rather than racing to spawn duplicates
+        let mut initializers_lock = self.initializers.write().await;
+        if let Some(initializer) = initializers_lock.get(&state_fingerprint).cloned() {
+            drop(initializers_lock);
+
+            // Awaiting an in-flight computation counts as a hit: no new work is done
+            self.hits.fetch_add(1, Ordering::Relaxed);
 
-        if let Some(initializer) = initializer {
             // Calculation is in progress, await on Arc clone of it's initializer
-            // Drop lock on initializers collection
             let result = initializer.read().await;
             let result = match result.as_ref() {
                 None => self.spawn_initializer(state_fingerprint, init).await,
@@ -293,7 +467,15 @@ impl VegaFusionCache {
             };
             result
         } else {
-            self.spawn_initializer(state_fingerprint, init).await
+            self.misses.fetch_add(1, Ordering::Relaxed);
+
+            let initializer: Initializer = Arc::new(RwLock::new(None));
+            let initializer_lock = initializer.write().await;
+            initializers_lock.insert(state_fingerprint, initializer.clone());
+            drop(initializers_lock);
+
+            self.run_initializer(state_fingerprint, initializer_lock, init)
+                .await
         }
     }
 
@@ -305,7 +487,7 @@ impl VegaFusionCache {
         let initializer: Initializer = Arc::new(RwLock::new(None));
 
         // Get and hold write lock for initializer
-        let mut initializer_lock = initializer.write().await;
+        let initializer_lock = initializer.write().await;
 
         // Store Arc clone of initializer in initializers map
         self.initializers
@@ -313,6 +495,19 @@ impl VegaFusionCache {
             .await
             .insert(state_fingerprint, initializer.clone());
 
+        self.run_initializer(state_fingerprint, initializer_lock, init)
+            .await
+    }
+
+    async fn run_initializer<F>(
+        &self,
+        state_fingerprint: u64,
+        mut initializer_lock: RwLockWriteGuard<'_, Option<Result<NodeValue>>>,
+        init: F,
+    ) -> Result<NodeValue>
+    where
+        F: Future<Output = Result<NodeValue>> + Send + 'static,
+    {
         // Record start time
         let start = Instant::now();
 
@@ -331,6 +526,9 @@ impl VegaFusionCache {
                         self.set_value(state_fingerprint, value.clone(), millis)
                             .await;
 
+                        // Persist to the disk cache (if any)
+                        self.write_to_disk(state_fingerprint, &value);
+
                         // Stored initializer no longer required. Initializers are Arc
                         // pointers, so it's fine to drop initializer from here even if
                         // other tasks are still awaiting on it.
@@ -363,6 +561,48 @@ impl VegaFusionCache {
     }
 }
 
+/// Write a node value to a disk cache file as length-delimited protobuf task
+/// values: first the node's own value, then each of its output values. The file
+/// is written to a temp path and renamed so readers never see partial contents
+fn write_node_value(path: &Path, value: &NodeValue) -> Result<()> {
+    let mut buf: Vec<u8> = Vec::new();
+    ProtoTaskValue::try_from(&value.0)?
+        .encode_length_delimited(&mut buf)
+        .external("Failed to encode task value")?;
+    for output in &value.1 {
+        ProtoTaskValue::try_from(output)?
+            .encode_length_delimited(&mut buf)
+            .external("Failed to encode task value")?;
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).external("Failed to create disk cache directory")?;
+    }
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, buf).external("Failed to write disk cache file")?;
+    std::fs::rename(&tmp_path, path).external("Failed to rename disk cache file")?;
+    Ok(())
+}
+
+/// Read a node value from a disk cache file written by write_node_value
+fn read_node_value(path: &Path) -> Result<NodeValue> {
+    let buf = std::fs::read(path).external("Failed to read disk cache file")?;
+    let mut cursor = buf.as_slice();
+
+    let proto_value = ProtoTaskValue::decode_length_delimited(&mut cursor)
+        .external("Failed to decode task value")?;
+    let value = TaskValue::try_from(&proto_value)?;
+
+    let mut outputs: Vec<TaskValue> = Vec::new();
+    while cursor.has_remaining() {
+        let proto_output = ProtoTaskValue::decode_length_delimited(&mut cursor)
+            .external("Failed to decode task value")?;
+        outputs.push(TaskValue::try_from(&proto_output)?);
+    }
+
+    Ok((value, outputs))
+}
+
 #[cfg(test)]
 mod test_cache {
     use crate::task_graph::cache::{NodeValue, VegaFusionCache};
@@ -11,60 +11,120 @@ use std::collections::HashMap;
 use vegafusion_core::error::{Result, ResultWithContext, ToExternalError, VegaFusionError};
 use vegafusion_core::task_graph::task_value::TaskValue;
 
-use crate::task_graph::cache::VegaFusionCache;
+use crate::data::http::{get_http_config, set_http_config, HttpConfig};
+use crate::expression::compiler::builtin_functions::control_flow::logging::ExpressionWarningCollector;
+use crate::expression::compiler::builtin_functions::date_time::label_formats::{
+    set_time_locale, TimeLocale,
+};
+use crate::task_graph::cache::{CacheStats, VegaFusionCache};
 use crate::task_graph::task::TaskCall;
 use crate::task_graph::timezone::RuntimeTzConfig;
+use crate::tokio_runtime::set_tokio_worker_threads;
 use futures_util::{future, FutureExt};
 use prost::Message as ProstMessage;
 use serde_json::Value;
+use lru::LruCache;
 use std::convert::{TryFrom, TryInto};
 use std::panic::AssertUnwindSafe;
-use std::sync::Arc;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use vegafusion_core::data::dataset::VegaFusionDataset;
+use vegafusion_core::data::table::VegaFusionTable;
+use vegafusion_core::planning::base_url::apply_base_url;
+use vegafusion_core::planning::initial_values::apply_initial_values;
 use vegafusion_core::planning::plan::{PlannerConfig, SpecPlan};
 use vegafusion_core::planning::watch::{ExportUpdate, ExportUpdateNamespace};
-use vegafusion_core::proto::gen::errors::error::Errorkind;
-use vegafusion_core::proto::gen::errors::{Error, TaskGraphValueError};
 use vegafusion_core::proto::gen::pretransform::pre_transform_spec_warning::WarningType;
+use vegafusion_core::proto::gen::pretransform::pre_transform_datasets_warning::WarningType as DatasetsWarningType;
+use vegafusion_core::proto::gen::pretransform::pre_transform_extract_warning::WarningType as ExtractWarningType;
 use vegafusion_core::proto::gen::pretransform::pre_transform_values_warning::WarningType as ValuesWarningType;
 use vegafusion_core::proto::gen::pretransform::{
-    PlannerWarning, PreTransformSpecWarning, PreTransformValuesRequest, PreTransformValuesResponse,
-    PreTransformValuesWarning,
+    PlannerWarning, PlannerWarningType, PreTransformDatasetsRequest, PreTransformDatasetsResponse,
+    PreTransformDatasetsWarning, PreTransformExtractDataset, PreTransformExtractRequest,
+    PreTransformExtractResponse, PreTransformExtractWarning, PreTransformInitialValue,
+    PreTransformScopedName, PreTransformSpecWarning, PreTransformValuesRequest,
+    PreTransformValuesResponse, PreTransformValuesWarning,
+};
+use vegafusion_core::proto::gen::errors::{
+    error::Errorkind, Error as ProtoError, TaskGraphValueError,
 };
 use vegafusion_core::proto::gen::pretransform::{
-    PreTransformBrokenInteractivityWarning, PreTransformRowLimitWarning, PreTransformSpecRequest,
+    PreTransformBrokenInteractivityWarning, PreTransformRowLimitDataset,
+    PreTransformRowLimitWarning, PreTransformSpecBatchRequest, PreTransformSpecRequest,
     PreTransformSpecResponse, PreTransformUnsupportedWarning,
 };
 use vegafusion_core::proto::gen::services::{
-    pre_transform_spec_result, pre_transform_values_result, query_request, query_result,
-    PreTransformSpecResult, PreTransformValuesResult, QueryRequest, QueryResult,
+    pre_transform_datasets_result, pre_transform_extract_result, pre_transform_spec_result,
+    pre_transform_values_result, query_request, query_result, PreTransformDatasetsResult,
+    PreTransformExtractResult, PreTransformSpecBatchResult, PreTransformSpecResult,
+    PreTransformValuesResult, QueryRequest, QueryResult,
 };
+use std::time::Instant;
 use vegafusion_core::proto::gen::tasks::{
-    task::TaskKind, NodeValueIndex, ResponseTaskValue, TaskGraph, TaskGraphValueResponse,
-    TaskValue as ProtoTaskValue, TzConfig, Variable, VariableNamespace,
+    task::TaskKind, task_graph_value_request, ChunkMetadata, DataCompression, NodeError,
+    NodeMetrics, NodeValueIndex, ResponseTaskValue, TaskGraph, TaskGraphValueRequest,
+    TaskGraphValueResponse, TaskGraphValueUpdate, TaskValue as ProtoTaskValue, TzConfig, Variable,
+    VariableNamespace,
 };
+use crate::data::table::VegaFusionTableUtils;
+use crate::expression::compiler::config::CompilationConfig;
+use crate::transform::aggregate::eval_incremental;
+use crate::transform::pipeline::incremental_aggregate;
 use vegafusion_core::spec::chart::ChartSpec;
-use vegafusion_core::task_graph::graph::ScopedVariable;
+use vegafusion_core::task_graph::graph::{ScopedVariable, ValueUpdate};
 
 type CacheValue = (TaskValue, Vec<TaskValue>);
 
+/// Number of registered task graphs to retain
+const REGISTERED_GRAPH_CAPACITY: usize = 32;
+
+/// Default row count above which pre_transform_extract returns datasets as
+/// separate Arrow payloads rather than inlining them into the spec
+const DEFAULT_EXTRACT_THRESHOLD: u32 = 20;
+
 #[derive(Clone)]
 pub struct TaskGraphRuntime {
     pub cache: VegaFusionCache,
+
+    /// Default input timezone applied when pre-transform requests don't specify one
+    pub default_input_tz: Option<String>,
+
+    /// Task graphs registered by id so clients can reference them without
+    /// resending the full graph on every request
+    registered_graphs: Arc<Mutex<LruCache<u64, TaskGraph>>>,
 }
 
 impl TaskGraphRuntime {
     pub fn new(capacity: Option<usize>, memory_limit: Option<usize>) -> Self {
         Self {
             cache: VegaFusionCache::new(capacity, memory_limit),
+            default_input_tz: None,
+            registered_graphs: Arc::new(Mutex::new(LruCache::new(REGISTERED_GRAPH_CAPACITY))),
         }
     }
 
+    /// Create a builder that collects runtime configuration in one place
+    pub fn builder() -> TaskGraphRuntimeBuilder {
+        TaskGraphRuntimeBuilder::default()
+    }
+
+    /// Register a custom expression function backed by a DataFusion scalar UDF. The
+    /// UDF's name is used as the expression function name, and the name is registered
+    /// with the planner so that expressions using the function are planned for
+    /// server-side evaluation.
+    ///
+    /// Functions should be registered before specs are planned and task graphs are
+    /// built. The registry is shared process-wide across runtime instances.
+    pub fn register_function(&self, udf: datafusion::physical_plan::udf::ScalarUDF) {
+        crate::expression::compiler::call::register_udf(udf);
+    }
+
     pub async fn get_node_value(
         &self,
         task_graph: Arc<TaskGraph>,
         node_value_index: &NodeValueIndex,
         inline_datasets: HashMap<String, VegaFusionDataset>,
+        expression_warnings: ExpressionWarningCollector,
     ) -> Result<TaskValue> {
         // We shouldn't panic inside get_or_compute_node_value, but since this may be used
         // in a server context, wrap in catch_unwind just in case.
@@ -73,6 +133,7 @@ impl TaskGraphRuntime {
             node_value_index.node_index as usize,
             self.cache.clone(),
             inline_datasets,
+            expression_warnings,
         ))
         .catch_unwind()
         .await;
@@ -87,76 +148,389 @@ impl TaskGraphRuntime {
         })
     }
 
+    /// Build a future that computes the response values and execution metrics for
+    /// a single requested index. Table values larger than chunk_size are split
+    /// into multiple response values with chunk metadata; otherwise a single
+    /// response value is produced. Evaluation failures are captured as a
+    /// structured NodeError rather than a future error, so that one failing node
+    /// doesn't discard the values of the nodes that succeeded
+    #[allow(clippy::type_complexity)]
+    fn response_value_future(
+        &self,
+        task_graph: &Arc<TaskGraph>,
+        node_value_index: NodeValueIndex,
+        compression: DataCompression,
+        chunk_size: u64,
+    ) -> Result<
+        impl std::future::Future<
+            Output = (Vec<ResponseTaskValue>, Option<NodeMetrics>, Option<NodeError>),
+        >,
+    > {
+        let node = task_graph
+            .nodes
+            .get(node_value_index.node_index as usize)
+            .with_context(|| {
+                format!(
+                    "Node index {} out of bounds for graph with size {}",
+                    node_value_index.node_index,
+                    task_graph.nodes.len()
+                )
+            })?;
+        let task = node.task();
+        let var = match node_value_index.output_index {
+            None => task.variable().clone(),
+            Some(output_index) => task.output_vars()[output_index as usize].clone(),
+        };
+
+        let scope = node.task().scope.clone();
+
+        // Value tasks are read straight from the graph, so they never hit the cache
+        let is_value_task = matches!(task.task_kind(), TaskKind::Value(_));
+        let state_fingerprint = node.state_fingerprint;
+
+        // Clone task_graph and task_graph_runtime for use in closure
+        let task_graph_runtime = self.clone();
+        let task_graph = task_graph.clone();
+
+        Ok(async move {
+            let result: Result<(Vec<ResponseTaskValue>, NodeMetrics)> = async {
+                let cache_hit = !is_value_task
+                    && task_graph_runtime
+                        .cache
+                        .value_cached(state_fingerprint)
+                        .await;
+
+                let start = Instant::now();
+                let value = task_graph_runtime
+                    .clone()
+                    .get_node_value(
+                        task_graph,
+                        &node_value_index,
+                        Default::default(),
+                        Default::default(),
+                    )
+                    .await?;
+                let duration_ms = start.elapsed().as_secs_f64() * 1e3;
+
+                let num_rows = match &value {
+                    TaskValue::Table(table) => Some(table.num_rows() as u64),
+                    _ => None,
+                };
+
+                let metrics = NodeMetrics {
+                    variable: Some(var.clone()),
+                    scope: scope.clone(),
+                    duration_ms,
+                    num_rows,
+                    cache_hit,
+                };
+
+                let response_values = match &value {
+                    TaskValue::Table(table)
+                        if chunk_size > 0 && value.size_of() as u64 > chunk_size =>
+                    {
+                        let chunks = chunk_table(table, chunk_size as usize)?;
+                        let num_chunks = chunks.len() as u32;
+                        chunks
+                            .into_iter()
+                            .enumerate()
+                            .map(|(chunk_index, chunk)| {
+                                Ok(ResponseTaskValue {
+                                    variable: Some(var.clone()),
+                                    scope: scope.clone(),
+                                    value: Some(
+                                        TaskValue::Table(chunk).to_compressed_proto(compression)?,
+                                    ),
+                                    chunk: Some(ChunkMetadata {
+                                        chunk_index: chunk_index as u32,
+                                        num_chunks,
+                                    }),
+                                })
+                            })
+                            .collect::<Result<Vec<_>>>()?
+                    }
+                    _ => vec![ResponseTaskValue {
+                        variable: Some(var.clone()),
+                        scope: scope.clone(),
+                        value: Some(value.to_compressed_proto(compression)?),
+                        chunk: None,
+                    }],
+                };
+
+                Ok((response_values, metrics))
+            }
+            .await;
+
+            match result {
+                Ok((response_values, metrics)) => (response_values, Some(metrics), None),
+                Err(e) => (
+                    Vec::new(),
+                    None,
+                    Some(NodeError {
+                        variable: Some(var),
+                        scope,
+                        msg: e.to_string(),
+                    }),
+                ),
+            }
+        })
+    }
+
+    /// Register a task graph so later requests can reference it by id instead of
+    /// resending it. Returns the graph's registration id. Registration is
+    /// idempotent since the id is a deterministic hash of the graph contents
+    pub fn register_task_graph(&self, task_graph: TaskGraph) -> u64 {
+        let graph_id = task_graph.registration_id();
+        self.registered_graphs
+            .lock()
+            .unwrap()
+            .put(graph_id, task_graph);
+        graph_id
+    }
+
+    /// Remove a registered task graph
+    pub fn unregister_task_graph(&self, graph_id: u64) {
+        self.registered_graphs.lock().unwrap().pop(&graph_id);
+    }
+
+    /// Resolve the task graph referenced by a value request and apply any value
+    /// updates it carries. Full graphs are registered for reuse. Updates against a
+    /// registered graph are applied to the registered copy, so each request only
+    /// needs to carry the updates since the previous one
+    async fn resolve_task_graph(&self, request: &TaskGraphValueRequest) -> Result<Arc<TaskGraph>> {
+        let (task_graph, old_fingerprints, deltas) = match &request.graph {
+            Some(task_graph_value_request::Graph::TaskGraph(task_graph)) => {
+                // Register under the id of the graph as sent, which is the id the
+                // client computes locally, then apply updates
+                let graph_id = task_graph.registration_id();
+                let mut task_graph = task_graph.clone();
+                let old_fingerprints = node_fingerprints(&task_graph);
+                let deltas = apply_graph_updates(&mut task_graph, &request.updates)?;
+                self.registered_graphs
+                    .lock()
+                    .unwrap()
+                    .put(graph_id, task_graph.clone());
+                (Arc::new(task_graph), old_fingerprints, deltas)
+            }
+            Some(task_graph_value_request::Graph::RegisteredGraphId(graph_id)) => {
+                let mut registered_graphs = self.registered_graphs.lock().unwrap();
+                let task_graph = registered_graphs.get_mut(graph_id).with_context(|| {
+                    format!(
+                        "No task graph registered with id {}. Resend the full task graph",
+                        graph_id
+                    )
+                })?;
+                let old_fingerprints = node_fingerprints(task_graph);
+                let deltas = apply_graph_updates(task_graph, &request.updates)?;
+                (Arc::new(task_graph.clone()), old_fingerprints, deltas)
+            }
+            None => {
+                return Err(VegaFusionError::internal(
+                    "TaskGraphValueRequest must include a task graph or a registered graph id",
+                ))
+            }
+        };
+
+        self.apply_incremental_updates(&task_graph, &old_fingerprints, &deltas)
+            .await?;
+        Ok(task_graph)
+    }
+
+    /// Incrementally recompute the aggregations downstream of appended-row value
+    /// updates, seeding the cache under the nodes' new state fingerprints so the
+    /// subsequent evaluation is a cache hit rather than a full recomputation of
+    /// the aggregate. Nodes whose previous result is no longer cached, and
+    /// pipelines that don't support incremental recomputation, are skipped and
+    /// fall back to a full recompute through the normal cache-miss path
+    async fn apply_incremental_updates(
+        &self,
+        task_graph: &Arc<TaskGraph>,
+        old_fingerprints: &[u64],
+        deltas: &[(usize, ValueUpdate)],
+    ) -> Result<()> {
+        for (node_index, delta) in deltas {
+            let delta_table = match delta {
+                ValueUpdate::Append(delta_table) => delta_table,
+                ValueUpdate::Replace => continue,
+            };
+
+            for child_index in task_graph.child_indices(*node_index)? {
+                let child_node = task_graph.node(child_index)?;
+                let agg = match child_node.task().task_kind() {
+                    TaskKind::DataSource(task) => {
+                        match task.pipeline.as_ref().and_then(incremental_aggregate) {
+                            Some(agg) => agg,
+                            None => continue,
+                        }
+                    }
+                    _ => continue,
+                };
+
+                // The updated value must be the aggregate's only input for the
+                // merge with the previous result to be valid
+                if task_graph.parent_indices(child_index)? != vec![*node_index] {
+                    continue;
+                }
+
+                let new_fingerprint = child_node.state_fingerprint;
+                let old_fingerprint = old_fingerprints[child_index];
+                if new_fingerprint == old_fingerprint {
+                    continue;
+                }
+
+                // The previous result must still be cached
+                let prev_result = match self.cache.get_value(old_fingerprint).await {
+                    Some((TaskValue::Table(prev_table), _)) => prev_table,
+                    _ => continue,
+                };
+
+                let agg = agg.clone();
+                let delta_df = delta_table.to_dataframe()?;
+                let fut = async move {
+                    let config = CompilationConfig::default();
+                    let result_df = eval_incremental(&agg, &prev_result, delta_df, &config).await?;
+                    let table = VegaFusionTable::from_dataframe(result_df).await?;
+                    Ok((TaskValue::Table(table), Vec::new()))
+                };
+                self.cache
+                    .get_or_try_insert_with(new_fingerprint, fut)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
     pub async fn query_request(&self, request: QueryRequest) -> Result<QueryResult> {
         match request.request {
             Some(query_request::Request::TaskGraphValues(task_graph_values)) => {
-                let task_graph = Arc::new(task_graph_values.task_graph.unwrap());
-
-                // Clone task_graph and task_graph_runtime for use in closure
-                let task_graph_runtime = self.clone();
-                let task_graph = task_graph.clone();
+                let task_graph = self.resolve_task_graph(&task_graph_values).await?;
+                let compression = choose_compression(&task_graph_values.accept_compression);
+                let chunk_size = task_graph_values.chunk_size;
 
                 let response_value_futures: Vec<_> = task_graph_values
                     .indices
                     .iter()
                     .map(|node_value_index| {
-                        let node = task_graph
-                            .nodes
-                            .get(node_value_index.node_index as usize)
-                            .with_context(|| {
-                                format!(
-                                    "Node index {} out of bounds for graph with size {}",
-                                    node_value_index.node_index,
-                                    task_graph.nodes.len()
-                                )
-                            })?;
-                        let task = node.task();
-                        let var = match node_value_index.output_index {
-                            None => task.variable().clone(),
-                            Some(output_index) => task.output_vars()[output_index as usize].clone(),
-                        };
-
-                        let scope = node.task().scope.clone();
-
-                        // Clone task_graph and task_graph_runtime for use in closure
-                        let task_graph_runtime = task_graph_runtime.clone();
-                        let task_graph = task_graph.clone();
-
-                        Ok(async move {
-                            let value = task_graph_runtime
-                                .clone()
-                                .get_node_value(task_graph, node_value_index, Default::default())
-                                .await?;
-
-                            Ok::<_, VegaFusionError>(ResponseTaskValue {
-                                variable: Some(var),
-                                scope,
-                                value: Some(ProtoTaskValue::try_from(&value).unwrap()),
-                            })
-                        })
+                        self.response_value_future(
+                            &task_graph,
+                            node_value_index.clone(),
+                            compression,
+                            chunk_size,
+                        )
                     })
                     .collect::<Result<Vec<_>>>()?;
 
-                match future::try_join_all(response_value_futures).await {
-                    Ok(response_values) => {
-                        let response_msg = QueryResult {
-                            response: Some(query_result::Response::TaskGraphValues(
-                                TaskGraphValueResponse { response_values },
-                            )),
-                        };
-                        Ok(response_msg)
-                    }
-                    Err(e) => {
-                        let response_msg = QueryResult {
-                            response: Some(query_result::Response::Error(Error {
-                                errorkind: Some(Errorkind::Error(TaskGraphValueError {
-                                    msg: e.to_string(),
-                                })),
-                            })),
-                        };
-                        Ok(response_msg)
-                    }
+                let mut response_values: Vec<ResponseTaskValue> = Vec::new();
+                let mut metrics: Vec<NodeMetrics> = Vec::new();
+                let mut node_errors: Vec<NodeError> = Vec::new();
+                for (values, node_metrics, node_error) in
+                    future::join_all(response_value_futures).await
+                {
+                    response_values.extend(values);
+                    metrics.extend(node_metrics);
+                    node_errors.extend(node_error);
                 }
+
+                let response_msg = QueryResult {
+                    response: Some(query_result::Response::TaskGraphValues(
+                        TaskGraphValueResponse {
+                            response_values,
+                            metrics,
+                            node_errors,
+                        },
+                    )),
+                };
+                Ok(response_msg)
+            }
+            _ => Err(VegaFusionError::internal(
+                "Invalid VegaFusionRuntimeRequest request",
+            )),
+        }
+    }
+
+    /// Evaluate several query requests (e.g. the task graphs of all charts on a
+    /// dashboard page) concurrently in a single call. Nodes with the same state
+    /// fingerprint are only computed once across the batch: concurrent requests
+    /// share in-flight computations and the cache is shared, so datasets used by
+    /// multiple charts don't multiply the work. Results are returned in request
+    /// order
+    pub async fn query_request_batch(
+        &self,
+        requests: Vec<QueryRequest>,
+    ) -> Result<Vec<QueryResult>> {
+        let futures: Vec<_> = requests
+            .into_iter()
+            .map(|request| self.query_request(request))
+            .collect();
+        future::try_join_all(futures).await
+    }
+
+    /// Like query_request, but sends a QueryResult for each requested index as soon
+    /// as its value is computed, rather than waiting for the slowest index. Each
+    /// result carries a single response value (and its metrics), in completion
+    /// order; clients match results to requests using the variable and scope. A
+    /// failed index produces a result carrying its per-node error without
+    /// cancelling the others
+    pub async fn query_request_stream(
+        &self,
+        request: QueryRequest,
+    ) -> Result<tokio::sync::mpsc::Receiver<QueryResult>> {
+        match request.request {
+            Some(query_request::Request::TaskGraphValues(task_graph_values)) => {
+                let task_graph = self.resolve_task_graph(&task_graph_values).await?;
+                let compression = choose_compression(&task_graph_values.accept_compression);
+                let chunk_size = task_graph_values.chunk_size;
+                let (sender, receiver) =
+                    tokio::sync::mpsc::channel(task_graph_values.indices.len().max(1));
+
+                for node_value_index in &task_graph_values.indices {
+                    let fut = self.response_value_future(
+                        &task_graph,
+                        node_value_index.clone(),
+                        compression,
+                        chunk_size,
+                    )?;
+                    let sender = sender.clone();
+                    tokio::spawn(async move {
+                        let (response_values, mut metrics, node_error) = fut.await;
+
+                        if let Some(node_error) = node_error {
+                            let result = QueryResult {
+                                response: Some(query_result::Response::TaskGraphValues(
+                                    TaskGraphValueResponse {
+                                        response_values: Vec::new(),
+                                        metrics: Vec::new(),
+                                        node_errors: vec![node_error],
+                                    },
+                                )),
+                            };
+                            let _ = sender.send(result).await;
+                            return;
+                        }
+
+                        // Send each chunk as its own result so individual
+                        // messages stay below the transport's size limits.
+                        // Metrics accompany the first chunk only
+                        for response_value in response_values {
+                            let result = QueryResult {
+                                response: Some(query_result::Response::TaskGraphValues(
+                                    TaskGraphValueResponse {
+                                        response_values: vec![response_value],
+                                        metrics: metrics.take().into_iter().collect(),
+                                        node_errors: Vec::new(),
+                                    },
+                                )),
+                            };
+                            // Receiver may have been dropped if the client
+                            // disconnected
+                            if sender.send(result).await.is_err() {
+                                break;
+                            }
+                        }
+                    });
+                }
+
+                Ok(receiver)
             }
             _ => Err(VegaFusionError::internal(
                 "Invalid VegaFusionRuntimeRequest request",
@@ -186,6 +560,39 @@ impl TaskGraphRuntime {
         // Get row limit
         let row_limit = request.opts.as_ref().and_then(|opts| opts.row_limit);
 
+        // Get per-dataset row limits
+        let dataset_row_limits: HashMap<ScopedVariable, u32> = request
+            .opts
+            .as_ref()
+            .map(|opts| opts.dataset_row_limits.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(|limit| {
+                let variable = limit
+                    .variable
+                    .clone()
+                    .with_context(|| "Unwrap failed for variable of dataset row limit".to_string())?;
+                Ok(((variable, limit.scope.clone()), limit.row_limit))
+            })
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        // Get base url for resolving relative data urls
+        let base_url = request.opts.as_ref().and_then(|opts| opts.base_url.clone());
+
+        // Get variables to keep in the client spec
+        let keep_variables = request
+            .opts
+            .as_ref()
+            .map(|opts| decode_keep_variables(&opts.keep_signals, &opts.keep_datasets))
+            .unwrap_or_default();
+
+        // Get initial variable values
+        let initial_values = request
+            .opts
+            .as_ref()
+            .map(|opts| opts.initial_values.clone())
+            .unwrap_or_default();
+
         // Extract and deserialize inline datasets
         let inline_pretransform_datasets = request
             .opts
@@ -201,7 +608,8 @@ impl TaskGraphRuntime {
             .collect::<Result<HashMap<_, _>>>()?;
 
         // Parse spec
-        let spec_string = request.spec;
+        let spec_string = apply_request_base_url(request.spec, &base_url)?;
+        let spec_string = apply_request_initial_values(spec_string, &initial_values)?;
         let local_tz = request.local_tz;
         let output_tz = request.output_tz;
 
@@ -210,6 +618,8 @@ impl TaskGraphRuntime {
             &local_tz,
             &output_tz,
             row_limit,
+            &dataset_row_limits,
+            &keep_variables,
             inline_datasets,
         )
         .await
@@ -221,6 +631,8 @@ impl TaskGraphRuntime {
         local_tz: &str,
         default_input_tz: &Option<String>,
         row_limit: Option<u32>,
+        dataset_row_limits: &HashMap<ScopedVariable, u32>,
+        keep_variables: &[ScopedVariable],
         inline_datasets: HashMap<String, VegaFusionDataset>,
     ) -> Result<PreTransformSpecResult> {
         let spec: ChartSpec =
@@ -232,6 +644,7 @@ impl TaskGraphRuntime {
             &PlannerConfig {
                 stringify_local_datetimes: true,
                 extract_inline_data: true,
+                keep_variables: Vec::from(keep_variables),
                 ..Default::default()
             },
         )?;
@@ -239,7 +652,9 @@ impl TaskGraphRuntime {
         // Create task graph for server spec
         let tz_config = TzConfig {
             local_tz: local_tz.to_string(),
-            default_input_tz: default_input_tz.clone(),
+            default_input_tz: default_input_tz
+                .clone()
+                .or_else(|| self.default_input_tz.clone()),
         };
         let task_scope = plan.server_spec.to_task_scope().unwrap();
         let tasks = plan
@@ -249,6 +664,7 @@ impl TaskGraphRuntime {
         let task_graph = TaskGraph::new(tasks, &task_scope).unwrap();
         let task_graph_mapping = task_graph.build_mapping();
 
+        let expression_warnings = ExpressionWarningCollector::default();
         // Gather values of server-to-client values
         let mut init = Vec::new();
         for var in &plan.comm_plan.server_to_client {
@@ -260,6 +676,7 @@ impl TaskGraphRuntime {
                     Arc::new(task_graph.clone()),
                     node_index,
                     inline_datasets.clone(),
+                    expression_warnings.clone(),
                 )
                 .await
                 .expect("Failed to get node value");
@@ -275,6 +692,7 @@ impl TaskGraphRuntime {
         // Update client spec with server values
         let mut spec = plan.client_spec.clone();
         let mut limited_datasets: Vec<Variable> = Vec::new();
+        let mut limited: Vec<PreTransformRowLimitDataset> = Vec::new();
         for export_update in init {
             let scope = export_update.scope.clone();
             let name = export_update.name.as_str();
@@ -285,13 +703,22 @@ impl TaskGraphRuntime {
                 }
                 ExportUpdateNamespace::Data => {
                     let data = spec.get_nested_data_mut(&scope, name)?;
-                    // Handle row_limit
+                    // Handle row limit, with per-dataset limits taking precedence over
+                    // the global row_limit
+                    let scoped_var = export_update.to_scoped_var();
+                    let dataset_limit = dataset_row_limits.get(&scoped_var).cloned().or(row_limit);
                     let value = if let Value::Array(values) = &export_update.value {
-                        if let Some(row_limit) = row_limit {
-                            let row_limit = row_limit as usize;
-                            if values.len() > row_limit {
-                                limited_datasets.push(export_update.to_scoped_var().0);
-                                Value::Array(Vec::from(&values[..row_limit]))
+                        if let Some(dataset_limit) = dataset_limit {
+                            let limit = dataset_limit as usize;
+                            if values.len() > limit {
+                                limited_datasets.push(scoped_var.0.clone());
+                                limited.push(PreTransformRowLimitDataset {
+                                    variable: Some(scoped_var.0),
+                                    scope: scoped_var.1,
+                                    total_rows: values.len() as u64,
+                                    row_limit: dataset_limit,
+                                });
+                                Value::Array(Vec::from(&values[..limit]))
                             } else {
                                 Value::Array(values.clone())
                             }
@@ -326,6 +753,7 @@ impl TaskGraphRuntime {
             warnings.push(PreTransformSpecWarning {
                 warning_type: Some(WarningType::RowLimit(PreTransformRowLimitWarning {
                     datasets: limited_datasets,
+                    limited,
                 })),
             });
         }
@@ -350,6 +778,17 @@ impl TaskGraphRuntime {
             warnings.push(PreTransformSpecWarning {
                 warning_type: Some(WarningType::Planner(PlannerWarning {
                     message: planner_warning.message(),
+                    warning_type: planner_warning.warning_type() as i32,
+                })),
+            });
+        }
+
+        // Add warnings from warn/info/debug expression functions
+        for expr_warning in expression_warnings.take() {
+            warnings.push(PreTransformSpecWarning {
+                warning_type: Some(WarningType::Planner(PlannerWarning {
+                    message: expr_warning.message,
+                    warning_type: PlannerWarningType::Expression as i32,
                 })),
             });
         }
@@ -368,10 +807,45 @@ impl TaskGraphRuntime {
         Ok(response)
     }
 
+    /// Pre-transform a batch of specs in one call. The specs are evaluated
+    /// concurrently and share the runtime's task value and source table caches, so
+    /// source datasets common to multiple specs are only loaded once. Results and
+    /// warnings are returned per spec, in request order; a failing spec produces an
+    /// error result without failing the rest of the batch
+    pub async fn pre_transform_spec_batch_request(
+        &self,
+        request: PreTransformSpecBatchRequest,
+    ) -> Result<PreTransformSpecBatchResult> {
+        let futures = request.requests.into_iter().map(|request| {
+            let runtime = self.clone();
+            async move { runtime.pre_transform_spec_request(request).await }
+        });
+
+        let results = future::join_all(futures)
+            .await
+            .into_iter()
+            .map(|result| match result {
+                Ok(result) => result,
+                Err(err) => PreTransformSpecResult {
+                    result: Some(pre_transform_spec_result::Result::Error(ProtoError {
+                        errorkind: Some(Errorkind::Error(TaskGraphValueError {
+                            msg: err.to_string(),
+                        })),
+                    })),
+                },
+            })
+            .collect();
+
+        Ok(PreTransformSpecBatchResult { results })
+    }
+
     pub async fn pre_transform_values_request(
         &self,
         request: PreTransformValuesRequest,
     ) -> Result<PreTransformValuesResult> {
+        // Get base url for resolving relative data urls
+        let base_url = request.opts.as_ref().and_then(|opts| opts.base_url.clone());
+
         // Extract and deserialize inline datasets
         let inline_pretransform_datasets = request
             .opts
@@ -397,7 +871,7 @@ impl TaskGraphRuntime {
             .collect();
 
         // Parse spec
-        let spec_string = request.spec;
+        let spec_string = apply_request_base_url(request.spec, &base_url)?;
         let local_tz = request.local_tz;
         let default_input_tz = request.default_input_tz;
 
@@ -420,6 +894,7 @@ impl TaskGraphRuntime {
                     variable: Some(var.0.clone()),
                     scope: var.1.clone(),
                     value: Some(proto_value),
+                    chunk: None,
                 })
             })
             .collect::<Result<Vec<_>>>()?;
@@ -492,7 +967,9 @@ impl TaskGraphRuntime {
         // Create task graph for server spec
         let tz_config = TzConfig {
             local_tz: local_tz.to_string(),
-            default_input_tz: default_input_tz.clone(),
+            default_input_tz: default_input_tz
+                .clone()
+                .or_else(|| self.default_input_tz.clone()),
         };
         let task_scope = plan.server_spec.to_task_scope().unwrap();
         let tasks = plan
@@ -509,11 +986,13 @@ impl TaskGraphRuntime {
             warnings.push(PreTransformValuesWarning {
                 warning_type: Some(ValuesWarningType::Planner(PlannerWarning {
                     message: planner_warning.message(),
+                    warning_type: planner_warning.warning_type() as i32,
                 })),
             });
         }
 
         // Gather the values of requested variables
+        let expression_warnings = ExpressionWarningCollector::default();
         let mut values: Vec<TaskValue> = Vec::new();
         for var in variables {
             let node_index = if let Some(node_index) = task_graph_mapping.get(var) {
@@ -531,17 +1010,761 @@ impl TaskGraphRuntime {
                     Arc::new(task_graph.clone()),
                     node_index,
                     inline_datasets.clone(),
+                    expression_warnings.clone(),
                 )
                 .await?;
             values.push(value);
         }
 
+        // Add warnings from warn/info/debug expression functions
+        for expr_warning in expression_warnings.take() {
+            warnings.push(PreTransformValuesWarning {
+                warning_type: Some(ValuesWarningType::Planner(PlannerWarning {
+                    message: expr_warning.message,
+                    warning_type: PlannerWarningType::Expression as i32,
+                })),
+            });
+        }
+
         Ok((values, warnings))
     }
 
+    pub async fn pre_transform_datasets_request(
+        &self,
+        request: PreTransformDatasetsRequest,
+    ) -> Result<PreTransformDatasetsResult> {
+        // Get base url for resolving relative data urls
+        let base_url = request.opts.as_ref().and_then(|opts| opts.base_url.clone());
+
+        // Extract and deserialize inline datasets
+        let inline_pretransform_datasets = request
+            .opts
+            .clone()
+            .map(|opts| opts.inline_datasets)
+            .unwrap_or_default();
+
+        let inline_datasets = inline_pretransform_datasets
+            .iter()
+            .map(|inline_dataset| {
+                let dataset = VegaFusionDataset::from_table_ipc_bytes(&inline_dataset.table)?;
+                Ok((inline_dataset.name.clone(), dataset))
+            })
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        // Extract requested variables
+        let variables: Vec<ScopedVariable> = request
+            .opts
+            .map(|opts| opts.variables)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|var| (var.variable.unwrap(), var.scope))
+            .collect();
+
+        // Parse spec
+        let spec_string = apply_request_base_url(request.spec, &base_url)?;
+        let local_tz = request.local_tz;
+        let default_input_tz = request.default_input_tz;
+
+        let (datasets, warnings) = self
+            .pre_transform_datasets(
+                &spec_string,
+                variables.as_slice(),
+                &local_tz,
+                &default_input_tz,
+                inline_datasets,
+            )
+            .await?;
+
+        let response_datasets: Vec<_> = datasets
+            .into_iter()
+            .zip(&variables)
+            .map(|(table, var)| {
+                let proto_value = ProtoTaskValue::try_from(&TaskValue::Table(table))?;
+                Ok(ResponseTaskValue {
+                    variable: Some(var.0.clone()),
+                    scope: var.1.clone(),
+                    value: Some(proto_value),
+                    chunk: None,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Build result
+        let result = PreTransformDatasetsResult {
+            result: Some(pre_transform_datasets_result::Result::Response(
+                PreTransformDatasetsResponse {
+                    datasets: response_datasets,
+                    warnings,
+                },
+            )),
+        };
+
+        Ok(result)
+    }
+
+    /// Plan a spec and evaluate the requested datasets on the server, returning them
+    /// as Arrow tables rather than a rewritten spec. This lets applications grab
+    /// transformed data for tables and exports without rendering the chart. The
+    /// requested datasets are pinned to the server during planning, so planning fails
+    /// with an informative error if a dataset requires transforms that are not
+    /// supported for server evaluation
+    pub async fn pre_transform_datasets(
+        &self,
+        spec: &str,
+        variables: &[ScopedVariable],
+        local_tz: &str,
+        default_input_tz: &Option<String>,
+        inline_datasets: HashMap<String, VegaFusionDataset>,
+    ) -> Result<(Vec<VegaFusionTable>, Vec<PreTransformDatasetsWarning>)> {
+        let spec: ChartSpec =
+            serde_json::from_str(spec).with_context(|| "Failed to parse spec".to_string())?;
+
+        // Check that requested variables are datasets that exist in the spec
+        for var in variables {
+            let scope = var.1.as_slice();
+            match &var.0.ns() {
+                VariableNamespace::Data => {
+                    if spec.get_nested_data(scope, &var.0.name).is_err() {
+                        return Err(VegaFusionError::pre_transform(format!(
+                            "No dataset named {} with scope {:?}",
+                            var.0.name, scope
+                        )));
+                    }
+                }
+                _ => {
+                    return Err(VegaFusionError::pre_transform(format!(
+                        "pre_transform_datasets does not support variable {:?}",
+                        var.0
+                    )))
+                }
+            }
+        }
+
+        // Create spec plan, pinning the requested datasets to the server so they are
+        // extracted (or planning fails with a clear error). Pruning is disabled so
+        // requested datasets aren't removed when nothing else consumes them
+        let plan = SpecPlan::try_new(
+            &spec,
+            &PlannerConfig {
+                stringify_local_datetimes: true,
+                extract_inline_data: true,
+                split_domain_data: false,
+                projection_pushdown: false,
+                prune_unused: false,
+                force_server_datasets: Vec::from(variables),
+                ..Default::default()
+            },
+        )?;
+
+        // Create task graph for server spec
+        let tz_config = TzConfig {
+            local_tz: local_tz.to_string(),
+            default_input_tz: default_input_tz
+                .clone()
+                .or_else(|| self.default_input_tz.clone()),
+        };
+        let task_scope = plan.server_spec.to_task_scope().unwrap();
+        let tasks = plan
+            .server_spec
+            .to_tasks(&tz_config, &inline_datasets)
+            .unwrap();
+        let task_graph = TaskGraph::new(tasks, &task_scope).unwrap();
+        let task_graph_mapping = task_graph.build_mapping();
+
+        let mut warnings: Vec<PreTransformDatasetsWarning> = Vec::new();
+
+        // Add planner warnings
+        for planner_warning in &plan.warnings {
+            warnings.push(PreTransformDatasetsWarning {
+                warning_type: Some(DatasetsWarningType::Planner(PlannerWarning {
+                    message: planner_warning.message(),
+                    warning_type: planner_warning.warning_type() as i32,
+                })),
+            });
+        }
+
+        // Gather the values of the requested datasets
+        let expression_warnings = ExpressionWarningCollector::default();
+        let mut datasets: Vec<VegaFusionTable> = Vec::new();
+        for var in variables {
+            let node_index = if let Some(node_index) = task_graph_mapping.get(var) {
+                node_index
+            } else {
+                return Err(VegaFusionError::pre_transform(format!(
+                    "Requested dataset {:?}\n requires transforms or signal \
+                        expressions that are not yet supported",
+                    var
+                )));
+            };
+
+            let value = self
+                .get_node_value(
+                    Arc::new(task_graph.clone()),
+                    node_index,
+                    inline_datasets.clone(),
+                    expression_warnings.clone(),
+                )
+                .await?;
+            datasets.push(value.into_table()?);
+        }
+
+        // Add warnings from warn/info/debug expression functions
+        for expr_warning in expression_warnings.take() {
+            warnings.push(PreTransformDatasetsWarning {
+                warning_type: Some(DatasetsWarningType::Planner(PlannerWarning {
+                    message: expr_warning.message,
+                    warning_type: PlannerWarningType::Expression as i32,
+                })),
+            });
+        }
+
+        Ok((datasets, warnings))
+    }
+
+    pub async fn pre_transform_extract_request(
+        &self,
+        request: PreTransformExtractRequest,
+    ) -> Result<PreTransformExtractResult> {
+        // Get extract threshold
+        let extract_threshold = request
+            .opts
+            .as_ref()
+            .and_then(|opts| opts.extract_threshold)
+            .unwrap_or(DEFAULT_EXTRACT_THRESHOLD) as usize;
+
+        // Get base url for resolving relative data urls
+        let base_url = request.opts.as_ref().and_then(|opts| opts.base_url.clone());
+
+        // Get variables to keep in the client spec
+        let keep_variables = request
+            .opts
+            .as_ref()
+            .map(|opts| decode_keep_variables(&opts.keep_signals, &opts.keep_datasets))
+            .unwrap_or_default();
+
+        // Get initial variable values
+        let initial_values = request
+            .opts
+            .as_ref()
+            .map(|opts| opts.initial_values.clone())
+            .unwrap_or_default();
+
+        // Extract and deserialize inline datasets
+        let inline_pretransform_datasets = request
+            .opts
+            .map(|opts| opts.inline_datasets)
+            .unwrap_or_default();
+
+        let inline_datasets = inline_pretransform_datasets
+            .iter()
+            .map(|inline_dataset| {
+                let dataset = VegaFusionDataset::from_table_ipc_bytes(&inline_dataset.table)?;
+                Ok((inline_dataset.name.clone(), dataset))
+            })
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        // Parse spec
+        let spec_string = apply_request_base_url(request.spec, &base_url)?;
+        let spec_string = apply_request_initial_values(spec_string, &initial_values)?;
+        let local_tz = request.local_tz;
+        let default_input_tz = request.default_input_tz;
+
+        let (spec, datasets, warnings) = self
+            .pre_transform_extract(
+                &spec_string,
+                &local_tz,
+                &default_input_tz,
+                extract_threshold,
+                &keep_variables,
+                inline_datasets,
+            )
+            .await?;
+
+        let response_datasets: Vec<_> = datasets
+            .into_iter()
+            .map(|(name, scope, table)| {
+                Ok(PreTransformExtractDataset {
+                    name,
+                    scope,
+                    table: table.to_ipc_bytes()?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Build result
+        let result = PreTransformExtractResult {
+            result: Some(pre_transform_extract_result::Result::Response(
+                PreTransformExtractResponse {
+                    spec: serde_json::to_string(&spec)
+                        .expect("Failed to convert chart spec to string"),
+                    datasets: response_datasets,
+                    warnings,
+                },
+            )),
+        };
+
+        Ok(result)
+    }
+
+    /// Like pre_transform_spec, but datasets with more rows than extract_threshold
+    /// are returned as separate Arrow tables instead of being inlined as JSON values
+    /// in the spec. The extracted datasets are left empty in the returned spec and
+    /// should be provided to the Vega view by name (e.g. with `view.data(name, ...)`)
+    /// before rendering, keeping the JSON spec small for large datasets
+    pub async fn pre_transform_extract(
+        &self,
+        spec: &str,
+        local_tz: &str,
+        default_input_tz: &Option<String>,
+        extract_threshold: usize,
+        keep_variables: &[ScopedVariable],
+        inline_datasets: HashMap<String, VegaFusionDataset>,
+    ) -> Result<(
+        ChartSpec,
+        Vec<(String, Vec<u32>, VegaFusionTable)>,
+        Vec<PreTransformExtractWarning>,
+    )> {
+        let spec: ChartSpec =
+            serde_json::from_str(spec).with_context(|| "Failed to parse spec".to_string())?;
+
+        // Create spec plan
+        let plan = SpecPlan::try_new(
+            &spec,
+            &PlannerConfig {
+                stringify_local_datetimes: true,
+                extract_inline_data: true,
+                keep_variables: Vec::from(keep_variables),
+                ..Default::default()
+            },
+        )?;
+
+        // Create task graph for server spec
+        let tz_config = TzConfig {
+            local_tz: local_tz.to_string(),
+            default_input_tz: default_input_tz
+                .clone()
+                .or_else(|| self.default_input_tz.clone()),
+        };
+        let task_scope = plan.server_spec.to_task_scope().unwrap();
+        let tasks = plan
+            .server_spec
+            .to_tasks(&tz_config, &inline_datasets)
+            .unwrap();
+        let task_graph = TaskGraph::new(tasks, &task_scope).unwrap();
+        let task_graph_mapping = task_graph.build_mapping();
+
+        // Update client spec with server values, extracting large datasets
+        let mut spec = plan.client_spec.clone();
+        let expression_warnings = ExpressionWarningCollector::default();
+        let mut datasets: Vec<(String, Vec<u32>, VegaFusionTable)> = Vec::new();
+        for var in &plan.comm_plan.server_to_client {
+            let node_index = task_graph_mapping
+                .get(var)
+                .unwrap_or_else(|| panic!("Failed to lookup variable '{:?}'", var));
+            let value = self
+                .get_node_value(
+                    Arc::new(task_graph.clone()),
+                    node_index,
+                    inline_datasets.clone(),
+                    expression_warnings.clone(),
+                )
+                .await?;
+
+            let scope = var.1.clone();
+            let name = var.0.name.as_str();
+            match var.0.ns() {
+                VariableNamespace::Signal => {
+                    let signal = spec.get_nested_signal_mut(&scope, name)?;
+                    signal.value = Some(value.to_json()?);
+                }
+                VariableNamespace::Data => {
+                    let table = value.into_table()?;
+                    let data = spec.get_nested_data_mut(&scope, name)?;
+                    if table.num_rows() > extract_threshold {
+                        // Leave the dataset empty in the spec and return the table as a
+                        // separate Arrow payload
+                        data.values = Some(Value::Array(Vec::new()));
+                        datasets.push((name.to_string(), scope, table));
+                    } else {
+                        // Small dataset, inline the values as JSON
+                        data.values = Some(table.try_to_json()?);
+                    }
+                }
+                VariableNamespace::Scale => {
+                    return Err(VegaFusionError::internal(format!(
+                        "Unexpected scale variable {:?} in server to client comm plan",
+                        var.0
+                    )))
+                }
+            }
+        }
+
+        // Build warnings
+        let mut warnings: Vec<PreTransformExtractWarning> = Vec::new();
+
+        // Add planner warnings
+        for planner_warning in &plan.warnings {
+            warnings.push(PreTransformExtractWarning {
+                warning_type: Some(ExtractWarningType::Planner(PlannerWarning {
+                    message: planner_warning.message(),
+                    warning_type: planner_warning.warning_type() as i32,
+                })),
+            });
+        }
+
+        // Add warnings from warn/info/debug expression functions
+        for expr_warning in expression_warnings.take() {
+            warnings.push(PreTransformExtractWarning {
+                warning_type: Some(ExtractWarningType::Planner(PlannerWarning {
+                    message: expr_warning.message,
+                    warning_type: PlannerWarningType::Expression as i32,
+                })),
+            });
+        }
+
+        Ok((spec, datasets, warnings))
+    }
+
+    /// Plan a spec and evaluate all of its server-side nodes into the cache, so
+    /// dashboards can be primed ahead of time (e.g. at deploy time) and the first
+    /// user interaction is served from cache
+    pub async fn prewarm(
+        &self,
+        spec: &str,
+        local_tz: &str,
+        default_input_tz: &Option<String>,
+    ) -> Result<()> {
+        let spec: ChartSpec =
+            serde_json::from_str(spec).with_context(|| "Failed to parse spec".to_string())?;
+
+        // Plan with the same default planner configuration used for interactive
+        // rendering, so prewarmed fingerprints match those of later requests
+        let plan = SpecPlan::try_new(&spec, &Default::default())?;
+
+        let tz_config = TzConfig {
+            local_tz: local_tz.to_string(),
+            default_input_tz: default_input_tz
+                .clone()
+                .or_else(|| self.default_input_tz.clone()),
+        };
+        let task_scope = plan.server_spec.to_task_scope()?;
+        let tasks = plan.server_spec.to_tasks(&tz_config, &Default::default())?;
+        let task_graph = Arc::new(TaskGraph::new(tasks, &task_scope)?);
+
+        // Evaluate every node concurrently. Each node's value (and output values)
+        // is stored in the cache keyed by state fingerprint as a side effect
+        let futures: Vec<_> = (0..task_graph.nodes.len())
+            .map(|node_index| {
+                let task_graph = task_graph.clone();
+                let node_value_index = NodeValueIndex::new(node_index as u32, None);
+                async move {
+                    self.get_node_value(
+                        task_graph,
+                        &node_value_index,
+                        Default::default(),
+                        Default::default(),
+                    )
+                    .await
+                }
+            })
+            .collect();
+        future::try_join_all(futures).await?;
+
+        Ok(())
+    }
+
     pub async fn clear_cache(&self) {
         self.cache.clear().await;
     }
+
+    /// Snapshot of the task value cache's usage (entries, bytes) and hit rate
+    pub fn cache_stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+
+    /// Invalidate a single dataset (or signal) of a task graph: its cached value
+    /// is dropped and will be recomputed on the next request
+    pub async fn invalidate_dataset(
+        &self,
+        task_graph: &TaskGraph,
+        variable: &Variable,
+        scope: &[u32],
+    ) -> Result<()> {
+        let mapping = task_graph.build_mapping();
+        let node_value_index = mapping
+            .get(&(variable.clone(), Vec::from(scope)))
+            .with_context(|| {
+                format!(
+                    "No variable {:?} with scope {:?} in task graph",
+                    variable, scope
+                )
+            })?;
+        let node = task_graph
+            .nodes
+            .get(node_value_index.node_index as usize)
+            .with_context(|| "Node index out of bounds".to_string())?;
+        self.cache.remove(node.state_fingerprint).await;
+        Ok(())
+    }
+
+    /// Invalidate cached data scanned from a url: both the cached HTTP response
+    /// and the scanned source table are dropped, so the next evaluation re-reads
+    /// it. Cached task values computed from the url are unaffected; use
+    /// invalidate_dataset or clear_cache to drop those
+    pub fn invalidate_url(&self, url: &str) {
+        crate::data::http::remove_cached_response(url);
+        crate::data::tasks::invalidate_source_table_cache(url);
+    }
+}
+
+/// Builder that collects the configuration of a TaskGraphRuntime in one place.
+/// Some settings (worker threads, HTTP client settings) configure process-wide
+/// state shared by all runtime instances and are applied when build is called
+#[derive(Debug, Clone, Default)]
+pub struct TaskGraphRuntimeBuilder {
+    cache_capacity: Option<usize>,
+    cache_memory_limit: Option<usize>,
+    disk_cache_dir: Option<PathBuf>,
+    worker_threads: Option<usize>,
+    default_input_tz: Option<String>,
+    http_config: Option<HttpConfig>,
+    base_url: Option<String>,
+    time_locale: Option<TimeLocale>,
+}
+
+impl TaskGraphRuntimeBuilder {
+    /// Maximum number of cached task values, or unbounded if not set
+    pub fn cache_capacity(mut self, capacity: usize) -> Self {
+        self.cache_capacity = Some(capacity);
+        self
+    }
+
+    /// Maximum number of bytes of cached task values, or unbounded if not set
+    pub fn cache_memory_limit(mut self, memory_limit: usize) -> Self {
+        self.cache_memory_limit = Some(memory_limit);
+        self
+    }
+
+    /// Directory used to persist cached task values across restarts
+    pub fn disk_cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> Self {
+        self.disk_cache_dir = Some(cache_dir.into());
+        self
+    }
+
+    /// Number of worker threads used by the shared tokio runtime. Only takes
+    /// effect if the runtime is built before the shared tokio runtime is first
+    /// used
+    pub fn worker_threads(mut self, worker_threads: usize) -> Self {
+        self.worker_threads = Some(worker_threads);
+        self
+    }
+
+    /// Default input timezone applied when pre-transform requests don't
+    /// specify one
+    pub fn default_input_tz(mut self, default_input_tz: impl Into<String>) -> Self {
+        self.default_input_tz = Some(default_input_tz.into());
+        self
+    }
+
+    /// HTTP client settings (headers, authentication, proxy, retries) used
+    /// when loading remote url datasets
+    pub fn http_config(mut self, http_config: HttpConfig) -> Self {
+        self.http_config = Some(http_config);
+        self
+    }
+
+    /// Base url used to resolve relative data urls. Takes precedence over the
+    /// base url of an http_config passed to this builder
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Time locale used by the timeFormat, utcFormat and weekday / month label
+    /// expression functions when computing label columns server-side
+    pub fn time_locale(mut self, time_locale: TimeLocale) -> Self {
+        self.time_locale = Some(time_locale);
+        self
+    }
+
+    /// Apply the process-wide settings and build the runtime
+    pub fn build(self) -> TaskGraphRuntime {
+        if let Some(worker_threads) = self.worker_threads {
+            set_tokio_worker_threads(worker_threads);
+        }
+
+        if let Some(time_locale) = self.time_locale {
+            set_time_locale(time_locale);
+        }
+
+        if self.http_config.is_some() || self.base_url.is_some() {
+            let mut http_config = self.http_config.unwrap_or_else(get_http_config);
+            if let Some(base_url) = self.base_url {
+                http_config.base_url = Some(base_url);
+            }
+            set_http_config(http_config);
+        }
+
+        let mut cache = VegaFusionCache::new(self.cache_capacity, self.cache_memory_limit);
+        if let Some(disk_cache_dir) = self.disk_cache_dir {
+            cache = cache.with_disk_cache(disk_cache_dir);
+        }
+
+        TaskGraphRuntime {
+            cache,
+            default_input_tz: self.default_input_tz,
+            registered_graphs: Arc::new(Mutex::new(LruCache::new(REGISTERED_GRAPH_CAPACITY))),
+        }
+    }
+}
+
+/// Split a table into chunks of approximately chunk_size bytes each, slicing
+/// record batches as needed. Returns a single chunk when the table is empty
+fn chunk_table(table: &VegaFusionTable, chunk_size: usize) -> Result<Vec<VegaFusionTable>> {
+    let num_rows = table.num_rows();
+    let total_size = TaskValue::Table(table.clone()).size_of();
+    if num_rows == 0 || total_size <= chunk_size {
+        return Ok(vec![table.clone()]);
+    }
+
+    // Estimate the number of rows that fit in a chunk from the average row size
+    let bytes_per_row = (total_size as f64 / num_rows as f64).max(1.0);
+    let rows_per_chunk = ((chunk_size as f64 / bytes_per_row).floor() as usize).max(1);
+
+    let mut chunks: Vec<VegaFusionTable> = Vec::new();
+    let mut current_batches = Vec::new();
+    let mut current_rows = 0;
+    for batch in &table.batches {
+        let mut offset = 0;
+        while offset < batch.num_rows() {
+            let take = (rows_per_chunk - current_rows).min(batch.num_rows() - offset);
+            current_batches.push(batch.slice(offset, take));
+            current_rows += take;
+            offset += take;
+
+            if current_rows == rows_per_chunk {
+                chunks.push(VegaFusionTable::try_new(
+                    table.schema.clone(),
+                    std::mem::take(&mut current_batches),
+                )?);
+                current_rows = 0;
+            }
+        }
+    }
+    if !current_batches.is_empty() {
+        chunks.push(VegaFusionTable::try_new(
+            table.schema.clone(),
+            current_batches,
+        )?);
+    }
+
+    Ok(chunks)
+}
+
+/// Apply value updates to a task graph, mirroring client-side update_value calls.
+/// Returns the node index and value delta of each update, so that appended-row
+/// updates can be recomputed incrementally
+fn apply_graph_updates(
+    task_graph: &mut TaskGraph,
+    updates: &[TaskGraphValueUpdate],
+) -> Result<Vec<(usize, ValueUpdate)>> {
+    let mut deltas: Vec<(usize, ValueUpdate)> = Vec::new();
+    for update in updates {
+        let value = update
+            .value
+            .as_ref()
+            .with_context(|| "TaskGraphValueUpdate must include a value".to_string())?;
+        let (_, delta) = task_graph
+            .update_value_with_delta(update.node_index as usize, TaskValue::try_from(value)?)?;
+        deltas.push((update.node_index as usize, delta));
+    }
+    Ok(deltas)
+}
+
+/// The state fingerprint of every node in a task graph, by node index
+fn node_fingerprints(task_graph: &TaskGraph) -> Vec<u64> {
+    task_graph
+        .nodes
+        .iter()
+        .map(|node| node.state_fingerprint)
+        .collect()
+}
+
+/// Pick the first codec from the client's preference list. The native runtime
+/// supports all codecs, so the first valid entry wins
+fn choose_compression(accept_compression: &[i32]) -> DataCompression {
+    accept_compression
+        .iter()
+        .filter_map(|code| DataCompression::from_i32(*code))
+        .next()
+        .unwrap_or(DataCompression::CompressionNone)
+}
+
+/// Resolve relative data urls in a spec string against a per-request base url
+/// Convert the keep_signals and keep_datasets names of a pre-transform request
+/// into the scoped variables of the planner's keep_variables option
+fn decode_keep_variables(
+    keep_signals: &[PreTransformScopedName],
+    keep_datasets: &[PreTransformScopedName],
+) -> Vec<ScopedVariable> {
+    let mut keep_variables: Vec<ScopedVariable> = Vec::new();
+    for keep_signal in keep_signals {
+        keep_variables.push((
+            Variable::new_signal(&keep_signal.name),
+            keep_signal.scope.clone(),
+        ));
+    }
+    for keep_dataset in keep_datasets {
+        keep_variables.push((
+            Variable::new_data(&keep_dataset.name),
+            keep_dataset.scope.clone(),
+        ));
+    }
+    keep_variables
+}
+
+/// Apply the initial variable values of a pre-transform request to the spec
+/// string before planning
+fn apply_request_initial_values(
+    spec_string: String,
+    initial_values: &[PreTransformInitialValue],
+) -> Result<String> {
+    if initial_values.is_empty() {
+        return Ok(spec_string);
+    }
+
+    let initial_values = initial_values
+        .iter()
+        .map(|initial_value| {
+            let variable = initial_value
+                .variable
+                .clone()
+                .with_context(|| "Unwrap failed for variable of initial value".to_string())?;
+            let value: Value = serde_json::from_str(&initial_value.value).with_context(|| {
+                format!(
+                    "Failed to parse initial value for variable {:?} as JSON",
+                    variable
+                )
+            })?;
+            Ok(((variable, initial_value.scope.clone()), value))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut spec: ChartSpec = serde_json::from_str(&spec_string)
+        .with_context(|| "Failed to parse spec".to_string())?;
+    apply_initial_values(&mut spec, &initial_values)?;
+    Ok(serde_json::to_string(&spec).expect("Failed to convert chart spec to string"))
+}
+
+fn apply_request_base_url(spec_string: String, base_url: &Option<String>) -> Result<String> {
+    if let Some(base_url) = base_url {
+        let mut spec: ChartSpec = serde_json::from_str(&spec_string)
+            .with_context(|| "Failed to parse spec".to_string())?;
+        apply_base_url(&mut spec, base_url)?;
+        serde_json::to_string(&spec).external("Failed to serialize spec")
+    } else {
+        Ok(spec_string)
+    }
 }
 
 #[async_recursion]
@@ -550,6 +1773,7 @@ async fn get_or_compute_node_value(
     node_index: usize,
     cache: VegaFusionCache,
     inline_datasets: HashMap<String, VegaFusionDataset>,
+    expression_warnings: ExpressionWarningCollector,
 ) -> Result<CacheValue> {
     // Get the cache key for requested node
     let node = task_graph.node(node_index).unwrap();
@@ -563,6 +1787,9 @@ async fn get_or_compute_node_value(
         let input_node_indexes = task_graph.parent_indices(node_index).unwrap();
         let input_edges = node.incoming.clone();
 
+        #[cfg(feature = "tracing")]
+        let variable_name = task.variable().name.clone();
+
         // Clone task so we can move it to async block
         let task = task.clone();
         let tz_config = task.tz_config.clone().and_then(|tz_config| {
@@ -581,6 +1808,7 @@ async fn get_or_compute_node_value(
                     input_node_index,
                     cloned_cache.clone(),
                     inline_datasets.clone(),
+                    expression_warnings.clone(),
                 )));
             }
 
@@ -607,7 +1835,20 @@ async fn get_or_compute_node_value(
                 })
                 .collect::<Result<Vec<_>>>()?;
 
-            task.eval(&input_values, &tz_config, inline_datasets).await
+            task.eval(&input_values, &tz_config, inline_datasets, expression_warnings)
+                .await
+        };
+
+        // Attach a tracing span naming the task so embedders can attribute time
+        // spent evaluating it
+        #[cfg(feature = "tracing")]
+        let fut = {
+            use tracing::Instrument;
+            fut.instrument(tracing::info_span!(
+                "task_eval",
+                variable = %variable_name,
+                state_fingerprint = cache_key
+            ))
         };
 
         // get or construct from cache
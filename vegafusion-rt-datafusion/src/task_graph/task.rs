@@ -6,6 +6,7 @@
  * Please consult the license documentation provided alongside
  * this program the details of the active license.
  */
+use crate::expression::compiler::builtin_functions::control_flow::logging::ExpressionWarningCollector;
 use crate::task_graph::timezone::RuntimeTzConfig;
 use async_trait::async_trait;
 use std::collections::HashMap;
@@ -23,6 +24,7 @@ pub trait TaskCall {
         values: &[TaskValue],
         tz_config: &Option<RuntimeTzConfig>,
         inline_datasets: HashMap<String, VegaFusionDataset>,
+        expression_warnings: ExpressionWarningCollector,
     ) -> Result<(TaskValue, Vec<TaskValue>)>;
 }
 
@@ -33,13 +35,26 @@ impl TaskCall for Task {
         values: &[TaskValue],
         tz_config: &Option<RuntimeTzConfig>,
         inline_datasets: HashMap<String, VegaFusionDataset>,
+        expression_warnings: ExpressionWarningCollector,
     ) -> Result<(TaskValue, Vec<TaskValue>)> {
         match self.task_kind() {
             TaskKind::Value(value) => Ok((value.try_into()?, Default::default())),
-            TaskKind::DataUrl(task) => task.eval(values, tz_config, inline_datasets).await,
-            TaskKind::DataValues(task) => task.eval(values, tz_config, inline_datasets).await,
-            TaskKind::DataSource(task) => task.eval(values, tz_config, inline_datasets).await,
-            TaskKind::Signal(task) => task.eval(values, tz_config, inline_datasets).await,
+            TaskKind::DataUrl(task) => {
+                task.eval(values, tz_config, inline_datasets, expression_warnings)
+                    .await
+            }
+            TaskKind::DataValues(task) => {
+                task.eval(values, tz_config, inline_datasets, expression_warnings)
+                    .await
+            }
+            TaskKind::DataSource(task) => {
+                task.eval(values, tz_config, inline_datasets, expression_warnings)
+                    .await
+            }
+            TaskKind::Signal(task) => {
+                task.eval(values, tz_config, inline_datasets, expression_warnings)
+                    .await
+            }
         }
     }
 }
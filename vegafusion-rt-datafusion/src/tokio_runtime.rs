@@ -6,11 +6,25 @@
  * Please consult the license documentation provided alongside
  * this program the details of the active license.
  */
+use std::sync::RwLock;
 use tokio::runtime::Runtime;
 
 lazy_static! {
-    pub static ref TOKIO_RUNTIME: Runtime = tokio::runtime::Builder::new_multi_thread()
-        .enable_all()
-        .build()
-        .unwrap();
+    static ref TOKIO_WORKER_THREADS: RwLock<Option<usize>> = RwLock::new(None);
+    pub static ref TOKIO_RUNTIME: Runtime = {
+        let mut builder = tokio::runtime::Builder::new_multi_thread();
+        if let Some(worker_threads) = *TOKIO_WORKER_THREADS.read().unwrap() {
+            builder.worker_threads(worker_threads.max(1));
+        }
+        builder.enable_all().build().unwrap()
+    };
+}
+
+/// Set the number of worker threads used by the shared tokio runtime. This only
+/// takes effect if called before the runtime is first used; later calls have no
+/// effect. Embedders that drive VegaFusion from their own tokio runtime are
+/// unaffected
+pub fn set_tokio_worker_threads(worker_threads: usize) {
+    let mut guard = TOKIO_WORKER_THREADS.write().unwrap();
+    *guard = Some(worker_threads);
 }
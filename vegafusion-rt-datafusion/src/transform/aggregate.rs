@@ -6,17 +6,19 @@
  * Please consult the license documentation provided alongside
  * this program the details of the active license.
  */
+use crate::data::table::VegaFusionTableUtils;
 use crate::expression::compiler::config::CompilationConfig;
 use crate::transform::TransformTrait;
 use datafusion::dataframe::DataFrame;
 use datafusion::logical_plan::{avg, col, count, count_distinct, lit, max, min, sum, Expr};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::expression::compiler::utils::to_numeric;
 use async_trait::async_trait;
 use datafusion_expr::{aggregate_function, BuiltInWindowFunction, WindowFunction};
 use std::sync::Arc;
 use vegafusion_core::arrow::datatypes::DataType;
+use vegafusion_core::data::table::VegaFusionTable;
 use vegafusion_core::error::{Result, ResultWithContext, VegaFusionError};
 use vegafusion_core::proto::gen::transforms::{Aggregate, AggregateOp};
 use vegafusion_core::task_graph::task_value::TaskValue;
@@ -184,3 +186,94 @@ impl TransformTrait for Aggregate {
         Ok((grouped_dataframe, Vec::new()))
     }
 }
+
+/// Whether an aggregate transform can be recomputed incrementally when rows are
+/// appended to its input. This is the case when every op's partial aggregate over
+/// the appended rows can be merged with the previous result (sum/count/min/max
+/// and the count-like valid/missing ops)
+pub fn supports_incremental(agg: &Aggregate) -> bool {
+    agg.ops.iter().all(|op_code| {
+        matches!(
+            AggregateOp::from_i32(*op_code).unwrap(),
+            AggregateOp::Count
+                | AggregateOp::Sum
+                | AggregateOp::Min
+                | AggregateOp::Max
+                | AggregateOp::Valid
+                | AggregateOp::Missing
+        )
+    })
+}
+
+/// Recompute an aggregate transform given its previous result and the rows that
+/// were appended to its input since that result was computed. Only valid when
+/// supports_incremental returns true.
+///
+/// The appended rows are aggregated on their own, then merged with the previous
+/// result by re-aggregating the union of the two per-group partial results. Note
+/// that group ordering is not preserved: new groups are not guaranteed to appear
+/// after existing ones
+pub async fn eval_incremental(
+    agg: &Aggregate,
+    prev_result: &VegaFusionTable,
+    delta: Arc<DataFrame>,
+    config: &CompilationConfig,
+) -> Result<Arc<DataFrame>> {
+    if !supports_incremental(agg) {
+        return Err(VegaFusionError::internal(
+            "Aggregate transform does not support incremental evaluation",
+        ));
+    }
+
+    // Aggregate the appended rows on their own
+    let (delta_result, _) = agg.eval(delta, config).await?;
+
+    // Union the previous result with the delta aggregate. Both were produced by
+    // the same eval projection, so the schemas line up
+    let union_df = prev_result.to_dataframe()?.union(delta_result)?;
+
+    // Re-aggregate the per-group partial results. Count-like ops merge by summing
+    // partial counts
+    let mut projections: Vec<_> = agg.groupby.iter().map(|f| col(f)).collect();
+    let mut merge_exprs = Vec::new();
+    let mut merged_aliases: HashSet<String> = HashSet::new();
+
+    for (i, (field, op_code)) in agg.fields.iter().zip(agg.ops.iter()).enumerate() {
+        let op = AggregateOp::from_i32(*op_code).unwrap();
+        let alias = if let Some(alias) = agg.aliases.get(i).filter(|a| !a.is_empty()) {
+            alias.clone()
+        } else if field.is_empty() {
+            op_name(op).to_string()
+        } else {
+            format!("{}_{}", op_name(op), field,)
+        };
+
+        if !merged_aliases.insert(alias.clone()) {
+            // Duplicate alias, already handled
+            continue;
+        }
+        projections.push(col(&alias));
+
+        let merge_expr = match op {
+            AggregateOp::Count | AggregateOp::Sum | AggregateOp::Valid | AggregateOp::Missing => {
+                sum(col(&alias))
+            }
+            AggregateOp::Min => min(col(&alias)),
+            AggregateOp::Max => max(col(&alias)),
+            op => {
+                return Err(VegaFusionError::internal(&format!(
+                    "Aggregation op {:?} does not support incremental evaluation",
+                    op
+                )))
+            }
+        };
+        merge_exprs.push(merge_expr.alias(&alias));
+    }
+
+    let group_exprs: Vec<_> = agg.groupby.iter().map(|c| col(c)).collect();
+    let merged_dataframe = union_df
+        .aggregate(group_exprs, merge_exprs)
+        .with_context(|| "Failed to merge incremental aggregate results".to_string())?;
+
+    Ok(merged_dataframe.select(projections)?)
+}
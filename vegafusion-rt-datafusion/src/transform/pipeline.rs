@@ -7,6 +7,7 @@
  * this program the details of the active license.
  */
 use crate::expression::compiler::config::CompilationConfig;
+use crate::transform::aggregate::supports_incremental;
 use crate::transform::TransformTrait;
 use datafusion::dataframe::DataFrame;
 
@@ -17,8 +18,12 @@ use std::sync::Arc;
 use vegafusion_core::error::Result;
 
 use async_trait::async_trait;
+use vegafusion_core::proto::gen::expression::{expression::Expr, literal, BinaryOperator, Expression};
 use vegafusion_core::proto::gen::tasks::{Variable, VariableNamespace};
-use vegafusion_core::proto::gen::transforms::TransformPipeline;
+use vegafusion_core::proto::gen::transforms::{
+    transform::TransformKind, window_transform_op, Aggregate, Collect, Transform,
+    TransformPipeline, Window, WindowOp,
+};
 use vegafusion_core::task_graph::task_value::TaskValue;
 use vegafusion_core::transform::TransformDependencies;
 
@@ -33,7 +38,33 @@ impl TransformTrait for TransformPipeline {
         let mut result_outputs: HashMap<Variable, TaskValue> = Default::default();
         let mut config = config.clone();
 
-        for tx in &self.transforms {
+        let mut i = 0;
+        while i < self.transforms.len() {
+            let tx = &self.transforms[i];
+
+            // Top-K peephole: a row_number window followed by a filter on the produced
+            // column is rewritten to sort + limit before the window runs, so only the
+            // retained rows flow through the window and the rest of the pipeline
+            if let Some(limit) = top_k_limit(tx, self.transforms.get(i + 1)) {
+                if let Some(TransformKind::Window(window)) = &tx.transform_kind {
+                    if !window.sort_fields.is_empty() {
+                        let collect = Transform {
+                            transform_kind: Some(TransformKind::Collect(Collect {
+                                fields: window.sort_fields.clone(),
+                                order: window.sort.clone(),
+                            })),
+                        };
+                        result_df = collect.eval(result_df, &config).await?.0;
+                    }
+                    result_df = result_df.limit(limit)?;
+
+                    // Evaluate the window on the retained rows, then skip the filter
+                    result_df = tx.eval(result_df, &config).await?.0;
+                    i += 2;
+                    continue;
+                }
+            }
+
             let tx_result = tx.eval(result_df, &config).await?;
 
             // Update dataframe
@@ -60,6 +91,8 @@ impl TransformTrait for TransformPipeline {
                     }
                 }
             }
+
+            i += 1;
         }
 
         // Sort result signal value by signal name
@@ -71,3 +104,101 @@ impl TransformTrait for TransformPipeline {
         Ok((result_df, signals_values))
     }
 }
+
+/// The single aggregate transform of a pipeline, if the pipeline consists of
+/// exactly one aggregate transform that supports incremental recomputation when
+/// rows are appended to its input. Pipelines with additional transforms aren't
+/// recomputed incrementally, since their previous result isn't the aggregate's
+/// output
+pub fn incremental_aggregate(pipeline: &TransformPipeline) -> Option<&Aggregate> {
+    match pipeline.transforms.as_slice() {
+        [Transform {
+            transform_kind: Some(TransformKind::Aggregate(agg)),
+        }] if supports_incremental(agg) => Some(agg),
+        _ => None,
+    }
+}
+
+/// If `tx` is a row_number window transform without grouping and `next` is a filter
+/// of the form `datum.<output> <= k` (or `<`) on the window's output column, return
+/// the number of rows to retain. The window + filter pair can then be evaluated as
+/// sort + limit + window, so only the retained rows are computed
+fn top_k_limit(tx: &Transform, next: Option<&Transform>) -> Option<usize> {
+    // Match a single ungrouped row_number op. Ranking ops with ties (rank,
+    // dense_rank) can retain more than k rows, so they aren't rewritten
+    let window = match &tx.transform_kind {
+        Some(TransformKind::Window(window)) => window,
+        _ => return None,
+    };
+    if !window.groupby.is_empty() || window.ops.len() != 1 {
+        return None;
+    }
+    match &window.ops[0].op {
+        Some(window_transform_op::Op::WindowOp(op)) if *op == WindowOp::RowNumber as i32 => {}
+        _ => return None,
+    }
+    let output_col = output_column(window);
+
+    // Match a filter of the form `datum.<output_col> <= k` or `datum.<output_col> < k`
+    let filter = match next.map(|tx| &tx.transform_kind) {
+        Some(Some(TransformKind::Filter(filter))) => filter,
+        _ => return None,
+    };
+    let binary = match filter.expr.as_ref().and_then(|expr| expr.expr.as_ref()) {
+        Some(Expr::Binary(binary)) => binary,
+        _ => return None,
+    };
+    if datum_column(binary.left.as_deref())? != output_col {
+        return None;
+    }
+    let k = numeric_literal(binary.right.as_deref())?;
+    if !k.is_finite() || k < 0.0 {
+        return None;
+    }
+    if binary.operator == BinaryOperator::LessThanEqual as i32 {
+        Some(k.floor() as usize)
+    } else if binary.operator == BinaryOperator::LessThan as i32 {
+        Some((k.ceil() as i64 - 1).max(0) as usize)
+    } else {
+        None
+    }
+}
+
+/// The output column name of a single-op window transform
+fn output_column(window: &Window) -> String {
+    match window.aliases.first() {
+        Some(alias) if !alias.is_empty() => alias.clone(),
+        _ => "row_number".to_string(),
+    }
+}
+
+/// The column name of an expression of the form `datum.col` or `datum['col']`
+fn datum_column(expr: Option<&Expression>) -> Option<String> {
+    let member = match expr?.expr.as_ref()? {
+        Expr::Member(member) => member,
+        _ => return None,
+    };
+    match member.object.as_ref()?.expr.as_ref()? {
+        Expr::Identifier(identifier) if identifier.name == "datum" => {}
+        _ => return None,
+    }
+    match member.property.as_ref()?.expr.as_ref()? {
+        Expr::Identifier(identifier) if !member.computed => Some(identifier.name.clone()),
+        Expr::Literal(lit) if member.computed => match &lit.value {
+            Some(literal::Value::String(name)) => Some(name.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The value of a numeric literal expression
+fn numeric_literal(expr: Option<&Expression>) -> Option<f64> {
+    match expr?.expr.as_ref()? {
+        Expr::Literal(lit) => match &lit.value {
+            Some(literal::Value::Number(value)) => Some(*value),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -15,7 +15,7 @@ use rstest::rstest;
 use serde_json::json;
 use std::collections::HashMap;
 
-use util::check::check_scalar_evaluation;
+use util::check::{check_scalar_evaluation, check_scalar_evaluation_approx};
 use vegafusion_core::data::table::VegaFusionTable;
 use vegafusion_rt_datafusion::expression::compiler::config::CompilationConfig;
 
@@ -460,6 +460,10 @@ mod test_length {
         case("length(hello)"),
         case("length(data('dataB'))"),
         case("data('dataB').length"),
+        // Length counts characters, not bytes
+        case("length('héllo')"),
+        case("'héllo'.length"),
+        case("length('日本語')"),
     )]
     fn test(expr: &str) {
         check_scalar_evaluation(expr, &config_a())
@@ -498,3 +502,309 @@ mod test_null_comparison {
     #[test]
     fn test_marker() {} // Help IDE detect test module
 }
+
+mod test_bitwise {
+    use crate::*;
+
+    #[rstest(
+        expr,
+        case("12 & 10"),
+        case("12 | 10"),
+        case("12 ^ 10"),
+        case("~12"),
+        case("~-12"),
+        case("5 << 2"),
+        case("-20 >> 2"),
+        case("-20 >>> 2"),
+        // Operands are converted with ToInt32, wrapping modulo 2^32
+        case("2147483648 | 0"),
+        case("4294967296 | 0"),
+        case("2.9 | 0"),
+        case("-2.9 | 0"),
+        case("NaN | 0"),
+        // Shift counts are masked to 5 bits
+        case("1 << 32"),
+        case("1 << 33"),
+        case("1024 >> 33"),
+        case("1024 >>> 33"),
+        // Precedence: & over ^ over |, shifts over comparisons, additive over shifts
+        case("1 | 2 ^ 3 & 4"),
+        case("1 & 2 | 3 ^ 4"),
+        case("1 + 2 << 3"),
+        case("1 << 2 + 3"),
+        case("1 < 2 & 3"),
+        case("bar | 0"),
+        case("foo | 0")
+    )]
+    fn test(expr: &str) {
+        check_scalar_evaluation(expr, &config_a())
+    }
+
+    #[test]
+    fn test_marker() {} // Help IDE detect test module
+}
+
+mod test_logical_values {
+    use crate::*;
+
+    // || and && return the deciding operand's value, not a boolean
+    #[rstest(
+        expr,
+        case("0 || 42"),
+        case("'' || 'fallback'"),
+        case("NaN || 10"),
+        case("null || 'default'"),
+        case("'a' || 'b'"),
+        case("0 && 5"),
+        case("'' && 'b'"),
+        case("NaN && 5"),
+        case("'a' && 'b'"),
+        case("null && 1"),
+        case("foo || bar"),
+        case("valid && foo"),
+        case("valid || hello")
+    )]
+    fn test(expr: &str) {
+        check_scalar_evaluation(expr, &config_a())
+    }
+
+    #[test]
+    fn test_marker() {} // Help IDE detect test module
+}
+
+mod test_array_functions {
+    use crate::*;
+
+    #[rstest(
+        expr,
+        case("sequence(5)"),
+        case("sequence(1, 5)"),
+        case("sequence(0, 10, 2)"),
+        case("extent([3, 1, 2])"),
+        case("span([1, 10])"),
+        case("reverse([1, 2, 3])"),
+        case("sort([3, 1, 2])"),
+        case("sort(['b', 'c', 'a'])"),
+        case("slice([1, 2, 3, 4], 1)"),
+        case("slice([1, 2, 3, 4], 1, 3)"),
+        case("slice([1, 2, 3, 4], -2)"),
+        case("slice('hello', 1, 3)"),
+        case("join(['a', 'b', 'c'], '-')"),
+        case("join(['a', 'b', 'c'])"),
+        case("pluck([{a: 1}, {a: 2}, {a: 3}], 'a')"),
+        case("indexof([1, 2, 3, 2], 2)"),
+        case("indexof(['a', 'b', 'c'], 'd')"),
+        case("lastindexof([1, 2, 3, 2], 2)"),
+        case("indexof('hello world', 'o')"),
+        case("lastindexof('hello world', 'o')"),
+        case("indexof('hello', 'z')"),
+        case("inrange(2, [1, 3])"),
+        case("inrange(5, [1, 3])"),
+        case("inrange(1, [1, 3])"),
+        case("clampRange([2, 4], 0, 10)"),
+        case("clampRange([-3, 2], 0, 10)"),
+        case("clampRange([8, 14], 0, 10)")
+    )]
+    fn test(expr: &str) {
+        check_scalar_evaluation(expr, &config_a())
+    }
+
+    #[test]
+    fn test_marker() {} // Help IDE detect test module
+}
+
+mod test_type_checking {
+    use crate::*;
+
+    #[rstest(
+        expr,
+        case("isArray([1, 2])"),
+        case("isArray('abc')"),
+        case("isObject({a: 1})"),
+        case("isObject(5)"),
+        case("isBoolean(false)"),
+        case("isBoolean(1)"),
+        case("isNumber(1.5)"),
+        case("isNumber('1.5')"),
+        case("isString('abc')"),
+        case("isString(123)"),
+        case("isValid(null)"),
+        case("isValid(NaN)"),
+        case("isValid(0)"),
+        case("isDate(datetime(87, 3, 10))"),
+        case("isDate(1589603400000)")
+    )]
+    fn test(expr: &str) {
+        check_scalar_evaluation(expr, &config_a())
+    }
+
+    #[test]
+    fn test_marker() {} // Help IDE detect test module
+}
+
+mod test_string_functions {
+    use crate::*;
+
+    #[rstest(
+        expr,
+        case("pad('abc', 6)"),
+        case("pad('abc', 6, '*')"),
+        case("pad('abc', 6, '*', 'left')"),
+        case("pad('abc', 7, '*', 'center')"),
+        case("pad('abcdef', 3)"),
+        case("truncate('hello world', 5)"),
+        case("truncate('hello world', 8, 'left')"),
+        case("truncate('hello world', 8, 'right', '...')"),
+        case("truncate('hello', 10)")
+    )]
+    fn test(expr: &str) {
+        check_scalar_evaluation(expr, &config_a())
+    }
+
+    #[test]
+    fn test_marker() {} // Help IDE detect test module
+}
+
+mod test_lerp_quantile {
+    use crate::*;
+
+    #[rstest(
+        expr,
+        case("lerp([0, 50], 0.5)"),
+        case("lerp([0, 50], 0)"),
+        case("lerp([10, 20], 0.25)"),
+        case("quantile([1, 2, 3, 4, 5], 0.5)"),
+        case("quantile([1, 2, 3, 4], 0.25)"),
+        case("quantile([3, 1, 5, 2, 4], 0.3)")
+    )]
+    fn test(expr: &str) {
+        check_scalar_evaluation(expr, &config_a())
+    }
+
+    #[test]
+    fn test_marker() {} // Help IDE detect test module
+}
+
+mod test_distributions {
+    use crate::*;
+
+    // Vega's implementations use different floating point approximations, so results
+    // are compared to within a tolerance rather than exactly
+    #[rstest(
+        expr,
+        case("cumulativeNormal(0)"),
+        case("cumulativeNormal(1.5, 1, 2)"),
+        case("densityNormal(0)"),
+        case("densityNormal(1.5, 1, 2)"),
+        case("quantileNormal(0.5)"),
+        case("quantileNormal(0.25, 1, 2)"),
+        case("cumulativeLogNormal(2, 0, 1)"),
+        case("densityLogNormal(2, 0, 1)"),
+        case("quantileLogNormal(0.5, 0, 1)"),
+        case("cumulativeUniform(0.5, 0, 2)"),
+        case("densityUniform(0.5, 0, 2)"),
+        case("quantileUniform(0.25, 0, 2)")
+    )]
+    fn test(expr: &str) {
+        check_scalar_evaluation_approx(expr, &config_a(), 1e-6)
+    }
+
+    #[test]
+    fn test_marker() {} // Help IDE detect test module
+}
+
+mod test_merge {
+    use crate::*;
+
+    #[rstest(
+        expr,
+        case("merge({a: 1}, {b: 2})"),
+        case("merge({a: 1}, {a: 2, b: 3})"),
+        case("merge({a: 1}, {b: 2}, {c: 3})"),
+        case("merge({a: 1, b: 2}, {b: 3}).b")
+    )]
+    fn test(expr: &str) {
+        check_scalar_evaluation(expr, &config_a())
+    }
+
+    #[test]
+    fn test_marker() {} // Help IDE detect test module
+}
+
+mod test_to_date_format {
+    use crate::*;
+
+    #[rstest(
+        expr,
+        case("toDate('2020!05!16', '%Y!%m!%d')"),
+        case("toDate('05/16/2020 09:30', '%m/%d/%Y %H:%M')"),
+        case("toDate('16 May 2020', '%d %b %Y')")
+    )]
+    fn test(expr: &str) {
+        check_scalar_evaluation(expr, &config_a())
+    }
+
+    #[test]
+    fn test_marker() {} // Help IDE detect test module
+}
+
+mod test_label_formats {
+    use crate::*;
+
+    #[rstest(
+        expr,
+        case("dayFormat(0)"),
+        case("dayFormat(6)"),
+        case("dayAbbrevFormat(0)"),
+        case("dayAbbrevFormat(6)"),
+        case("monthFormat(0)"),
+        case("monthFormat(11)"),
+        case("monthAbbrevFormat(0)"),
+        case("monthAbbrevFormat(11)")
+    )]
+    fn test(expr: &str) {
+        check_scalar_evaluation(expr, &config_a())
+    }
+
+    #[test]
+    fn test_marker() {} // Help IDE detect test module
+}
+
+mod test_indata {
+    use crate::*;
+
+    #[rstest(
+        expr,
+        case("indata('dataB', 'colA', 40)"),
+        case("indata('dataB', 'colA', 41)"),
+        case("indata('dataB', 'colC', 'THIRD')"),
+        case("indata('dataA', 'colB', true)")
+    )]
+    fn test(expr: &str) {
+        check_scalar_evaluation(expr, &config_a())
+    }
+
+    #[test]
+    fn test_marker() {} // Help IDE detect test module
+}
+
+mod test_color_metrics {
+    use crate::*;
+
+    #[rstest(
+        expr,
+        case("luminance('#ffffff')"),
+        case("luminance('#000000')"),
+        case("luminance('#4682b4')"),
+        case("luminance('#abc')"),
+        case("luminance(rgb(64, 128, 255))"),
+        case("contrast('#ffffff', '#000000')"),
+        case("contrast('#4682b4', '#abc')")
+    )]
+    fn test(expr: &str) {
+        check_scalar_evaluation_approx(expr, &config_a(), 1e-9)
+    }
+
+    #[test]
+    fn test_marker() {} // Help IDE detect test module
+}
@@ -82,6 +82,53 @@ mod test_parse_binary_precedence {
     fn test_marker() {} // Help IDE detect test module
 }
 
+mod test_parse_bitwise {
+    use crate::*;
+
+    #[rstest(
+        expr,
+        case("12 & 10"),
+        case("12 | 10"),
+        case("12 ^ 10"),
+        case("~12"),
+        case("~~12"),
+        case("5 << 2"),
+        case("-20 >> 2"),
+        case("-20 >>> 2")
+    )]
+    fn test(expr: &str) {
+        check_parsing(expr);
+        check_expr_supported(expr);
+    }
+
+    #[test]
+    fn test_marker() {} // Help IDE detect test module
+}
+
+mod test_parse_bitwise_precedence {
+    use crate::*;
+
+    #[rstest(
+        expr,
+        case("1 | 2 ^ 3 & 4"),
+        case("(1 | 2) ^ 3 & 4"),
+        case("1 | (2 ^ 3) & 4"),
+        case("1 & 2 | 3 ^ 4"),
+        case("1 + 2 << 3 >> 4 >>> 5"),
+        case("1 << 2 + 3"),
+        case("~1 << 2"),
+        case("1 < 2 & 3"),
+        case("1 | 2 && 3 | 4")
+    )]
+    fn test(expr: &str) {
+        check_parsing(expr);
+        check_expr_supported(expr);
+    }
+
+    #[test]
+    fn test_marker() {} // Help IDE detect test module
+}
+
 mod test_parse_unary {
     use crate::*;
 
@@ -296,7 +343,14 @@ mod test_check_supported {
         case("no_such_fn(23)", false),
         case("my_signal[0 + 23]", true),
         case("my_signal[0 + other_signal]", true),
-        case("my_signal[datum.col + 'abc']", false)
+        case("my_signal[datum.col + 'abc']", false),
+        case("sequence(0, 10, 2)", true),
+        case("cumulativeNormal(0)", true),
+        case("scale('xscale', 5)", true),
+        case("bandwidth('xscale')", true),
+        // The scale name must be a string literal
+        case("scale(my_signal, 5)", false),
+        case("gradient('ramp', 0, 1)", false)
     )]
     fn test(expr: &str, supported: bool) {
         let expr = parse(expr).unwrap();
@@ -1157,6 +1157,11 @@ mod test_pre_transform_inline {
         let opts = PreTransformSpecOpts {
             row_limit: None,
             inline_datasets,
+            base_url: None,
+            dataset_row_limits: vec![],
+            keep_signals: vec![],
+            keep_datasets: vec![],
+            initial_values: vec![],
         };
         let request = PreTransformSpecRequest {
             spec: serde_json::to_string(&inline_spec).unwrap(),
@@ -1296,6 +1301,11 @@ async fn check_pre_transform_spec_from_files(spec_name: &str, tolerance: f64) {
     let opts = PreTransformSpecOpts {
         row_limit: None,
         inline_datasets: vec![],
+        base_url: None,
+        dataset_row_limits: vec![],
+        keep_signals: vec![],
+        keep_datasets: vec![],
+        initial_values: vec![],
     };
     let request = PreTransformSpecRequest {
         spec: serde_json::to_string(&full_spec).unwrap(),
@@ -1422,7 +1432,12 @@ async fn check_spec_sequence(
     for var in &spec_plan.comm_plan.server_to_client {
         let node_index = task_graph_mapping.get(var).unwrap();
         let value = runtime
-            .get_node_value(Arc::new(task_graph.clone()), node_index, Default::default())
+            .get_node_value(
+                Arc::new(task_graph.clone()),
+                node_index,
+                Default::default(),
+                Default::default(),
+            )
             .await
             .expect("Failed to get node value");
 
@@ -1490,7 +1505,12 @@ async fn check_spec_sequence(
         let mut server_to_client_value_batch = HashMap::new();
         for (var, node_index) in watch_vars.iter().zip(&watch_indices) {
             let value = runtime
-                .get_node_value(Arc::new(task_graph.clone()), node_index, Default::default())
+                .get_node_value(
+                Arc::new(task_graph.clone()),
+                node_index,
+                Default::default(),
+                Default::default(),
+            )
                 .await
                 .unwrap();
 
@@ -0,0 +1,176 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use serde_json::json;
+use std::convert::TryFrom;
+use vegafusion_core::data::table::VegaFusionTable;
+use vegafusion_core::proto::gen::services::{query_request, query_result, QueryRequest, QueryResult};
+use vegafusion_core::proto::gen::tasks::{
+    task_graph_value_request, DataSourceTask, NodeValueIndex, Task, TaskGraph,
+    TaskGraphValueRequest, TaskGraphValueUpdate, TaskValue as ProtoTaskValue, TzConfig, Variable,
+};
+use vegafusion_core::proto::gen::transforms::transform::TransformKind;
+use vegafusion_core::proto::gen::transforms::{
+    Aggregate, AggregateOp, Transform, TransformPipeline,
+};
+use vegafusion_core::task_graph::scope::TaskScope;
+use vegafusion_core::task_graph::task_value::TaskValue;
+use vegafusion_rt_datafusion::task_graph::runtime::TaskGraphRuntime;
+
+/// Task graph with a "source" value task feeding an "aggregated" data source
+/// task that sums column b grouped by column a
+fn aggregate_task_graph(source_table: VegaFusionTable) -> TaskGraph {
+    let tz_config = TzConfig {
+        local_tz: "UTC".to_string(),
+        default_input_tz: None,
+    };
+    let mut task_scope = TaskScope::new();
+    task_scope
+        .add_variable(&Variable::new_data("source"), Default::default())
+        .unwrap();
+    task_scope
+        .add_variable(&Variable::new_data("aggregated"), Default::default())
+        .unwrap();
+
+    let tasks = vec![
+        Task::new_value(
+            Variable::new_data("source"),
+            Default::default(),
+            TaskValue::Table(source_table),
+        ),
+        Task::new_data_source(
+            Variable::new_data("aggregated"),
+            Default::default(),
+            DataSourceTask {
+                source: "source".to_string(),
+                pipeline: Some(TransformPipeline {
+                    transforms: vec![Transform {
+                        transform_kind: Some(TransformKind::Aggregate(Aggregate {
+                            groupby: vec!["a".to_string()],
+                            fields: vec!["b".to_string()],
+                            aliases: vec!["sum_b".to_string()],
+                            ops: vec![AggregateOp::Sum as i32],
+                        })),
+                    }],
+                }),
+            },
+            &tz_config,
+        ),
+    ];
+
+    TaskGraph::new(tasks, &task_scope).unwrap()
+}
+
+/// Query request for the value of the "aggregated" node
+fn value_request(graph: &TaskGraph, updates: Vec<TaskGraphValueUpdate>) -> QueryRequest {
+    QueryRequest {
+        request: Some(query_request::Request::TaskGraphValues(
+            TaskGraphValueRequest {
+                graph: Some(task_graph_value_request::Graph::TaskGraph(graph.clone())),
+                indices: vec![NodeValueIndex::new(1, None)],
+                accept_compression: Vec::new(),
+                updates,
+                chunk_size: 0,
+            },
+        )),
+    }
+}
+
+/// Extract the per-group sums (sorted by group) and the cache_hit metric of the
+/// "aggregated" node from a query result
+fn unpack_result(result: QueryResult) -> (Vec<(String, f64)>, bool) {
+    let response = match result.response.unwrap() {
+        query_result::Response::TaskGraphValues(response) => response,
+        response => panic!("Unexpected query response: {:?}", response),
+    };
+    assert!(response.node_errors.is_empty());
+    let cache_hit = response.metrics[0].cache_hit;
+
+    let proto_value = response.response_values[0].value.as_ref().unwrap();
+    let table = TaskValue::try_from(proto_value).unwrap().into_table().unwrap();
+    let mut sums: Vec<(String, f64)> = table
+        .try_to_json()
+        .unwrap()
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| {
+            (
+                row["a"].as_str().unwrap().to_string(),
+                row["sum_b"].as_f64().unwrap(),
+            )
+        })
+        .collect();
+    sums.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0));
+
+    (sums, cache_hit)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_incremental_aggregate_on_append() {
+    let source_table = VegaFusionTable::from_json(
+        &json!([
+            {"a": "A", "b": 1.0},
+            {"a": "B", "b": 2.0},
+            {"a": "A", "b": 3.0},
+        ]),
+        1024,
+    )
+    .unwrap();
+    let appended_rows = VegaFusionTable::from_json(
+        &json!([
+            {"a": "B", "b": 4.0},
+            {"a": "C", "b": 5.0},
+        ]),
+        1024,
+    )
+    .unwrap();
+
+    // Appended table: the original batches followed by the new batch
+    let mut batches = source_table.batches.clone();
+    batches.extend(appended_rows.batches.clone());
+    let appended_table = VegaFusionTable::try_new(source_table.schema.clone(), batches).unwrap();
+
+    let graph = aggregate_task_graph(source_table);
+    let runtime = TaskGraphRuntime::new(Some(16), Some(1024_i32.pow(3) as usize));
+
+    // The initial query computes the aggregate from scratch
+    let result = runtime
+        .query_request(value_request(&graph, Vec::new()))
+        .await
+        .unwrap();
+    let (sums, cache_hit) = unpack_result(result);
+    assert!(!cache_hit);
+    assert_eq!(
+        sums,
+        vec![("A".to_string(), 4.0), ("B".to_string(), 2.0)]
+    );
+
+    // Append rows to the source value. The aggregate should be recomputed
+    // incrementally by merging the previous result with the aggregate of the
+    // appended rows, so the node's value is already cached under its new
+    // fingerprint when the query evaluates it
+    let update = TaskGraphValueUpdate {
+        node_index: 0,
+        value: Some(ProtoTaskValue::try_from(&TaskValue::Table(appended_table)).unwrap()),
+    };
+    let result = runtime
+        .query_request(value_request(&graph, vec![update]))
+        .await
+        .unwrap();
+    let (sums, cache_hit) = unpack_result(result);
+    assert!(cache_hit);
+    assert_eq!(
+        sums,
+        vec![
+            ("A".to_string(), 4.0),
+            ("B".to_string(), 6.0),
+            ("C".to_string(), 5.0)
+        ]
+    );
+}
@@ -7,7 +7,8 @@
  * this program the details of the active license.
  */
 use vegafusion_core::planning::extract::extract_server_data;
-use vegafusion_core::proto::gen::tasks::{TaskGraph, TzConfig, Variable};
+use vegafusion_core::planning::plan::{PlannerConfig, SpecPlan};
+use vegafusion_core::proto::gen::tasks::{TaskGraph, TzConfig, Variable, VariableNamespace};
 use vegafusion_core::spec::chart::ChartSpec;
 use vegafusion_rt_datafusion::task_graph::runtime::TaskGraphRuntime;
 
@@ -75,6 +76,7 @@ async fn test_extract_server_data() {
                 .get(&(Variable::new_data("data_3"), Vec::new()))
                 .unwrap(),
             Default::default(),
+            Default::default(),
         )
         .await
         .unwrap();
@@ -91,6 +93,7 @@ async fn test_extract_server_data() {
                 ))
                 .unwrap(),
             Default::default(),
+            Default::default(),
         )
         .await
         .unwrap();
@@ -140,6 +143,93 @@ async fn try_extract_split_server_data() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_extract_nested_group_data() {
+    let mut spec = nested_group_spec();
+
+    // Get full spec's scope
+    let mut task_scope = spec.to_task_scope().unwrap();
+
+    let mut server_spec =
+        extract_server_data(&mut spec, &mut task_scope, &Default::default()).unwrap();
+    let comm_plan = stitch_specs(&task_scope, &mut server_spec, &mut spec).unwrap();
+
+    // The dataset defined inside the nested group should be extracted into the
+    // matching group scope of the server spec
+    let server_group = server_spec.get_nested_group(&[0]).unwrap();
+    assert!(server_group.data.iter().any(|d| d.name == "group_data"));
+
+    // The client copy should be emptied out at the same scope
+    let client_data = spec.get_nested_data(&[0], "group_data").unwrap();
+    assert!(client_data.url.is_none());
+    assert!(client_data.transform.is_empty());
+
+    // ... and receive its value from the server
+    assert!(comm_plan
+        .server_to_client
+        .contains(&(Variable::new_data("group_data"), vec![0])));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_static_scale_evaluated_on_server() {
+    let spec = static_scale_spec();
+    let config = PlannerConfig {
+        extract_inline_data: true,
+        ..Default::default()
+    };
+    let plan = SpecPlan::try_new(&spec, &config).unwrap();
+
+    // The dataset and the static scale its formula transform references should both
+    // be planned for server-side evaluation
+    assert!(plan.server_spec.data.iter().any(|d| d.name == "source_0"));
+    assert!(plan.server_spec.scales.iter().any(|s| s.name == "xscale"));
+
+    // Scale state is static and the client keeps its own copy, so no scale values
+    // are communicated
+    assert!(plan
+        .comm_plan
+        .server_to_client
+        .iter()
+        .chain(plan.comm_plan.client_to_server.iter())
+        .all(|var| var.0.namespace() != VariableNamespace::Scale));
+
+    // Evaluate the dataset and check that scale() was applied
+    let tz_config = TzConfig {
+        local_tz: "America/New_York".to_string(),
+        default_input_tz: None,
+    };
+    let task_scope = plan.server_spec.to_task_scope().unwrap();
+    let tasks = plan
+        .server_spec
+        .to_tasks(&tz_config, &Default::default())
+        .unwrap();
+    let graph = Arc::new(TaskGraph::new(tasks, &task_scope).unwrap());
+    let mapping = graph.build_mapping();
+
+    let graph_runtime = TaskGraphRuntime::new(Some(20), Some(1024_i32.pow(3) as usize));
+    let value = graph_runtime
+        .get_node_value(
+            graph.clone(),
+            mapping
+                .get(&(Variable::new_data("source_0"), Vec::new()))
+                .unwrap(),
+            Default::default(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+
+    let table = value.into_table().unwrap();
+    let xpos: Vec<f64> = table
+        .to_json()
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| row["xpos"].as_f64().unwrap())
+        .collect();
+    assert_eq!(xpos, vec![0.0, 50.0, 100.0]);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn try_split_domain() {
     // let mut spec = sorted_bar_spec();
@@ -148,6 +238,48 @@ async fn try_split_domain() {
     println!("{}", serde_json::to_string_pretty(&spec).unwrap())
 }
 
+// Inline dataset with a formula transform that applies a fully static linear scale
+#[allow(dead_code)]
+fn static_scale_spec() -> ChartSpec {
+    serde_json::from_str(
+        r##"
+{
+  "$schema": "https://vega.github.io/schema/vega/v5.json",
+  "data": [
+    {
+      "name": "source_0",
+      "values": [{"x": 0}, {"x": 5}, {"x": 10}],
+      "transform": [
+        {"type": "formula", "expr": "scale(\"xscale\", datum.x)", "as": "xpos"}
+      ]
+    }
+  ],
+  "scales": [
+    {
+      "name": "xscale",
+      "type": "linear",
+      "domain": [0, 10],
+      "range": [0, 100]
+    }
+  ],
+  "marks": [
+    {
+      "type": "rect",
+      "from": {"data": "source_0"},
+      "encode": {
+        "update": {
+          "x": {"field": "xpos"},
+          "y": {"value": 0}
+        }
+      }
+    }
+  ]
+}
+"##,
+    )
+    .unwrap()
+}
+
 #[allow(dead_code)]
 fn spec1() -> ChartSpec {
     serde_json::from_str(r##"
@@ -2750,3 +2882,49 @@ fn cars_scatter_spec() -> ChartSpec {
 }
     "##).unwrap()
 }
+
+#[allow(dead_code)]
+fn nested_group_spec() -> ChartSpec {
+    serde_json::from_str(
+        r##"
+{
+  "$schema": "https://vega.github.io/schema/vega/v5.json",
+  "background": "white",
+  "padding": 5,
+  "marks": [
+    {
+      "type": "group",
+      "data": [
+        {
+          "name": "group_data",
+          "url": "https://raw.githubusercontent.com/vega/vega-datasets/master/data/movies.json",
+          "transform": [
+            {
+              "type": "aggregate",
+              "groupby": ["MPAA Rating"],
+              "ops": ["count"],
+              "fields": [null],
+              "as": ["__count"]
+            }
+          ]
+        }
+      ],
+      "marks": [
+        {
+          "type": "rect",
+          "from": {"data": "group_data"},
+          "encode": {
+            "update": {
+              "x": {"field": "MPAA Rating"},
+              "y": {"field": "__count"}
+            }
+          }
+        }
+      ]
+    }
+  ]
+}
+"##,
+    )
+    .unwrap()
+}
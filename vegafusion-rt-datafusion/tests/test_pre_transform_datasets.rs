@@ -0,0 +1,165 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+#[cfg(test)]
+mod tests {
+    use crate::crate_dir;
+    use std::fs;
+    use vegafusion_core::data::dataset::VegaFusionDataset;
+    use vegafusion_core::data::table::VegaFusionTable;
+    use vegafusion_core::error::VegaFusionError;
+    use vegafusion_core::proto::gen::tasks::Variable;
+    use vegafusion_rt_datafusion::data::table::VegaFusionTableUtils;
+    use vegafusion_rt_datafusion::task_graph::runtime::TaskGraphRuntime;
+
+    #[tokio::test]
+    async fn test_pre_transform_datasets() {
+        // Load spec
+        let spec_path = format!("{}/tests/specs/vegalite/histogram.vg.json", crate_dir());
+        let spec_str = fs::read_to_string(spec_path).unwrap();
+
+        // Initialize task graph runtime
+        let runtime = TaskGraphRuntime::new(Some(16), Some(1024_i32.pow(3) as usize));
+
+        let (datasets, warnings) = runtime
+            .pre_transform_datasets(
+                &spec_str,
+                &[(Variable::new_data("source_0"), vec![])],
+                "UTC",
+                &None,
+                Default::default(),
+            )
+            .await
+            .unwrap();
+
+        // Check there are no warnings
+        assert!(warnings.is_empty());
+
+        // Check single returned dataset
+        assert_eq!(datasets.len(), 1);
+
+        let expected = "\
++----------------------------+--------------------------------+---------+
+| bin_maxbins_10_IMDB Rating | bin_maxbins_10_IMDB Rating_end | __count |
++----------------------------+--------------------------------+---------+
+| 6                          | 7                              | 985     |
+| 3                          | 4                              | 100     |
+| 7                          | 8                              | 741     |
+| 5                          | 6                              | 633     |
+| 8                          | 9                              | 204     |
+| 2                          | 3                              | 43      |
+| 4                          | 5                              | 273     |
+| 9                          | 10                             | 4       |
+| 1                          | 2                              | 5       |
++----------------------------+--------------------------------+---------+";
+        assert_eq!(datasets[0].pretty_format(None).unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_pre_transform_datasets_inline() {
+        // Load spec that references the movies dataset with a
+        // vegafusion+dataset://movies url
+        let spec_path = format!(
+            "{}/tests/specs/pre_transform/imdb_histogram_inline.vg.json",
+            crate_dir()
+        );
+        let spec_str = fs::read_to_string(spec_path).unwrap();
+
+        // Load movies dataset as an in-memory table
+        let data_path = format!(
+            "{}/tests/util/vegajs_runtime/data/movies.json",
+            crate_dir()
+        );
+        let data_str = fs::read_to_string(data_path).unwrap();
+        let json_value: serde_json::Value = serde_json::from_str(&data_str).unwrap();
+        let movies_table = VegaFusionTable::from_json(&json_value, 1024).unwrap();
+
+        // Register table under the movies name
+        let inline_datasets = VegaFusionDataset::from_tables(
+            vec![("movies".to_string(), movies_table)]
+                .into_iter()
+                .collect(),
+        )
+        .unwrap();
+
+        // Initialize task graph runtime
+        let runtime = TaskGraphRuntime::new(Some(16), Some(1024_i32.pow(3) as usize));
+
+        let (datasets, warnings) = runtime
+            .pre_transform_datasets(
+                &spec_str,
+                &[(Variable::new_data("source_0"), vec![])],
+                "UTC",
+                &None,
+                inline_datasets,
+            )
+            .await
+            .unwrap();
+
+        // Check there are no warnings
+        assert!(warnings.is_empty());
+
+        // Check binned dataset was computed from the inline table
+        assert_eq!(datasets.len(), 1);
+        assert_eq!(datasets[0].num_rows(), 9);
+    }
+
+    #[tokio::test]
+    async fn test_pre_transform_datasets_validate() {
+        // Load spec
+        let spec_path = format!("{}/tests/specs/vegalite/histogram.vg.json", crate_dir());
+        let spec_str = fs::read_to_string(spec_path).unwrap();
+
+        // Initialize task graph runtime
+        let runtime = TaskGraphRuntime::new(Some(16), Some(1024_i32.pow(3) as usize));
+
+        // Check non-existent dataset name
+        let result = runtime
+            .pre_transform_datasets(
+                &spec_str,
+                &[(Variable::new_data("bogus_0"), vec![])],
+                "UTC",
+                &None,
+                Default::default(),
+            )
+            .await;
+
+        if let Err(VegaFusionError::PreTransformError(err, _)) = result {
+            assert_eq!(err, "No dataset named bogus_0 with scope []")
+        } else {
+            panic!("Expected PreTransformError");
+        }
+
+        // Check signal variables are rejected
+        let result = runtime
+            .pre_transform_datasets(
+                &spec_str,
+                &[(Variable::new_signal("width"), vec![])],
+                "UTC",
+                &None,
+                Default::default(),
+            )
+            .await;
+
+        if let Err(VegaFusionError::PreTransformError(err, _)) = result {
+            assert_eq!(
+                err,
+                "pre_transform_datasets does not support variable \
+                 Variable { name: \"width\", namespace: Signal }"
+            )
+        } else {
+            panic!("Expected PreTransformError");
+        }
+    }
+}
+
+fn crate_dir() -> String {
+    std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .display()
+        .to_string()
+}
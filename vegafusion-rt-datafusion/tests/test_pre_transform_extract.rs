@@ -0,0 +1,79 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+#[cfg(test)]
+mod tests {
+    use crate::crate_dir;
+    use serde_json::Value;
+    use std::fs;
+    use vegafusion_rt_datafusion::task_graph::runtime::TaskGraphRuntime;
+
+    #[tokio::test]
+    async fn test_pre_transform_extract() {
+        // Load spec
+        let spec_path = format!("{}/tests/specs/vegalite/histogram.vg.json", crate_dir());
+        let spec_str = fs::read_to_string(spec_path).unwrap();
+
+        // Initialize task graph runtime
+        let runtime = TaskGraphRuntime::new(Some(16), Some(1024_i32.pow(3) as usize));
+
+        // Extract datasets with more than 5 rows
+        let (spec, datasets, warnings) = runtime
+            .pre_transform_extract(&spec_str, "UTC", &None, 5, &Vec::new(), Default::default())
+            .await
+            .unwrap();
+
+        // Check there are no warnings
+        assert!(warnings.is_empty());
+
+        // Check single extracted dataset
+        assert_eq!(datasets.len(), 1);
+        let (name, scope, table) = &datasets[0];
+        assert_eq!(name, "source_0");
+        assert!(scope.is_empty());
+        assert_eq!(table.num_rows(), 9);
+
+        // The extracted dataset should be left empty in the spec
+        let data = spec.get_nested_data(&[], "source_0").unwrap();
+        assert_eq!(data.values, Some(Value::Array(Vec::new())));
+    }
+
+    #[tokio::test]
+    async fn test_pre_transform_extract_inline_below_threshold() {
+        // Load spec
+        let spec_path = format!("{}/tests/specs/vegalite/histogram.vg.json", crate_dir());
+        let spec_str = fs::read_to_string(spec_path).unwrap();
+
+        // Initialize task graph runtime
+        let runtime = TaskGraphRuntime::new(Some(16), Some(1024_i32.pow(3) as usize));
+
+        // With the default threshold of 20 the 9-row binned dataset is inlined
+        let (spec, datasets, warnings) = runtime
+            .pre_transform_extract(&spec_str, "UTC", &None, 20, &Vec::new(), Default::default())
+            .await
+            .unwrap();
+
+        // Check there are no warnings and no extracted datasets
+        assert!(warnings.is_empty());
+        assert!(datasets.is_empty());
+
+        // The dataset should be inlined in the spec as JSON values
+        let data = spec.get_nested_data(&[], "source_0").unwrap();
+        if let Some(Value::Array(values)) = &data.values {
+            assert_eq!(values.len(), 9);
+        } else {
+            panic!("Expected source_0 values to be an Array");
+        }
+    }
+}
+
+fn crate_dir() -> String {
+    std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .display()
+        .to_string()
+}
@@ -0,0 +1,126 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+#[cfg(test)]
+mod tests {
+    use crate::crate_dir;
+    use std::collections::HashMap;
+    use std::fs;
+    use vegafusion_core::proto::gen::pretransform::pre_transform_spec_warning::WarningType;
+    use vegafusion_core::proto::gen::services::pre_transform_spec_result;
+    use vegafusion_core::proto::gen::tasks::Variable;
+    use vegafusion_core::spec::chart::ChartSpec;
+    use vegafusion_rt_datafusion::task_graph::runtime::TaskGraphRuntime;
+
+    #[tokio::test]
+    async fn test_pre_transform_spec_dataset_row_limit() {
+        // Load spec
+        let spec_path = format!("{}/tests/specs/vegalite/histogram.vg.json", crate_dir());
+        let spec_str = fs::read_to_string(spec_path).unwrap();
+
+        // Initialize task graph runtime
+        let runtime = TaskGraphRuntime::new(Some(16), Some(1024_i32.pow(3) as usize));
+
+        // Limit source_0 to 3 rows. The per-dataset limit should take precedence
+        // over the global row limit
+        let dataset_row_limits: HashMap<_, _> =
+            vec![((Variable::new_data("source_0"), vec![]), 3)]
+                .into_iter()
+                .collect();
+
+        let pre_tx_result = runtime
+            .pre_transform_spec(
+                &spec_str,
+                "UTC",
+                &None,
+                Some(100),
+                &dataset_row_limits,
+                &Vec::new(),
+                Default::default(),
+            )
+            .await
+            .unwrap();
+
+        match pre_tx_result.result.unwrap() {
+            pre_transform_spec_result::Result::Error(err) => {
+                panic!("pre_transform_spec error: {:?}", err);
+            }
+            pre_transform_spec_result::Result::Response(response) => {
+                // Check that source_0 was truncated to 3 rows
+                let spec: ChartSpec = serde_json::from_str(&response.spec).unwrap();
+                let data = spec.get_nested_data(&[], "source_0").unwrap();
+                let values = data.values.as_ref().unwrap().as_array().unwrap();
+                assert_eq!(values.len(), 3);
+
+                // Check row limit warning with per-dataset truncation details
+                assert_eq!(response.warnings.len(), 1);
+                let warning = &response.warnings[0];
+                if let Some(WarningType::RowLimit(row_limit_warning)) = &warning.warning_type {
+                    assert_eq!(
+                        row_limit_warning.datasets,
+                        vec![Variable::new_data("source_0")]
+                    );
+                    assert_eq!(row_limit_warning.limited.len(), 1);
+                    let limited = &row_limit_warning.limited[0];
+                    assert_eq!(limited.variable, Some(Variable::new_data("source_0")));
+                    assert!(limited.scope.is_empty());
+                    assert_eq!(limited.total_rows, 9);
+                    assert_eq!(limited.row_limit, 3);
+                } else {
+                    panic!("Expected RowLimit warning");
+                }
+            }
+        }
+    }
+    #[tokio::test]
+    async fn test_pre_transform_spec_keep_datasets() {
+        // Load spec
+        let spec_path = format!("{}/tests/specs/vegalite/histogram.vg.json", crate_dir());
+        let spec_str = fs::read_to_string(spec_path).unwrap();
+
+        // Initialize task graph runtime
+        let runtime = TaskGraphRuntime::new(Some(16), Some(1024_i32.pow(3) as usize));
+
+        // Keep source_0 in the client spec so its definition is preserved
+        let keep_variables = vec![(Variable::new_data("source_0"), vec![])];
+
+        let pre_tx_result = runtime
+            .pre_transform_spec(
+                &spec_str,
+                "UTC",
+                &None,
+                None,
+                &Default::default(),
+                &keep_variables,
+                Default::default(),
+            )
+            .await
+            .unwrap();
+
+        match pre_tx_result.result.unwrap() {
+            pre_transform_spec_result::Result::Error(err) => {
+                panic!("pre_transform_spec error: {:?}", err);
+            }
+            pre_transform_spec_result::Result::Response(response) => {
+                // Check that the source_0 definition was preserved rather than
+                // replaced with materialized values
+                let spec: ChartSpec = serde_json::from_str(&response.spec).unwrap();
+                let data = spec.get_nested_data(&[], "source_0").unwrap();
+                assert!(data.values.is_none());
+                assert!(data.url.is_some());
+                assert!(!data.transform.is_empty());
+            }
+        }
+    }
+}
+
+fn crate_dir() -> String {
+    std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .display()
+        .to_string()
+}
@@ -85,6 +85,8 @@ mod test_stringify_datetimes {
                 &local_tz,
                 &Some(default_input_tz.to_string()),
                 None,
+                &Default::default(),
+                &Vec::new(),
                 Default::default(),
             )
             .await
@@ -143,6 +145,8 @@ mod test_stringify_datetimes {
                 &local_tz,
                 &Some(default_input_tz),
                 None,
+                &Default::default(),
+                &Vec::new(),
                 Default::default(),
             )
             .await
@@ -232,6 +236,8 @@ mod test_stringify_datetimes {
                 local_tz,
                 &Some(default_input_tz.to_string()),
                 None,
+                &Default::default(),
+                &Vec::new(),
                 Default::default(),
             )
             .await
@@ -92,7 +92,12 @@ async fn try_it() {
     let graph_runtime = TaskGraphRuntime::new(Some(20), Some(1024_i32.pow(3) as usize));
     // let result = graph_runtime.get_node_value(graph, 2, None).await.unwrap();
     let result = graph_runtime
-        .get_node_value(graph, &NodeValueIndex::new(2, Some(0)), Default::default())
+        .get_node_value(
+            graph,
+            &NodeValueIndex::new(2, Some(0)),
+            Default::default(),
+            Default::default(),
+        )
         .await
         .unwrap();
 
@@ -149,7 +154,12 @@ async fn try_it_from_spec() {
 
     let graph_runtime = TaskGraphRuntime::new(Some(20), Some(1024_i32.pow(3) as usize));
     let result = graph_runtime
-        .get_node_value(graph, &NodeValueIndex::new(2, Some(0)), Default::default())
+        .get_node_value(
+            graph,
+            &NodeValueIndex::new(2, Some(0)),
+            Default::default(),
+            Default::default(),
+        )
         .await
         .unwrap();
     println!("result: {:?}", result);
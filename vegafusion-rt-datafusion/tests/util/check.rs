@@ -6,7 +6,10 @@
  * Please consult the license documentation provided alongside
  * this program the details of the active license.
  */
-use crate::util::equality::{assert_signals_almost_equal, assert_tables_equal, TablesEqualConfig};
+use crate::util::equality::{
+    assert_scalars_almost_equals, assert_signals_almost_equal, assert_tables_equal,
+    TablesEqualConfig,
+};
 use crate::util::vegajs_runtime::vegajs_runtime;
 use datafusion::scalar::ScalarValue;
 
@@ -71,6 +74,34 @@ pub fn check_scalar_evaluation(expr_str: &str, config: &CompilationConfig) {
     assert_eq!(result, expected, " left: {}\nright: {}\n", result, expected);
 }
 
+/// Like check_scalar_evaluation, but compares numeric results to within a tolerance.
+/// For functions (e.g. the statistical distributions) whose Vega implementations use
+/// different floating point approximations, so results are close but not bit-identical
+pub fn check_scalar_evaluation_approx(expr_str: &str, config: &CompilationConfig, tol: f64) {
+    let vegajs_runtime = vegajs_runtime();
+    let expected = vegajs_runtime
+        .eval_scalar_expression(expr_str, config)
+        .unwrap();
+
+    // Add local timezone info to config
+    let local_tz_str = vegajs_runtime.nodejs_runtime.local_timezone().unwrap();
+    let config = CompilationConfig {
+        tz_config: Some(RuntimeTzConfig::try_new(&local_tz_str, &None).unwrap()),
+        ..config.clone()
+    };
+
+    let parsed = parse(expr_str).unwrap();
+    let compiled = compile(&parsed, &config, None).unwrap();
+    let result = compiled.eval_to_scalar().unwrap();
+
+    // Serialize and deserialize to normalize types to those supported by JavaScript
+    // (e.g. Int to Float)
+    let result = ScalarValue::from_json(&result.to_json().unwrap()).unwrap();
+
+    println!("{:?}", result);
+    assert_scalars_almost_equals(&result, &expected, tol);
+}
+
 pub fn check_transform_evaluation(
     data: &VegaFusionTable,
     transform_specs: &[TransformSpec],
@@ -131,7 +131,7 @@ fn numeric_to_f64(s: &ScalarValue) -> f64 {
     }
 }
 
-fn assert_scalars_almost_equals(lhs: &ScalarValue, rhs: &ScalarValue, tol: f64) {
+pub fn assert_scalars_almost_equals(lhs: &ScalarValue, rhs: &ScalarValue, tol: f64) {
     match (lhs, rhs) {
         (
             ScalarValue::Struct(Some(lhs_vals), lhs_fields),
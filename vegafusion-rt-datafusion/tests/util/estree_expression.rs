@@ -107,6 +107,24 @@ pub enum BinaryOperator {
 
     #[serde(rename = "%")]
     Mod,
+
+    #[serde(rename = "&")]
+    BitwiseAnd,
+
+    #[serde(rename = "|")]
+    BitwiseOr,
+
+    #[serde(rename = "^")]
+    BitwiseXor,
+
+    #[serde(rename = "<<")]
+    LeftShift,
+
+    #[serde(rename = ">>")]
+    RightShift,
+
+    #[serde(rename = ">>>")]
+    UnsignedRightShift,
 }
 
 impl BinaryOperator {
@@ -125,6 +143,14 @@ impl BinaryOperator {
             BinaryOperator::Mult => proto_expression::BinaryOperator::Mult,
             BinaryOperator::Div => proto_expression::BinaryOperator::Div,
             BinaryOperator::Mod => proto_expression::BinaryOperator::Mod,
+            BinaryOperator::BitwiseAnd => proto_expression::BinaryOperator::BitwiseAnd,
+            BinaryOperator::BitwiseOr => proto_expression::BinaryOperator::BitwiseOr,
+            BinaryOperator::BitwiseXor => proto_expression::BinaryOperator::BitwiseXor,
+            BinaryOperator::LeftShift => proto_expression::BinaryOperator::LeftShift,
+            BinaryOperator::RightShift => proto_expression::BinaryOperator::RightShift,
+            BinaryOperator::UnsignedRightShift => {
+                proto_expression::BinaryOperator::UnsignedRightShift
+            }
         }
     }
 }
@@ -203,6 +229,9 @@ pub enum UnaryOperator {
 
     #[serde(rename = "!")]
     Not,
+
+    #[serde(rename = "~")]
+    BitwiseNot,
 }
 
 impl UnaryOperator {
@@ -211,6 +240,7 @@ impl UnaryOperator {
             UnaryOperator::Pos => proto_expression::UnaryOperator::Pos,
             UnaryOperator::Neg => proto_expression::UnaryOperator::Neg,
             UnaryOperator::Not => proto_expression::UnaryOperator::Not,
+            UnaryOperator::BitwiseNot => proto_expression::UnaryOperator::BitwiseNot,
         }
     }
 }
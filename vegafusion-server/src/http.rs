@@ -0,0 +1,223 @@
+/*
+ * VegaFusion
+ * Copyright (C) 2022 VegaFusion Technologies LLC
+ *
+ * This program is distributed under multiple licenses.
+ * Please consult the license documentation provided alongside
+ * this program the details of the active license.
+ */
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use vegafusion_core::error::{ResultWithContext, ToExternalError, VegaFusionError};
+use vegafusion_core::proto::gen::pretransform::pre_transform_datasets_warning::WarningType as DatasetsWarningType;
+use vegafusion_core::proto::gen::pretransform::pre_transform_spec_warning::WarningType;
+use vegafusion_core::proto::gen::services::pre_transform_spec_result;
+use vegafusion_core::proto::gen::tasks::Variable;
+use vegafusion_core::task_graph::graph::ScopedVariable;
+use vegafusion_rt_datafusion::task_graph::runtime::TaskGraphRuntime;
+
+/// Request body for the `POST /pre_transform/spec` endpoint
+#[derive(Deserialize)]
+struct PreTransformSpecHttpRequest {
+    spec: Value,
+    local_tz: String,
+    #[serde(default)]
+    default_input_tz: Option<String>,
+    #[serde(default)]
+    row_limit: Option<u32>,
+}
+
+/// Request body for the `POST /pre_transform/datasets` endpoint
+#[derive(Deserialize)]
+struct PreTransformDatasetsHttpRequest {
+    spec: Value,
+    datasets: Vec<HttpScopedDataset>,
+    local_tz: String,
+    #[serde(default)]
+    default_input_tz: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct HttpScopedDataset {
+    name: String,
+    #[serde(default)]
+    scope: Vec<u32>,
+}
+
+#[derive(Serialize)]
+struct HttpWarning {
+    #[serde(rename = "type")]
+    typ: String,
+    message: String,
+}
+
+/// HTTP+JSON API for the pre-transform methods, so simple integrations
+/// (serverless functions, curl, non-protobuf clients) can use pre-transform
+/// without generated gRPC stubs
+pub async fn http_server(
+    address: String,
+    runtime: TaskGraphRuntime,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let addr: SocketAddr = address.parse()?;
+    let make_svc = make_service_fn(move |_conn| {
+        let runtime = runtime.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let runtime = runtime.clone();
+                async move { Ok::<_, Infallible>(handle_request(request, runtime).await) }
+            }))
+        }
+    });
+
+    println!("Starting HTTP server on {}", addr);
+    Server::bind(&addr).serve(make_svc).await?;
+
+    Ok(())
+}
+
+async fn handle_request(request: Request<Body>, runtime: TaskGraphRuntime) -> Response<Body> {
+    let result = match (request.method(), request.uri().path()) {
+        (&Method::POST, "/pre_transform/spec") => pre_transform_spec(request, runtime).await,
+        (&Method::POST, "/pre_transform/datasets") => {
+            pre_transform_datasets(request, runtime).await
+        }
+        _ => {
+            return error_response(StatusCode::NOT_FOUND, "Resource not found");
+        }
+    };
+
+    match result {
+        Ok(response) => response,
+        Err(err) => error_response(StatusCode::BAD_REQUEST, &err.to_string()),
+    }
+}
+
+async fn pre_transform_spec(
+    request: Request<Body>,
+    runtime: TaskGraphRuntime,
+) -> Result<Response<Body>, VegaFusionError> {
+    let request: PreTransformSpecHttpRequest = parse_body(request).await?;
+    let spec_string = serde_json::to_string(&request.spec)?;
+
+    let result = runtime
+        .pre_transform_spec(
+            &spec_string,
+            &request.local_tz,
+            &request.default_input_tz,
+            request.row_limit,
+            &Default::default(),
+            &Vec::new(),
+            Default::default(),
+        )
+        .await?;
+
+    match result.result.unwrap() {
+        pre_transform_spec_result::Result::Error(err) => Err(VegaFusionError::pre_transform(
+            format!("{:?}", err),
+        )),
+        pre_transform_spec_result::Result::Response(response) => {
+            let warnings: Vec<_> = response.warnings.iter().map(|warning| {
+                match warning.warning_type.as_ref().unwrap() {
+                    WarningType::RowLimit(_) => HttpWarning {
+                        typ: "RowLimitExceeded".to_string(),
+                        message: "Some datasets in resulting Vega specification have been truncated to the provided row limit".to_string(),
+                    },
+                    WarningType::BrokenInteractivity(_) => HttpWarning {
+                        typ: "BrokenInteractivity".to_string(),
+                        message: "Some interactive features may have been broken in the resulting Vega specification".to_string(),
+                    },
+                    WarningType::Unsupported(_) => HttpWarning {
+                        typ: "Unsupported".to_string(),
+                        message: "Unable to pre-transform any datasets in the Vega specification".to_string(),
+                    },
+                    WarningType::Planner(warning) => HttpWarning {
+                        typ: "Planner".to_string(),
+                        message: warning.message.clone(),
+                    },
+                }
+            }).collect();
+
+            let spec: Value = serde_json::from_str(&response.spec)?;
+            json_response(&json!({"spec": spec, "warnings": warnings}))
+        }
+    }
+}
+
+async fn pre_transform_datasets(
+    request: Request<Body>,
+    runtime: TaskGraphRuntime,
+) -> Result<Response<Body>, VegaFusionError> {
+    let request: PreTransformDatasetsHttpRequest = parse_body(request).await?;
+    let spec_string = serde_json::to_string(&request.spec)?;
+
+    // Build variables
+    let variables: Vec<ScopedVariable> = request
+        .datasets
+        .iter()
+        .map(|dataset| (Variable::new_data(&dataset.name), dataset.scope.clone()))
+        .collect();
+
+    let (values, warnings) = runtime
+        .pre_transform_datasets(
+            &spec_string,
+            &variables,
+            &request.local_tz,
+            &request.default_input_tz,
+            Default::default(),
+        )
+        .await?;
+
+    let warnings: Vec<_> = warnings
+        .iter()
+        .map(|warning| match warning.warning_type.as_ref().unwrap() {
+            DatasetsWarningType::Planner(warning) => HttpWarning {
+                typ: "Planner".to_string(),
+                message: warning.message.clone(),
+            },
+        })
+        .collect();
+
+    let datasets: Vec<_> = request
+        .datasets
+        .iter()
+        .zip(&values)
+        .map(|(dataset, table)| {
+            json!({
+                "name": dataset.name,
+                "scope": dataset.scope,
+                "values": table.to_json(),
+            })
+        })
+        .collect();
+
+    json_response(&json!({"datasets": datasets, "warnings": warnings}))
+}
+
+async fn parse_body<T: serde::de::DeserializeOwned>(
+    request: Request<Body>,
+) -> Result<T, VegaFusionError> {
+    let body = hyper::body::to_bytes(request.into_body())
+        .await
+        .external("Failed to read request body")?;
+    serde_json::from_slice(&body).with_context(|| "Failed to parse request body as JSON".to_string())
+}
+
+fn json_response(value: &Value) -> Result<Response<Body>, VegaFusionError> {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(hyper::header::CONTENT_TYPE, "application/json")
+        .body(Body::from(value.to_string()))
+        .external("Failed to build HTTP response")
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(hyper::header::CONTENT_TYPE, "application/json")
+        .body(Body::from(json!({ "error": message }).to_string()))
+        .unwrap()
+}
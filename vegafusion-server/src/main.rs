@@ -7,20 +7,32 @@
  * this program the details of the active license.
  */
 
-use tonic::{transport::Server, Request, Response, Status};
-use vegafusion_core::error::{ResultWithContext, VegaFusionError};
+use futures_util::{SinkExt, StreamExt};
+use prost::Message as ProstMessage;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_tungstenite::tungstenite::Message;
+use tonic::transport::{Certificate, Identity, Server, ServerTlsConfig};
+use tonic::{Request, Response, Status};
+use vegafusion_core::error::{ResultWithContext, ToExternalError, VegaFusionError};
+use vegafusion_core::proto::gen::errors::error::Errorkind;
+use vegafusion_core::proto::gen::errors::{Error, TaskGraphValueError};
+use vegafusion_core::proto::gen::services::query_result;
 use vegafusion_core::proto::gen::services::vega_fusion_runtime_server::{
     VegaFusionRuntime as TonicVegaFusionRuntime,
     VegaFusionRuntimeServer as TonicVegaFusionRuntimeServer,
 };
 use vegafusion_core::proto::gen::services::{
+    PreTransformDatasetsResult, PreTransformExtractResult, PreTransformSpecBatchResult,
     PreTransformSpecResult, PreTransformValuesResult, QueryRequest, QueryResult,
 };
 use vegafusion_rt_datafusion::task_graph::runtime::TaskGraphRuntime;
 
+mod http;
+
 use clap::Parser;
 use regex::Regex;
 use vegafusion_core::proto::gen::pretransform::{
+    PreTransformDatasetsRequest, PreTransformExtractRequest, PreTransformSpecBatchRequest,
     PreTransformSpecRequest, PreTransformValuesRequest,
 };
 
@@ -52,6 +64,32 @@ impl TonicVegaFusionRuntime for VegaFusionRuntimeGrpc {
         }
     }
 
+    type TaskGraphQueryStreamStream = ReceiverStream<Result<QueryResult, Status>>;
+
+    async fn task_graph_query_stream(
+        &self,
+        request: Request<QueryRequest>,
+    ) -> Result<Response<Self::TaskGraphQueryStreamStream>, Status> {
+        let mut receiver = self
+            .runtime
+            .query_request_stream(request.into_inner())
+            .await
+            .map_err(|err| Status::unknown(err.to_string()))?;
+
+        // Forward each result, wrapped in Ok, to the gRPC response stream
+        let (sender, wrapped_receiver) = tokio::sync::mpsc::channel(8);
+        tokio::spawn(async move {
+            while let Some(result) = receiver.recv().await {
+                if sender.send(Ok(result)).await.is_err() {
+                    // Client disconnected
+                    break;
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(wrapped_receiver)))
+    }
+
     async fn pre_transform_spec(
         &self,
         request: Request<PreTransformSpecRequest>,
@@ -66,6 +104,20 @@ impl TonicVegaFusionRuntime for VegaFusionRuntimeGrpc {
         }
     }
 
+    async fn pre_transform_spec_batch(
+        &self,
+        request: Request<PreTransformSpecBatchRequest>,
+    ) -> Result<Response<PreTransformSpecBatchResult>, Status> {
+        let result = self
+            .runtime
+            .pre_transform_spec_batch_request(request.into_inner())
+            .await;
+        match result {
+            Ok(result) => Ok(Response::new(result)),
+            Err(err) => Err(Status::unknown(err.to_string())),
+        }
+    }
+
     async fn pre_transform_values(
         &self,
         request: Request<PreTransformValuesRequest>,
@@ -79,6 +131,34 @@ impl TonicVegaFusionRuntime for VegaFusionRuntimeGrpc {
             Err(err) => Err(Status::unknown(err.to_string())),
         }
     }
+
+    async fn pre_transform_datasets(
+        &self,
+        request: Request<PreTransformDatasetsRequest>,
+    ) -> Result<Response<PreTransformDatasetsResult>, Status> {
+        let result = self
+            .runtime
+            .pre_transform_datasets_request(request.into_inner())
+            .await;
+        match result {
+            Ok(result) => Ok(Response::new(result)),
+            Err(err) => Err(Status::unknown(err.to_string())),
+        }
+    }
+
+    async fn pre_transform_extract(
+        &self,
+        request: Request<PreTransformExtractRequest>,
+    ) -> Result<Response<PreTransformExtractResult>, Status> {
+        let result = self
+            .runtime
+            .pre_transform_extract_request(request.into_inner())
+            .await;
+        match result {
+            Ok(result) => Ok(Response::new(result)),
+            Err(err) => Err(Status::unknown(err.to_string())),
+        }
+    }
 }
 
 /// VegaFusion Server
@@ -104,6 +184,28 @@ struct Args {
     /// Include compatibility with gRPC-Web
     #[clap(long, takes_value = false)]
     pub web: bool,
+
+    /// Port for WebSocket server. The WebSocket server is not started if omitted
+    #[clap(long)]
+    pub websocket_port: Option<u32>,
+
+    /// Port for HTTP+JSON pre-transform server. The HTTP server is not started if omitted
+    #[clap(long)]
+    pub http_port: Option<u32>,
+
+    /// Path to PEM-encoded TLS certificate for the gRPC server. TLS is enabled when
+    /// both --tls-cert and --tls-key are provided
+    #[clap(long)]
+    pub tls_cert: Option<String>,
+
+    /// Path to PEM-encoded private key for the gRPC server
+    #[clap(long)]
+    pub tls_key: Option<String>,
+
+    /// Path to PEM-encoded CA certificate used to verify client certificates (mTLS).
+    /// Clients that don't present a certificate signed by this CA are rejected
+    #[clap(long)]
+    pub tls_client_ca: Option<String>,
 }
 
 #[tokio::main]
@@ -128,13 +230,71 @@ async fn main() -> Result<(), VegaFusionError> {
 
     let tg_runtime = TaskGraphRuntime::new(Some(args.capacity), memory_limit);
 
-    grpc_server(grpc_address, tg_runtime.clone(), args.web)
+    // Load TLS configuration, if requested
+    let tls_config = load_tls_config(&args)?;
+
+    // Start HTTP+JSON server, if requested
+    if let Some(http_port) = args.http_port {
+        let http_address = format!("{}:{}", args.host, http_port);
+        let http_runtime = tg_runtime.clone();
+        tokio::spawn(async move {
+            http::http_server(http_address, http_runtime)
+                .await
+                .expect("Failed to start HTTP server");
+        });
+    }
+
+    // Start WebSocket server, if requested
+    if let Some(websocket_port) = args.websocket_port {
+        let websocket_address = format!("{}:{}", args.host, websocket_port);
+        let websocket_runtime = tg_runtime.clone();
+        tokio::spawn(async move {
+            websocket_server(websocket_address, websocket_runtime)
+                .await
+                .expect("Failed to start WebSocket server");
+        });
+    }
+
+    grpc_server(grpc_address, tg_runtime.clone(), args.web, tls_config)
         .await
         .expect("Failed to start grpc service");
 
     Ok(())
 }
 
+fn load_tls_config(args: &Args) -> Result<Option<ServerTlsConfig>, VegaFusionError> {
+    let (cert_path, key_path) = match (&args.tls_cert, &args.tls_key) {
+        (Some(cert_path), Some(key_path)) => (cert_path, key_path),
+        (None, None) => {
+            if args.tls_client_ca.is_some() {
+                return Err(VegaFusionError::specification(
+                    "--tls-client-ca requires --tls-cert and --tls-key",
+                ));
+            }
+            return Ok(None);
+        }
+        _ => {
+            return Err(VegaFusionError::specification(
+                "--tls-cert and --tls-key must be provided together",
+            ))
+        }
+    };
+
+    let cert = std::fs::read(cert_path)
+        .with_context(|| format!("Failed to read TLS certificate from {}", cert_path))?;
+    let key = std::fs::read(key_path)
+        .with_context(|| format!("Failed to read TLS private key from {}", key_path))?;
+    let mut tls_config = ServerTlsConfig::new().identity(Identity::from_pem(cert, key));
+
+    if let Some(ca_path) = &args.tls_client_ca {
+        let ca_cert = std::fs::read(ca_path)
+            .with_context(|| format!("Failed to read TLS client CA certificate from {}", ca_path))?;
+        tls_config = tls_config.client_ca_root(Certificate::from_pem(ca_cert));
+    }
+
+    Ok(Some(tls_config))
+}
+
 fn parse_memory_string(memory_limit: &str) -> Result<usize, VegaFusionError> {
     let pattern = Regex::new(r"(^\d+(\.\d+)?)(g|gb|gib|m|mb|mib|k|kb|kib|b)?$").unwrap();
     match pattern.captures(&memory_limit.to_lowercase()) {
@@ -165,6 +325,7 @@ async fn grpc_server(
     address: String,
     runtime: TaskGraphRuntime,
     web: bool,
+    tls_config: Option<ServerTlsConfig>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let addr = address
         .parse()
@@ -172,22 +333,108 @@ async fn grpc_server(
         .with_context(|| format!("Failed to parse address: {}", address))?;
     let server = TonicVegaFusionRuntimeServer::new(VegaFusionRuntimeGrpc::new(runtime));
 
+    let mut builder = Server::builder();
+    let scheme = if let Some(tls_config) = tls_config {
+        builder = builder.tls_config(tls_config)?;
+        "gRPC+TLS"
+    } else {
+        "gRPC"
+    };
+
     if web {
-        println!("Starting gRPC + gRPC-Web server on {}", address);
+        println!("Starting {} + gRPC-Web server on {}", scheme, address);
         let server = tonic_web::config().enable(server);
-        Server::builder()
+        builder
             .accept_http1(true)
             .add_service(server)
             .serve(addr)
             .await?;
     } else {
-        println!("Starting gRPC server on {}", address);
-        Server::builder().add_service(server).serve(addr).await?;
+        println!("Starting {} server on {}", scheme, address);
+        builder.add_service(server).serve(addr).await?;
     }
 
     Ok(())
 }
 
+/// WebSocket server that carries the protobuf QueryRequest / QueryResult
+/// messages of the gRPC TaskGraphQuery method as binary WebSocket messages.
+/// This makes the runtime available to browsers embedding vegafusion-wasm,
+/// which often can't speak raw gRPC
+async fn websocket_server(
+    address: String,
+    runtime: TaskGraphRuntime,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let listener = tokio::net::TcpListener::bind(&address).await?;
+    println!("Starting WebSocket server on {}", address);
+
+    while let Ok((stream, _)) = listener.accept().await {
+        let runtime = runtime.clone();
+        tokio::spawn(async move {
+            if let Err(err) = handle_websocket_connection(stream, runtime).await {
+                println!("WebSocket connection error: {}", err);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+async fn handle_websocket_connection(
+    stream: tokio::net::TcpStream,
+    runtime: TaskGraphRuntime,
+) -> Result<(), VegaFusionError> {
+    let ws_stream = tokio_tungstenite::accept_async(stream)
+        .await
+        .external("WebSocket handshake failed")?;
+    let (mut write, mut read) = ws_stream.split();
+
+    while let Some(msg) = read.next().await {
+        let msg = msg.external("Failed to read WebSocket message")?;
+        match msg {
+            Message::Binary(request_bytes) => {
+                let response = match QueryRequest::decode(request_bytes.as_slice()) {
+                    Ok(request) => match runtime.query_request(request).await {
+                        Ok(response) => response,
+                        // Send evaluation errors as error query results rather than
+                        // closing the connection
+                        Err(err) => error_query_result(err.to_string()),
+                    },
+                    Err(err) => error_query_result(format!(
+                        "Failed to decode QueryRequest message: {}",
+                        err
+                    )),
+                };
+
+                let mut response_bytes: Vec<u8> = Vec::with_capacity(response.encoded_len());
+                response
+                    .encode(&mut response_bytes)
+                    .external("Failed to encode QueryResult message")?;
+
+                write
+                    .send(Message::Binary(response_bytes))
+                    .await
+                    .external("Failed to send WebSocket message")?;
+            }
+            Message::Close(_) => break,
+            _ => {
+                // Ignore other message types (text, ping / pong frames are handled
+                // by tungstenite)
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn error_query_result(msg: String) -> QueryResult {
+    QueryResult {
+        response: Some(query_result::Response::Error(Error {
+            errorkind: Some(Errorkind::Error(TaskGraphValueError { msg })),
+        })),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::parse_memory_string;
@@ -30,7 +30,8 @@ use vegafusion_core::proto::gen::services::query_result::Response;
 use vegafusion_core::proto::gen::services::vega_fusion_runtime_client::VegaFusionRuntimeClient;
 use vegafusion_core::proto::gen::services::{query_request, QueryRequest};
 use vegafusion_core::proto::gen::tasks::{
-    NodeValueIndex, TaskGraph, TaskGraphValueRequest, TzConfig, VariableNamespace,
+    task_graph_value_request, NodeValueIndex, TaskGraph, TaskGraphValueRequest, TzConfig,
+    VariableNamespace,
 };
 use vegafusion_core::spec::chart::ChartSpec; // Add methods on commands
 
@@ -82,8 +83,11 @@ async fn try_it_from_spec() {
     let request = QueryRequest {
         request: Some(query_request::Request::TaskGraphValues(
             TaskGraphValueRequest {
-                task_graph: Some(graph),
+                graph: Some(task_graph_value_request::Graph::TaskGraph(graph)),
                 indices: vec![NodeValueIndex::new(2, Some(0))],
+                accept_compression: Vec::new(),
+                updates: Vec::new(),
+                chunk_size: 0,
             },
         )),
     };
@@ -12,7 +12,8 @@ use prost::Message;
 
 use vegafusion_core::data::scalar::{ScalarValue, ScalarValueHelpers};
 use vegafusion_core::proto::gen::tasks::{
-    NodeValueIndex, TaskGraph, TaskGraphValueRequest, TzConfig, VariableNamespace,
+    task_graph_value_request, DataCompression, NodeValueIndex, TaskGraph, TaskGraphValueRequest,
+    TaskGraphValueUpdate, TaskValue as ProtoTaskValue, TzConfig, VariableNamespace,
 };
 use vegafusion_core::task_graph::task_value::TaskValue;
 use wasm_bindgen::prelude::*;
@@ -67,8 +68,10 @@ pub struct MsgReceiver {
     comm_plan: CommPlan,
     send_msg_fn: Arc<js_sys::Function>,
     task_graph: Arc<Mutex<TaskGraph>>,
+    graph_id: Arc<Mutex<u64>>,
     task_graph_mapping: Arc<HashMap<ScopedVariable, NodeValueIndex>>,
     server_to_client_value_indices: Arc<HashSet<NodeValueIndex>>,
+    sent_value_fingerprints: Arc<Mutex<HashMap<NodeValueIndex, u64>>>,
     view: Arc<View>,
     verbose: bool,
     debounce_wait: f64,
@@ -92,6 +95,7 @@ impl MsgReceiver {
         set_panic_hook();
 
         let task_graph_mapping = task_graph.build_mapping();
+        let graph_id = task_graph.registration_id();
 
         let server_to_client_value_indices: Arc<HashSet<_>> = Arc::new(
             comm_plan
@@ -121,9 +125,11 @@ impl MsgReceiver {
             server_spec: Arc::new(server_spec),
             comm_plan,
             task_graph: Arc::new(Mutex::new(task_graph)),
+            graph_id: Arc::new(Mutex::new(graph_id)),
             task_graph_mapping: Arc::new(task_graph_mapping),
             send_msg_fn: Arc::new(send_msg_fn),
             server_to_client_value_indices,
+            sent_value_fingerprints: Arc::new(Mutex::new(Default::default())),
             view: Arc::new(view),
             verbose,
             debounce_wait,
@@ -142,6 +148,20 @@ impl MsgReceiver {
         if let Some(response) = response.response {
             match response {
                 query_result::Response::TaskGraphValues(task_graph_vals) => {
+                    // Nodes that failed to evaluate are reported individually; values
+                    // for the nodes that succeeded are still applied below
+                    for node_error in &task_graph_vals.node_errors {
+                        let name = node_error
+                            .variable
+                            .as_ref()
+                            .map(|var| var.name.clone())
+                            .unwrap_or_default();
+                        log(&format!(
+                            "VegaFusion(wasm): Failed to evaluate {}: {}",
+                            name, node_error.msg
+                        ));
+                    }
+
                     let view = self.view();
                     for (var, scope, value) in task_graph_vals
                         .deserialize()
@@ -181,7 +201,15 @@ impl MsgReceiver {
                     view.run();
                 }
                 query_result::Response::Error(error) => {
-                    log(&error.msg());
+                    let msg = error.msg();
+                    log(&msg);
+
+                    // The server keeps a bounded number of registered task graphs, so
+                    // ours may have been evicted at any point. Recover by re-sending
+                    // the full task graph and re-requesting the server provided values
+                    if msg.contains("No task graph registered") {
+                        self.resend_task_graph();
+                    }
                 }
             }
         }
@@ -221,6 +249,7 @@ impl MsgReceiver {
             let server_to_client = self.server_to_client_value_indices.clone();
 
             let task_graph = self.task_graph.clone();
+            let graph_id = self.graph_id.clone();
             let send_msg_fn = self.send_msg_fn.clone();
             let verbose = self.verbose;
 
@@ -238,12 +267,15 @@ impl MsgReceiver {
                             log(&serde_json::to_string_pretty(&val).unwrap());
                         }
 
+                        let value = TaskValue::Scalar(ScalarValue::from_json(&val).unwrap());
+                        let update = TaskGraphValueUpdate {
+                            node_index: node_value_index.node_index,
+                            value: Some(ProtoTaskValue::try_from(&value).unwrap()),
+                        };
+
                         let mut task_graph = task_graph.lock().unwrap();
                         let updated_nodes = &task_graph
-                            .update_value(
-                                node_value_index.node_index as usize,
-                                TaskValue::Scalar(ScalarValue::from_json(&val).unwrap()),
-                            )
+                            .update_value(node_value_index.node_index as usize, value)
                             .unwrap();
 
                         // Filter to update nodes in the comm plan
@@ -253,11 +285,41 @@ impl MsgReceiver {
                             .filter(|node| server_to_client.contains(node))
                             .collect();
 
+                        // Skip nodes whose state fingerprint matches the value most
+                        // recently requested, so overlapping listeners firing for a
+                        // single interaction don't re-request unaffected datasets
+                        let mut sent_fingerprints =
+                            this.sent_value_fingerprints.lock().unwrap();
+                        let updated_nodes: Vec<_> = updated_nodes
+                            .into_iter()
+                            .filter(|node| {
+                                let fingerprint = task_graph.nodes[node.node_index as usize]
+                                    .state_fingerprint;
+                                if sent_fingerprints.get(node) == Some(&fingerprint) {
+                                    false
+                                } else {
+                                    sent_fingerprints.insert(node.clone(), fingerprint);
+                                    true
+                                }
+                            })
+                            .collect();
+
+                        // Reference the registered task graph by id and send only the
+                        // update, rather than resending the full graph
                         let request_msg = QueryRequest {
                             request: Some(query_request::Request::TaskGraphValues(
                                 TaskGraphValueRequest {
-                                    task_graph: Some(task_graph.clone()),
+                                    graph: Some(
+                                        task_graph_value_request::Graph::RegisteredGraphId(
+                                            *graph_id.lock().unwrap(),
+                                        ),
+                                    ),
                                     indices: updated_nodes,
+                                    accept_compression: vec![
+                                        DataCompression::CompressionLz4 as i32,
+                                    ],
+                                    updates: vec![update],
+                                    chunk_size: 0,
                                 },
                             )),
                         };
@@ -282,13 +344,17 @@ impl MsgReceiver {
                             log(&serde_json::to_string_pretty(&val).unwrap());
                         }
 
+                        let value =
+                            TaskValue::Table(VegaFusionTable::from_json(&val, 1024).unwrap());
+                        let update = TaskGraphValueUpdate {
+                            node_index: node_value_index.node_index,
+                            value: Some(ProtoTaskValue::try_from(&value).unwrap()),
+                        };
+
                         let mut task_graph = task_graph.lock().expect("lock task graph");
 
                         let updated_nodes = &task_graph
-                            .update_value(
-                                node_value_index.node_index as usize,
-                                TaskValue::Table(VegaFusionTable::from_json(&val, 1024).unwrap()),
-                            )
+                            .update_value(node_value_index.node_index as usize, value)
                             .unwrap();
 
                         // Filter to update nodes in the comm plan
@@ -298,18 +364,46 @@ impl MsgReceiver {
                             .filter(|node| server_to_client.contains(node))
                             .collect();
 
-                        if !updated_nodes.is_empty() {
-                            let request_msg = QueryRequest {
-                                request: Some(query_request::Request::TaskGraphValues(
-                                    TaskGraphValueRequest {
-                                        task_graph: Some(task_graph.clone()),
-                                        indices: updated_nodes,
-                                    },
-                                )),
-                            };
-
-                            this.send_request(send_msg_fn.as_ref(), request_msg);
-                        }
+                        // Skip nodes whose state fingerprint matches the value most
+                        // recently requested, so overlapping listeners firing for a
+                        // single interaction don't re-request unaffected datasets
+                        let mut sent_fingerprints =
+                            this.sent_value_fingerprints.lock().unwrap();
+                        let updated_nodes: Vec<_> = updated_nodes
+                            .into_iter()
+                            .filter(|node| {
+                                let fingerprint = task_graph.nodes[node.node_index as usize]
+                                    .state_fingerprint;
+                                if sent_fingerprints.get(node) == Some(&fingerprint) {
+                                    false
+                                } else {
+                                    sent_fingerprints.insert(node.clone(), fingerprint);
+                                    true
+                                }
+                            })
+                            .collect();
+
+                        // Send the update even when no server values are needed, so the
+                        // server's registered copy of the graph stays in sync
+                        let request_msg = QueryRequest {
+                            request: Some(query_request::Request::TaskGraphValues(
+                                TaskGraphValueRequest {
+                                    graph: Some(
+                                        task_graph_value_request::Graph::RegisteredGraphId(
+                                            *graph_id.lock().unwrap(),
+                                        ),
+                                    ),
+                                    indices: updated_nodes,
+                                    accept_compression: vec![
+                                        DataCompression::CompressionLz4 as i32,
+                                    ],
+                                    updates: vec![update],
+                                    chunk_size: 0,
+                                },
+                            )),
+                        };
+
+                        this.send_request(send_msg_fn.as_ref(), request_msg);
                     })
                         as Box<dyn FnMut(String, JsValue)>);
 
@@ -323,6 +417,42 @@ impl MsgReceiver {
         }
     }
 
+    /// Re-register the task graph after the server reported it as no longer
+    /// registered. The client's copy of the graph incorporates every update applied
+    /// since the initial registration, so re-sending it brings the server fully up
+    /// to date, after which interaction requests can reference it by id again
+    fn resend_task_graph(&self) {
+        let task_graph = self.task_graph.lock().unwrap().clone();
+
+        // The graph is registered under the hash of its contents, which changes as
+        // updates are applied, so recompute the id that later requests reference
+        *self.graph_id.lock().unwrap() = task_graph.registration_id();
+
+        // Re-request all server provided values, recording their fingerprints so
+        // interaction callbacks don't immediately re-request them
+        let indices = self.initial_node_value_indices();
+        {
+            let mut sent_fingerprints = self.sent_value_fingerprints.lock().unwrap();
+            for node in &indices {
+                let fingerprint = task_graph.nodes[node.node_index as usize].state_fingerprint;
+                sent_fingerprints.insert(node.clone(), fingerprint);
+            }
+        }
+
+        let request_msg = QueryRequest {
+            request: Some(query_request::Request::TaskGraphValues(
+                TaskGraphValueRequest {
+                    graph: Some(task_graph_value_request::Graph::TaskGraph(task_graph)),
+                    indices,
+                    accept_compression: vec![DataCompression::CompressionLz4 as i32],
+                    updates: Vec::new(),
+                    chunk_size: 0,
+                },
+            )),
+        };
+        self.send_request(self.send_msg_fn.as_ref(), request_msg);
+    }
+
     fn send_request(&self, send_msg_fn: &js_sys::Function, request_msg: QueryRequest) {
         let mut buf: Vec<u8> = Vec::new();
         buf.reserve(request_msg.encoded_len());
@@ -407,11 +537,26 @@ pub fn render_vegafusion(
     // Request initial values
     let updated_node_indices: Vec<_> = receiver.initial_node_value_indices();
 
+    // Record the fingerprints of the requested values so interaction callbacks can
+    // skip re-requesting them until they change
+    {
+        let mut sent_fingerprints = receiver.sent_value_fingerprints.lock().unwrap();
+        for node in &updated_node_indices {
+            let fingerprint = task_graph.nodes[node.node_index as usize].state_fingerprint;
+            sent_fingerprints.insert(node.clone(), fingerprint);
+        }
+    }
+
+    // The initial request carries the full task graph, which the server registers
+    // so that interaction requests can reference it by id
     let request_msg = QueryRequest {
         request: Some(query_request::Request::TaskGraphValues(
             TaskGraphValueRequest {
-                task_graph: Some(task_graph),
+                graph: Some(task_graph_value_request::Graph::TaskGraph(task_graph)),
                 indices: updated_node_indices,
+                accept_compression: vec![DataCompression::CompressionLz4 as i32],
+                updates: Vec::new(),
+                chunk_size: 0,
             },
         )),
     };